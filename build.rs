@@ -0,0 +1,37 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    write_c_header();
+}
+
+/// Writes the C declarations of the `ffi` module to `$OUT_DIR/skyscrapper.h`.
+///
+/// The source tree is left untouched: a warning is emitted when the committed
+/// `include/skyscrapper.h` differs from the generated declarations.
+#[cfg(feature = "ffi")]
+fn write_c_header() {
+    let crate_dir = std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=include/skyscrapper.h");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("failed to read `cbindgen.toml`");
+
+    let header = out_dir.join("skyscrapper.h");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate the C bindings")
+        .write_to_file(&header);
+
+    let generated = std::fs::read(&header).unwrap();
+    if std::fs::read(crate_dir.join("include/skyscrapper.h")).ok() != Some(generated) {
+        println!(
+            "cargo:warning=`include/skyscrapper.h` is out of date, regenerate it with \
+             `cbindgen --config cbindgen.toml --output include/skyscrapper.h`"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+#ifndef SKYSCRAPPER_H
+#define SKYSCRAPPER_H
+
+/* This file is generated from `src/ffi.rs` using `cbindgen.toml`. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The outcome of a call to one of the exported functions.
+ */
+typedef enum SkyscrapperStatus {
+  /**
+   * The call succeeded.
+   */
+  SKYSCRAPPER_STATUS_OK = 0,
+  /**
+   * A pointer is null, the size is `0` or larger than 64, or a clue exceeds the size of the
+   * board.
+   */
+  SKYSCRAPPER_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * The header has no solution.
+   */
+  SKYSCRAPPER_STATUS_NO_SOLUTION = 2,
+  /**
+   * The board does not solve the header.
+   */
+  SKYSCRAPPER_STATUS_WRONG_SOLUTION = 3,
+  /**
+   * The operation has been interrupted before it could complete.
+   */
+  SKYSCRAPPER_STATUS_INTERRUPTED = 4,
+} SkyscrapperStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Solves the header whose `size * 4` clues are found at `clues`, and writes the `size * size`
+ * cells of the solution to `out`.
+ *
+ * A clue of `0` is missing. Nothing is written to `out` unless `SKYSCRAPPER_STATUS_OK` is
+ * returned.
+ *
+ * # Safety
+ *
+ * `clues` must be valid for reads of `size * 4` bytes, and `out` must be valid for writes of
+ * `size * size` bytes.
+ */
+enum SkyscrapperStatus skyscrapper_solve(const uint8_t *clues, size_t size, uint8_t *out);
+
+/**
+ * Generates a random solved board of size `size`, and writes its `size * size` cells to
+ * `out_cells` and its `size * 4` clues to `out_clues`.
+ *
+ * The same `seed` always produces the same board, the one `skyscrapper-cli generate --seed`
+ * produces. Nothing is written unless `SKYSCRAPPER_STATUS_OK` is returned.
+ *
+ * # Safety
+ *
+ * `out_cells` must be valid for writes of `size * size` bytes, and `out_clues` must be valid for
+ * writes of `size * 4` bytes.
+ */
+enum SkyscrapperStatus skyscrapper_generate(size_t size,
+                                            uint64_t seed,
+                                            uint8_t *out_cells,
+                                            uint8_t *out_clues);
+
+/**
+ * Checks whether the `size * size` cells found at `cells` solve the header whose `size * 4`
+ * clues are found at `clues`.
+ *
+ * Returns `SKYSCRAPPER_STATUS_OK` when they do, and `SKYSCRAPPER_STATUS_WRONG_SOLUTION` when
+ * they don't.
+ *
+ * # Safety
+ *
+ * `clues` must be valid for reads of `size * 4` bytes, and `cells` must be valid for reads of
+ * `size * size` bytes.
+ */
+enum SkyscrapperStatus skyscrapper_check(const uint8_t *clues, size_t size, const uint8_t *cells);
+
+#if defined(SKYSCRAPPER_CAPI)
+/**
+ * Same as [`skyscrapper_solve`].
+ *
+ * # Safety
+ *
+ * See [`skyscrapper_solve`].
+ */
+enum SkyscrapperStatus sky_solve(const uint8_t *clues, size_t size, uint8_t *out);
+#endif
+
+#if defined(SKYSCRAPPER_CAPI)
+/**
+ * Same as [`skyscrapper_generate`].
+ *
+ * # Safety
+ *
+ * See [`skyscrapper_generate`].
+ */
+enum SkyscrapperStatus sky_generate(size_t size,
+                                    uint64_t seed,
+                                    uint8_t *out_cells,
+                                    uint8_t *out_clues);
+#endif
+
+#if defined(SKYSCRAPPER_CAPI)
+/**
+ * Same as [`skyscrapper_check`].
+ *
+ * # Safety
+ *
+ * See [`skyscrapper_check`].
+ */
+enum SkyscrapperStatus sky_check(const uint8_t *clues, size_t size, const uint8_t *cells);
+#endif
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SKYSCRAPPER_H */
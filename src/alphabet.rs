@@ -0,0 +1,196 @@
+//! Symbols used to write the heights of the buildings.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Display;
+use core::str::FromStr;
+
+/// Compute the floored 10-th logarithm of `size`.
+pub fn log10(mut size: u8) -> usize {
+    let mut log10 = 0;
+    while size != 0 {
+        size /= 10;
+        log10 += 1;
+    }
+    log10
+}
+
+/// The symbols used to write the heights of the buildings, from the smallest to the tallest.
+///
+/// Clues are always written as numbers, as they count buildings rather than measure them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Alphabet {
+    /// The heights are written as decimal numbers, starting at `1`.
+    #[default]
+    Digits,
+    /// The heights are written as uppercase letters, starting at `A`.
+    Letters,
+    /// The heights are written using arbitrary symbols.
+    Custom(Box<[Box<str>]>),
+}
+
+impl Alphabet {
+    /// Returns the largest size of a board that can be written using this alphabet.
+    pub fn max_size(&self) -> usize {
+        match self {
+            Self::Digits => u8::MAX as usize,
+            Self::Letters => 26,
+            Self::Custom(symbols) => symbols.len(),
+        }
+    }
+
+    /// Returns the symbol of `value`, which pads itself like a string when formatted with a width.
+    ///
+    /// The value `0` (an empty cell) is written as a `0` with [`Alphabet::Digits`], and as a `.`
+    /// otherwise.
+    pub fn symbol(&self, value: u8) -> Symbol<'_> {
+        Symbol {
+            alphabet: self,
+            value,
+        }
+    }
+
+    /// Returns the number of characters of the longest symbol of a board of size `size`.
+    pub fn width(&self, size: u8) -> usize {
+        match self {
+            Self::Digits => log10(size),
+            Self::Letters => 1,
+            Self::Custom(symbols) => symbols
+                .iter()
+                .take(size as usize)
+                .map(|symbol| symbol.chars().count())
+                .max()
+                .unwrap_or(1),
+        }
+    }
+
+    /// Returns the length, in bytes, of the symbol found at the start of `input`, or `0` if
+    /// `input` does not start with a symbol.
+    ///
+    /// With [`Alphabet::Digits`], every leading digit is part of the symbol. Otherwise, the
+    /// longest symbol that matches is used.
+    pub fn symbol_len(&self, input: &[u8]) -> usize {
+        match self {
+            Self::Digits => input.iter().take_while(|b| b.is_ascii_digit()).count(),
+            Self::Letters => matches!(input.first(), Some(b'A'..=b'Z')) as usize,
+            Self::Custom(symbols) => symbols
+                .iter()
+                .filter(|symbol| input.starts_with(symbol.as_bytes()))
+                .map(|symbol| symbol.len())
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Returns the value written by `symbol`, or `None` if it is not a symbol of this alphabet.
+    pub fn parse(&self, symbol: &[u8]) -> Option<u8> {
+        match self {
+            Self::Digits => {
+                let mut result = 0u8;
+
+                for &b in symbol {
+                    let value = b.wrapping_sub(b'0');
+                    if value > 9 {
+                        return None;
+                    }
+                    result = result.checked_mul(10)?.checked_add(value)?;
+                }
+
+                Some(result)
+            }
+            Self::Letters => match symbol {
+                [c @ b'A'..=b'Z'] => Some(c - b'A' + 1),
+                _ => None,
+            },
+            Self::Custom(symbols) => symbols
+                .iter()
+                .position(|s| s.as_bytes() == symbol)
+                .map(|i| i as u8 + 1),
+        }
+    }
+}
+
+/// A value written using an [`Alphabet`], as returned by [`Alphabet::symbol`].
+pub struct Symbol<'a> {
+    alphabet: &'a Alphabet,
+    value: u8,
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.alphabet, self.value) {
+            (Alphabet::Digits, value) => Display::fmt(&value, f),
+            (_, 0) => f.pad("."),
+            (Alphabet::Letters, value) => {
+                f.pad(char::from(b'A' + value - 1).encode_utf8(&mut [0; 4]))
+            }
+            (Alphabet::Custom(symbols), value) => f.pad(&symbols[value as usize - 1]),
+        }
+    }
+}
+
+/// An error that might occur whilst parsing an [`Alphabet`] instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseAlphabetError {
+    EmptySymbol,
+    Whitespace,
+    Duplicate,
+    TooManySymbols,
+}
+
+impl Display for ParseAlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptySymbol => f.write_str("symbols can't be empty"),
+            Self::Whitespace => f.write_str("symbols can't contain whitespace"),
+            Self::Duplicate => f.write_str("the same symbol appears twice"),
+            Self::TooManySymbols => f.write_str("an alphabet can't have more than 255 symbols"),
+        }
+    }
+}
+
+impl core::error::Error for ParseAlphabetError {}
+
+// An alphabet is either `digits`, `letters`, or a list of symbols. When the list contains a comma,
+// the symbols are separated by commas. Otherwise, every character is a symbol.
+impl FromStr for Alphabet {
+    type Err = ParseAlphabetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => return Ok(Self::Digits),
+            "letters" => return Ok(Self::Letters),
+            _ => (),
+        }
+
+        let symbols: Vec<Box<str>> = if s.contains(',') {
+            s.split(',').map(Box::from).collect()
+        } else {
+            s.chars()
+                .map(|c| c.encode_utf8(&mut [0; 4]).into())
+                .collect()
+        };
+
+        if symbols.is_empty() || symbols.iter().any(|symbol| symbol.is_empty()) {
+            return Err(ParseAlphabetError::EmptySymbol);
+        }
+
+        if symbols
+            .iter()
+            .any(|symbol| symbol.contains(char::is_whitespace))
+        {
+            return Err(ParseAlphabetError::Whitespace);
+        }
+
+        if symbols.len() > u8::MAX as usize {
+            return Err(ParseAlphabetError::TooManySymbols);
+        }
+
+        if (1..symbols.len()).any(|i| symbols[..i].contains(&symbols[i])) {
+            return Err(ParseAlphabetError::Duplicate);
+        }
+
+        Ok(Self::Custom(symbols.into_boxed_slice()))
+    }
+}
@@ -0,0 +1,662 @@
+//! Implements functionalities for the `analyze` subcommand.
+
+use std::io::{self, Write};
+
+use rand::{RngCore, SeedableRng};
+use rand_xoshiro::Xoroshiro128StarStar;
+
+use crate::board::{Board, Header};
+use crate::{generate, solve};
+
+/// The difficulty of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Difficulty {
+    /// The header alone is enough to determine the board.
+    Easy,
+    /// The solver needs at most `size * size` steps.
+    Medium,
+    /// The solver needs at most `10 * size * size` steps.
+    Hard,
+    /// The solver needs more than `10 * size * size` steps.
+    Expert,
+}
+
+impl Difficulty {
+    /// Every difficulty, from the easiest to the hardest.
+    pub const ALL: [Self; 4] = [Self::Easy, Self::Medium, Self::Hard, Self::Expert];
+
+    /// Rates a puzzle of size `size` from the number of steps the solver needed to solve it.
+    pub fn from_steps(steps: usize, size: u8) -> Self {
+        let cells = size as usize * size as usize;
+
+        if steps == 0 {
+            Self::Easy
+        } else if steps <= cells {
+            Self::Medium
+        } else if steps <= 10 * cells {
+            Self::Hard
+        } else {
+            Self::Expert
+        }
+    }
+
+    /// Returns the name of this difficulty.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+            Self::Expert => "expert",
+        }
+    }
+}
+
+/// The analysis of a single generated puzzle.
+pub struct Sample {
+    /// The seed used to generate the puzzle.
+    pub seed: u64,
+    /// The number of steps the solver needed to find a solution. This is used to measure the
+    /// difficulty of the puzzle.
+    pub steps: usize,
+    /// The difficulty of the puzzle.
+    pub difficulty: Difficulty,
+    /// Whether the header of the puzzle has exactly one solution.
+    pub unique: bool,
+}
+
+/// Generates the puzzle associated with `seed`, exactly like `generate --seed` would, and analyzes
+/// it.
+pub fn analyze_seed(seed: u64, size: u8) -> Result<Sample, solve::SolutionError> {
+    let (_, stats, unique) = sample_seed(seed, size)?;
+
+    Ok(Sample {
+        seed,
+        steps: stats.steps,
+        difficulty: Difficulty::from_steps(stats.steps, size),
+        unique,
+    })
+}
+
+/// Generates the puzzle associated with `seed`, and solves it.
+///
+/// Returns its header, the statistics of the solver, and whether the header has exactly one
+/// solution.
+fn sample_seed(seed: u64, size: u8) -> Result<(Header, solve::Stats, bool), solve::SolutionError> {
+    let mut rng = Xoroshiro128StarStar::seed_from_u64(seed);
+    let solution = generate::generate_solution(&mut rng, size)?;
+    let header = generate::solution_to_header(&solution);
+
+    let mut stats = solve::Stats::default();
+    solve::solve_with_stats(&header, Default::default(), &mut stats)?;
+    let solutions = solve::count_solutions(&header, 2, Default::default())?;
+
+    Ok((header, stats, solutions == 1))
+}
+
+/// The behavior of the generator and of the solver over many puzzles of the same size.
+pub struct Statistics {
+    /// The size of the puzzles.
+    pub size: u8,
+    /// `clues[v - 1]` is the number of clues of value `v` among the generated headers.
+    pub clues: Vec<usize>,
+    /// The number of headers with exactly one solution.
+    pub unique: usize,
+    /// The number of steps the solver needed for each puzzle.
+    pub steps: Vec<usize>,
+    /// The number of backtracks the solver needed for each puzzle.
+    pub backtracks: Vec<usize>,
+}
+
+impl Statistics {
+    /// Creates empty statistics for puzzles of the provided size.
+    pub fn new(size: u8) -> Self {
+        Self {
+            size,
+            clues: vec![0; size as usize],
+            unique: 0,
+            steps: Vec::new(),
+            backtracks: Vec::new(),
+        }
+    }
+
+    /// Generates the puzzle associated with `seed`, exactly like `generate --seed` would, and
+    /// accounts for it.
+    pub fn add_seed(&mut self, seed: u64) -> Result<(), solve::SolutionError> {
+        let (header, stats, unique) = sample_seed(seed, self.size)?;
+
+        for &clue in header.iter() {
+            self.clues[clue as usize - 1] += 1;
+        }
+        self.unique += unique as usize;
+        self.steps.push(stats.steps);
+        self.backtracks.push(stats.backtracks);
+        Ok(())
+    }
+}
+
+/// Writes the mean, the median, the 90th percentile and the maximum of `values`.
+fn write_summary(w: &mut dyn Write, name: &str, values: &[usize]) -> io::Result<()> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let mean = sorted.iter().sum::<usize>() as f64 / sorted.len() as f64;
+
+    writeln!(w, "{name}:")?;
+    writeln!(w, "  mean: {mean:.1}")?;
+    writeln!(w, "  p50:  {}", percentile(&sorted, 0.5))?;
+    writeln!(w, "  p90:  {}", percentile(&sorted, 0.9))?;
+    writeln!(w, "  max:  {}", sorted[sorted.len() - 1])
+}
+
+/// Writes the report of the `stats` subcommand.
+pub fn write_statistics(w: &mut dyn Write, stats: &Statistics) -> io::Result<()> {
+    let puzzles = stats.steps.len();
+
+    writeln!(w, "size:    {}", stats.size)?;
+    writeln!(w, "puzzles: {puzzles}")?;
+
+    if puzzles == 0 {
+        return Ok(());
+    }
+
+    let n = puzzles as f64;
+    let p = stats.unique as f64 / n;
+    writeln!(
+        w,
+        "unique:  {} ({:.1}% ± {:.1}%)",
+        stats.unique,
+        p * 100.0,
+        1.96 * (p * (1.0 - p) / n).sqrt() * 100.0,
+    )?;
+
+    let total: usize = stats.clues.iter().sum();
+    let largest = stats.clues.iter().copied().max().unwrap_or(1).max(1);
+    let mean = (1..)
+        .zip(&stats.clues)
+        .map(|(value, &count)| value * count)
+        .sum::<usize>() as f64
+        / total as f64;
+    let width = stats.size.to_string().len();
+
+    writeln!(w)?;
+    writeln!(w, "clue values (mean {mean:.2}):")?;
+    for (value, &count) in (1..).zip(&stats.clues) {
+        let bar = (count * 40).div_ceil(largest);
+        writeln!(
+            w,
+            "  {value:>width$} | {:<40} {count} ({:.1}%)",
+            "#".repeat(bar),
+            count as f64 * 100.0 / total as f64,
+        )?;
+    }
+
+    writeln!(w)?;
+    write_summary(w, "solver steps", &stats.steps)?;
+    writeln!(w)?;
+    write_summary(w, "solver backtracks", &stats.backtracks)
+}
+
+/// The most advanced technique needed to solve a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    /// The values allowed by the clues on the cells of their lines are enough to fix every cell.
+    EdgeClues,
+    /// Removing the value of every fixed cell from its row and column and applying the clues
+    /// again, until no more cell gets fixed, solves the board.
+    Singles,
+    /// On top of singles, fixing the cells that are the only place left for a value in their row
+    /// or column solves the board.
+    HiddenSingles,
+    /// The solver has to guess values and backtrack.
+    Backtracking,
+}
+
+impl Technique {
+    /// Returns the name of this technique.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::EdgeClues => "edge clues",
+            Self::Singles => "singles",
+            Self::HiddenSingles => "hidden singles",
+            Self::Backtracking => "backtracking",
+        }
+    }
+}
+
+/// The rating of a puzzle, as computed by [`rate`].
+pub struct Rating {
+    /// The most advanced technique needed to solve the puzzle.
+    pub technique: Technique,
+    /// The statistics of the solver.
+    pub stats: solve::Stats,
+    /// The difficulty of the puzzle.
+    ///
+    /// Puzzles that can be solved without guessing are easy. Puzzles that require backtracking
+    /// are easy as well when the solver needs at most `size * size` steps, medium when it needs
+    /// at most `4 * size * size` steps, hard when it needs at most `16 * size * size` steps, and
+    /// expert otherwise.
+    pub difficulty: Difficulty,
+}
+
+/// Returns a cell that is not fixed yet, but is the only place left for a value in its row or
+/// column, as `(x, y, value)`.
+fn hidden_single(session: &solve::Session) -> Option<(usize, usize, u8)> {
+    let size = session.size();
+    let rows = (0..size).map(|y| (0..size).map(move |x| (x, y)).collect::<Vec<_>>());
+    let columns = (0..size).map(|x| (0..size).map(move |y| (x, y)).collect::<Vec<_>>());
+
+    for line in rows.chain(columns) {
+        for value in 1..=size as u8 {
+            let mut places = line
+                .iter()
+                .filter(|&&(x, y)| session.candidates(x, y).any(|v| v == value));
+            if let (Some(&(x, y)), None) = (places.next(), places.next()) {
+                if session.candidates(x, y).len() > 1 {
+                    return Some((x, y, value));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Fixes the hidden singles of the board of `session`, propagating every one of them, until no
+/// more is found.
+///
+/// Returns whether the board got solved.
+fn solve_with_hidden_singles(session: &mut solve::Session) -> bool {
+    while let Some((x, y, value)) = hidden_single(session) {
+        if session.assign(x, y, value).is_err() || session.propagate().is_err() {
+            return false;
+        }
+    }
+
+    session.is_solved()
+}
+
+/// Rates the puzzle of `header` by solving it while tracking which techniques are needed.
+pub fn rate(header: &Header) -> Result<Rating, solve::SolutionError> {
+    let mut stats = solve::Stats::default();
+    solve::solve_with_stats(header, Default::default(), &mut stats)?;
+
+    let mut session = solve::Session::new(header);
+    let technique = if session.is_solved() {
+        Technique::EdgeClues
+    } else if session.propagate().is_ok() && session.is_solved() {
+        Technique::Singles
+    } else if solve_with_hidden_singles(&mut session) {
+        Technique::HiddenSingles
+    } else {
+        Technique::Backtracking
+    };
+
+    // Past size 5, nearly every puzzle with a unique solution requires backtracking, such that
+    // the difficulty mostly depends on the work of the solver.
+    let cells = header.size() * header.size();
+    let difficulty = match technique {
+        Technique::Backtracking if stats.steps > 16 * cells => Difficulty::Expert,
+        Technique::Backtracking if stats.steps > 4 * cells => Difficulty::Hard,
+        Technique::Backtracking if stats.steps > cells => Difficulty::Medium,
+        _ => Difficulty::Easy,
+    };
+
+    Ok(Rating {
+        technique,
+        stats,
+        difficulty,
+    })
+}
+
+/// Generates puzzles of size `size` with a unique solution until one of the provided difficulty
+/// is found, as rated by [`rate`].
+///
+/// `None` is returned when no such puzzle is found after `attempts` puzzles.
+pub fn generate_with_difficulty(
+    rng: &mut dyn RngCore,
+    size: u8,
+    difficulty: Difficulty,
+    attempts: usize,
+) -> Result<Option<Header>, solve::SolutionError> {
+    for _ in 0..attempts {
+        let solution = generate::generate_unique_solution_with(rng, size, Default::default())?;
+        let header = generate::solution_to_header(&solution);
+
+        if rate(&header)?.difficulty == difficulty {
+            return Ok(Some(header));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Writes the rating of a puzzle.
+pub fn write_rating(w: &mut dyn Write, rating: &Rating) -> io::Result<()> {
+    writeln!(w, "difficulty: {}", rating.difficulty.name())?;
+    writeln!(w, "technique:  {}", rating.technique.name())?;
+    writeln!(w, "steps:      {}", rating.stats.steps)?;
+    writeln!(w, "backtracks: {}", rating.stats.backtracks)?;
+    writeln!(w, "max depth:  {}", rating.stats.max_depth)
+}
+
+/// Returns the fraction of unique puzzles among `samples`, along with the half-width of its 95%
+/// confidence interval.
+fn uniqueness(samples: &[Sample]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let p = samples.iter().filter(|s| s.unique).count() as f64 / n;
+    (p, 1.96 * (p * (1.0 - p) / n).sqrt())
+}
+
+/// Returns the fraction of `samples` with the provided difficulty.
+fn fraction(samples: &[Sample], difficulty: Difficulty) -> f64 {
+    samples
+        .iter()
+        .filter(|s| s.difficulty == difficulty)
+        .count() as f64
+        / samples.len() as f64
+}
+
+/// Writes the difficulty distribution and the probability of uniqueness of each size, either as
+/// an aligned table or as CSV.
+pub fn write_distribution(
+    w: &mut dyn Write,
+    by_size: &[(u8, Vec<Sample>)],
+    csv: bool,
+) -> io::Result<()> {
+    if csv {
+        write!(w, "size,puzzles,unique,unique_margin")?;
+        for difficulty in Difficulty::ALL {
+            write!(w, ",{}", difficulty.name())?;
+        }
+        writeln!(w)?;
+    } else {
+        write!(w, "{:>4} {:>9} {:>16}", "size", "puzzles", "unique")?;
+        for difficulty in Difficulty::ALL {
+            write!(w, " {:>7}", difficulty.name())?;
+        }
+        writeln!(w)?;
+    }
+
+    for (size, samples) in by_size {
+        if samples.is_empty() {
+            continue;
+        }
+
+        let (unique, margin) = uniqueness(samples);
+
+        if csv {
+            write!(w, "{size},{},{unique:.4},{margin:.4}", samples.len())?;
+            for difficulty in Difficulty::ALL {
+                write!(w, ",{:.4}", fraction(samples, difficulty))?;
+            }
+        } else {
+            let unique = format!("{:.1}% (± {:.1}%)", unique * 100.0, margin * 100.0);
+            write!(w, "{size:>4} {:>9} {unique:>16}", samples.len())?;
+            for difficulty in Difficulty::ALL {
+                write!(w, " {:>6.1}%", fraction(samples, difficulty) * 100.0)?;
+            }
+        }
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the value at quantile `q` of the sorted slice `sorted`.
+fn percentile(sorted: &[usize], q: f64) -> usize {
+    sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+/// Writes the aggregate report of the provided samples.
+///
+/// The `hardest` seeds are listed at the end of the report.
+pub fn write_report(
+    w: &mut dyn Write,
+    size: u8,
+    samples: &[Sample],
+    hardest: usize,
+) -> io::Result<()> {
+    writeln!(w, "size:    {size}")?;
+    writeln!(w, "puzzles: {}", samples.len())?;
+
+    if samples.is_empty() {
+        return Ok(());
+    }
+
+    let unique = samples.iter().filter(|s| s.unique).count();
+    let (_, margin) = uniqueness(samples);
+    writeln!(
+        w,
+        "unique:  {unique} ({:.1}% ± {:.1}%)",
+        unique as f64 * 100.0 / samples.len() as f64,
+        margin * 100.0,
+    )?;
+
+    writeln!(w)?;
+    writeln!(w, "difficulty:")?;
+    for difficulty in Difficulty::ALL {
+        let count = samples
+            .iter()
+            .filter(|s| s.difficulty == difficulty)
+            .count();
+        writeln!(
+            w,
+            "  {:<7} {count} ({:.1}%)",
+            difficulty.name(),
+            fraction(samples, difficulty) * 100.0,
+        )?;
+    }
+
+    let mut steps: Vec<usize> = samples.iter().map(|s| s.steps).collect();
+    steps.sort_unstable();
+    let mean = steps.iter().sum::<usize>() as f64 / steps.len() as f64;
+
+    writeln!(w)?;
+    writeln!(w, "solver steps:")?;
+    writeln!(w, "  min:  {}", steps[0])?;
+    writeln!(w, "  p50:  {}", percentile(&steps, 0.5))?;
+    writeln!(w, "  p90:  {}", percentile(&steps, 0.9))?;
+    writeln!(w, "  p99:  {}", percentile(&steps, 0.99))?;
+    writeln!(w, "  max:  {}", steps[steps.len() - 1])?;
+    writeln!(w, "  mean: {mean:.1}")?;
+
+    // The histogram uses buckets of exponentially increasing sizes: [0, 1), [1, 10), [10, 100)...
+    let mut buckets: Vec<usize> = Vec::new();
+    for &s in &steps {
+        let mut bucket = 0;
+        let mut bound = 1;
+        while s >= bound {
+            bucket += 1;
+            bound = bound.saturating_mul(10);
+        }
+        if buckets.len() <= bucket {
+            buckets.resize(bucket + 1, 0);
+        }
+        buckets[bucket] += 1;
+    }
+
+    let largest = buckets.iter().copied().max().unwrap_or(1);
+    let labels: Vec<String> = (0..buckets.len() as u32)
+        .map(|i| match i {
+            0 => "0".to_string(),
+            _ => format!("{}..{}", 10usize.pow(i - 1), 10usize.pow(i)),
+        })
+        .collect();
+    let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+
+    writeln!(w)?;
+    writeln!(w, "solver steps histogram:")?;
+    for (label, &count) in labels.iter().zip(&buckets) {
+        let bar = (count * 40).div_ceil(largest);
+        writeln!(
+            w,
+            "  {label:>label_width$} | {:<40} {count}",
+            "#".repeat(bar)
+        )?;
+    }
+
+    let mut by_difficulty: Vec<&Sample> = samples.iter().collect();
+    by_difficulty.sort_by(|a, b| b.steps.cmp(&a.steps).then(a.seed.cmp(&b.seed)));
+
+    writeln!(w)?;
+    writeln!(w, "hardest seeds:")?;
+    for sample in by_difficulty.iter().take(hardest) {
+        let unique = if sample.unique { "unique" } else { "ambiguous" };
+        writeln!(
+            w,
+            "  {:<20} {:>10} steps  {unique}",
+            sample.seed, sample.steps
+        )?;
+    }
+
+    Ok(())
+}
+
+/// What happens to a puzzle when one of its clues is removed.
+pub struct ClueImpact {
+    /// The index of the clue in the header.
+    pub index: usize,
+    /// The number of solutions of the puzzle without that clue.
+    pub solutions: usize,
+    /// The number of cells that differ between those solutions.
+    pub undetermined: usize,
+}
+
+/// Returns the name of the clue at `index` in a header of size `size`, such as `left 3`.
+fn clue_name(index: usize, size: usize) -> String {
+    let side = match index / size {
+        0 => "top",
+        1 => "bottom",
+        2 => "left",
+        _ => "right",
+    };
+
+    format!("{side} {}", index % size + 1)
+}
+
+/// Removes every clue of `header` in turn, and measures how much of the board is left
+/// undetermined.
+///
+/// At most `limit` solutions are enumerated for each clue. When that limit is reached, the counts
+/// are only lower bounds. The returned impacts are sorted from the most important clue to the
+/// least important one.
+pub fn analyze_clues(
+    header: &Header,
+    limit: usize,
+) -> Result<Vec<ClueImpact>, solve::SolutionError> {
+    let size = header.size();
+    let mut impacts = Vec::with_capacity(header.len());
+
+    for index in 0..header.len() {
+        let mut without: Box<[u8]> = header.clues().into();
+        without[index] = 0;
+        let without = Header::new(without);
+
+        let mut first: Option<Board> = None;
+        let mut differ = vec![false; size * size];
+        let mut solutions = 0;
+
+        solve::for_each_solution(&without, Default::default(), &mut |solution| {
+            match &first {
+                Some(first) => {
+                    for (differ, (a, b)) in differ.iter_mut().zip(first.iter().zip(solution.iter()))
+                    {
+                        *differ |= a != b;
+                    }
+                }
+                None => first = Some(solution.clone()),
+            }
+
+            solutions += 1;
+            solutions < limit
+        })?;
+
+        impacts.push(ClueImpact {
+            index,
+            solutions,
+            undetermined: differ.iter().filter(|&&d| d).count(),
+        });
+    }
+
+    impacts.sort_by(|a, b| {
+        b.undetermined
+            .cmp(&a.undetermined)
+            .then(b.solutions.cmp(&a.solutions))
+            .then(a.index.cmp(&b.index))
+    });
+
+    Ok(impacts)
+}
+
+/// Writes the impacts computed by [`analyze_clues`] as a table, from the most important clue to
+/// the least important one.
+///
+/// `solutions` is the number of solutions of the complete puzzle, and counts that reached `limit`
+/// are marked with a `+`.
+pub fn write_clue_report(
+    w: &mut dyn Write,
+    header: &[u8],
+    size: usize,
+    solutions: usize,
+    impacts: &[ClueImpact],
+    limit: usize,
+) -> io::Result<()> {
+    let bound = |count: usize, solutions: usize| {
+        if solutions >= limit {
+            format!("{count}+")
+        } else {
+            count.to_string()
+        }
+    };
+
+    writeln!(w, "solutions: {}", bound(solutions, solutions))?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "{:>4}  {:<10} {:>5} {:>9} {:>12}",
+        "rank", "clue", "value", "solutions", "undetermined"
+    )?;
+
+    for (rank, impact) in impacts.iter().enumerate() {
+        writeln!(
+            w,
+            "{:>4}  {:<10} {:>5} {:>9} {:>12}",
+            rank + 1,
+            clue_name(impact.index, size),
+            header[impact.index],
+            bound(impact.solutions, impact.solutions),
+            bound(impact.undetermined, impact.solutions),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_singles_rate_below_backtracking() {
+        // The puzzle of `generate --seed 21 5 --unique`. Singles alone leave it unsolved, but
+        // hidden singles solve it without guessing.
+        let header: Header = "4 4 1 2 2 1 2 5 3 2 2 3 3 2 1 3 1 2 3 2".parse().unwrap();
+        let rating = rate(&header).unwrap();
+
+        assert_eq!(rating.technique, Technique::HiddenSingles);
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+    }
+
+    #[test]
+    fn every_difficulty_can_be_generated_at_the_default_size() {
+        // The default size of the subcommands, given as many attempts as `play --difficulty`.
+        let mut rng = Xoroshiro128StarStar::seed_from_u64(0);
+
+        for difficulty in Difficulty::ALL {
+            let header = generate_with_difficulty(&mut rng, 6, difficulty, 100)
+                .unwrap()
+                .unwrap_or_else(|| panic!("no {} puzzle was generated", difficulty.name()));
+            assert_eq!(rate(&header).unwrap().difficulty, difficulty);
+        }
+    }
+}
@@ -2,13 +2,34 @@
 
 use std::fmt;
 use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use clap::{Parser, Subcommand, ValueEnum};
 
+use crate::alphabet::Alphabet;
+use crate::analyze::Difficulty;
+use crate::board::{Header, ParseHeaderError, MAX_SIZE};
+use crate::solve::Heuristic;
+use crate::symmetry::Symmetry;
+
 /// A CLI tool to play the Skyscrapper game.
 #[derive(Debug, Clone, Parser)]
 pub struct Args {
+    /// Describes boards with sentences rather than grid art, and reports errors without relying
+    /// on colors.
+    ///
+    /// This makes the output usable with screen readers.
+    #[clap(
+        long,
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        value_parser = clap::builder::BoolishValueParser::new(),
+        env = "SKYSCRAPPER_ACCESSIBLE"
+    )]
+    pub accessible: bool,
     /// The selected subcommand.
     #[clap(subcommand)]
     pub command: Command,
@@ -19,130 +40,1556 @@ pub struct Args {
 pub enum OutputFormat {
     /// Only print the solution.
     Solution,
+    /// Only print the solution, on one single line (row by row).
+    Flat,
     /// Only print the header.
     Header,
     /// Only print the header, on one single line.
     HeaderLine,
     /// Print both the header and the solution.
     Both,
+    /// Print the header around an empty grid, ready to be filled by hand.
+    Blank,
+    /// The same as `blank`, as an SVG image.
+    BlankSvg,
+    /// The same as `blank`, as a one-page PDF document.
+    BlankPdf,
+    /// Only print the header, as the URL of the puzzle on puzz.link.
+    Puzzlink,
 }
 
-/// A possible command for the CLI tool.
+impl OutputFormat {
+    /// Returns whether this format writes a whole document, which can't be followed by anything
+    /// else.
+    pub fn is_document(&self) -> bool {
+        matches!(self, Self::BlankSvg | Self::BlankPdf)
+    }
+}
+
+/// The output format of the [`Command::Analyze`] subcommand.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AnalyzeFormat {
+    /// A detailed report for every size.
+    Report,
+    /// A table with the difficulty distribution and the probability of uniqueness of every size.
+    Table,
+    /// The same information as `table`, as CSV.
+    Csv,
+}
+
+/// The stream to which the animation of the [`Command::Solve`] subcommand is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AnimationTarget {
+    /// The standard output.
+    Stdout,
+    /// The standard error, leaving the standard output for the solution alone.
+    Stderr,
+}
+
+/// The algorithm used by the [`Command::Solve`] subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    /// A backtracking search over the candidates of every cell.
+    #[value(alias = "backtrack")]
+    Backtracking,
+    /// An integer linear program solved by a generic MILP solver.
+    Ilp,
+    /// A boolean formula solved by a SAT solver.
+    #[cfg(feature = "sat")]
+    Sat,
+}
+
+impl Algorithm {
+    /// Returns the name of this algorithm, as written on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Backtracking => "backtracking",
+            Self::Ilp => "ilp",
+            #[cfg(feature = "sat")]
+            Self::Sat => "sat",
+        }
+    }
+}
+
+/// A compression algorithm used when writing to a file.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Compression {
+    /// Compress the file using gzip.
+    Gzip,
+    /// Compress the file using zstd.
+    Zstd,
+}
+
+/// The format of the log written by `solve --trace`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TraceFormat {
+    /// One sentence per line.
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+/// The format of the statistics written by `solve --stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// One labelled statistic per line.
+    Text,
+    /// One JSON object per header.
+    Json,
+}
+
+/// The arguments of the [`Command::Generate`] subcommand.
+#[derive(Debug, Clone, clap::Args)]
+pub struct GenerateArgs {
+    /// Whether the solution should be displayed rather than the header.
+    #[clap(
+        long,
+        short = 'o',
+        value_enum,
+        value_delimiter = ',',
+        env = "SKYSCRAPPER_OUTPUT"
+    )]
+    pub output: Vec<OutputFormat>,
+    /// Prints the boards in another orientation, moving the clues accordingly.
+    #[clap(long, value_enum, value_name = "SYMMETRY")]
+    pub orient: Option<Symmetry>,
+    /// The symbols used to write the heights of the buildings.
+    ///
+    /// Either `digits`, `letters` (`A` being the smallest height), or a list of custom symbols
+    /// from the smallest height to the tallest one. The symbols are separated by commas, or are
+    /// single characters when the list contains no comma. Clues are always written as numbers.
+    #[clap(long, default_value = "digits", value_name = "ALPHABET")]
+    pub alphabet: Alphabet,
+    /// Provides the seed that should be used to generate the board.
+    ///
+    /// When generating multiple boards, the `i`-th board (starting at 0) is generated using the
+    /// seed `seed + i`. Boards discarded by `--dedup` or `--exclude-seeds` still use up their
+    /// seed.
+    #[clap(long, env = "SKYSCRAPPER_SEED")]
+    pub seed: Option<u64>,
+    /// The size of the board.
+    #[clap(env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+    pub size: u8,
+    /// Only generates boards whose header has exactly one solution.
+    #[clap(long, action)]
+    pub unique: bool,
+    /// Searches for boards that are as hard as possible for the solver.
+    ///
+    /// Starting from a random board, rows, columns and values are swapped at random, keeping the
+    /// changes that do not make the board easier according to the `estimate` subcommand. This is
+    /// much slower than generating a random board.
+    #[clap(long, action)]
+    pub extreme: bool,
+    /// Checks the generated solution against its header, and solves that header again,
+    /// before printing it.
+    ///
+    /// Checking the solution against its header is always enabled in debug builds.
+    #[clap(long, action)]
+    pub verify: bool,
+    /// The number of boards to generate.
+    ///
+    /// Each board is written as soon as it has been generated.
+    #[clap(long, short = 'n', default_value_t = 1)]
+    pub count: usize,
+    /// Buffers the output, and flushes it every N boards.
+    ///
+    /// By default, the output is flushed after every line.
+    #[clap(long, value_name = "N")]
+    pub flush_every: Option<NonZeroUsize>,
+    /// Writes the boards to the provided file rather than to the standard output.
+    #[clap(long, value_name = "FILE")]
+    pub out: Option<PathBuf>,
+    /// Compresses the file provided through `--out`.
+    #[clap(long, value_enum, requires = "out")]
+    pub compress: Option<Compression>,
+    /// Encrypts the file provided through `--out` using a passphrase.
+    ///
+    /// The file uses the age format. It can be read back with the `decrypt` subcommand.
+    #[clap(long, value_name = "PASSPHRASE", requires = "out")]
+    pub encrypt: Option<String>,
+    /// The number of threads used to generate the boards.
+    ///
+    /// The generated boards do not depend on the number of threads.
+    #[clap(long, short = 'j', default_value = "1", env = "SKYSCRAPPER_JOBS")]
+    pub jobs: NonZeroUsize,
+    /// Discards boards whose header is a rotation or a reflection of an already written one.
+    ///
+    /// Discarded boards are replaced by the boards of the next seeds, up to 100 seeds per board
+    /// of `--count`. The number of discarded boards is reported at the end.
+    #[clap(long, action)]
+    pub dedup: bool,
+    /// Discards boards whose seed or header appear in the provided file, and appends the written
+    /// boards to it.
+    ///
+    /// Each line of the file holds a seed and the hash of the header it generated (as printed by
+    /// the `hash` subcommand). This prevents recurring events from publishing the same puzzle
+    /// twice. Like with `--dedup`, discarded boards are replaced by the boards of the next seeds,
+    /// and their number is reported at the end.
+    #[clap(long, value_name = "FILE")]
+    pub exclude_seeds: Option<PathBuf>,
+    /// Signs every board with the provided key.
+    ///
+    /// A `signature:` line, authenticating both the header and the solution, is written after
+    /// each board. It can be checked using the `verify-signature` subcommand.
+    #[clap(
+        long,
+        value_name = "KEY",
+        env = "SKYSCRAPPER_SIGN_KEY",
+        hide_env_values = true
+    )]
+    pub sign_key: Option<String>,
+}
+
+impl GenerateArgs {
+    /// Returns the arguments generating a single board of size `size`, every option having its
+    /// default value.
+    pub fn new(size: u8) -> Self {
+        Self {
+            output: Vec::new(),
+            orient: None,
+            alphabet: Alphabet::Digits,
+            seed: None,
+            size,
+            unique: false,
+            extreme: false,
+            verify: false,
+            count: 1,
+            flush_every: None,
+            out: None,
+            compress: None,
+            encrypt: None,
+            jobs: NonZeroUsize::MIN,
+            dedup: false,
+            exclude_seeds: None,
+            sign_key: None,
+        }
+    }
+}
+
+/// The arguments of the [`Command::Solve`] subcommand.
+#[derive(Debug, Clone, clap::Args)]
+pub struct SolveArgs {
+    /// The headers that will be solved.
+    ///
+    /// When multiple headers are provided, their solutions are separated by an empty line.
+    /// Duplicates are removed: a header given more than once is only solved once, and its
+    /// solution is printed again. A header of `-` is read from the next non-empty line of the
+    /// standard input.
+    #[clap(
+        required_unless_present_any = ["header_file", "resume", "stdin"],
+        value_parser = parse_header
+    )]
+    pub header: Vec<HeaderSource>,
+    /// Reads the header from a file rather than from the command line.
+    #[clap(long, value_name = "FILE", conflicts_with = "header")]
+    pub header_file: Option<PathBuf>,
+    /// Decrypts the files provided through `--header-file` and `--givens` using the provided
+    /// passphrase before reading them.
+    #[clap(long, value_name = "PASSPHRASE")]
+    pub decrypt: Option<String>,
+    /// Reads the headers from the standard input, one per line, printing the solution of every
+    /// header before reading the next one.
+    ///
+    /// Empty lines and lines starting with `#` are ignored. Headers that can't be parsed or solved
+    /// are reported without stopping, and the same header is solved again every time it appears.
+    #[clap(
+        long,
+        action,
+        conflicts_with_all = [
+            "header", "header_file", "givens", "trace", "all", "checkpoint", "resume"
+        ]
+    )]
+    pub stdin: bool,
+    /// The algorithm used to find the solutions.
+    ///
+    /// Only the backtracking search can be animated, traced, given a node budget or report its
+    /// progress. The SAT solver may be given a `--timeout`, but the ILP solver may not. `--engine`
+    /// is accepted as well.
+    #[clap(
+        long,
+        visible_alias = "engine",
+        value_enum,
+        default_value_t = Algorithm::Backtracking
+    )]
+    pub algorithm: Algorithm,
+    /// Whether the process should be animated.
+    #[clap(long, short, action)]
+    pub animate: bool,
+    /// The stream to which the animation is written. Implies `--animate`.
+    ///
+    /// When the animation is written to the standard error, the solution is the only thing
+    /// written to the standard output.
+    #[clap(long, value_enum, value_name = "STREAM", conflicts_with = "trace_json")]
+    pub animate_to: Option<AnimationTarget>,
+    /// Writes the animation to the provided file as an animated GIF image, rather than to the
+    /// terminal. Implies `--animate`.
+    ///
+    /// Only a single header can be animated to a file.
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["animate_to", "trace_json", "stdin"])]
+    pub gif: Option<PathBuf>,
+    /// The generated output.
+    ///
+    /// Unlike `generate -o`, a single format is accepted, which is why the environment variable
+    /// differs from the one of `generate`.
+    #[clap(
+        long,
+        short = 'o',
+        value_enum,
+        default_value_t = OutputFormat::Both,
+        env = "SKYSCRAPPER_SOLVE_OUTPUT"
+    )]
+    pub output: OutputFormat,
+    /// Prints the board in another orientation, moving the clues accordingly.
+    ///
+    /// The provided header is always read in its original orientation.
+    #[clap(long, value_enum, value_name = "SYMMETRY")]
+    pub orient: Option<Symmetry>,
+    /// The symbols used to write the heights of the buildings.
+    ///
+    /// Either `digits`, `letters` (`A` being the smallest height), or a list of custom symbols
+    /// from the smallest height to the tallest one. The symbols are separated by commas, or are
+    /// single characters when the list contains no comma. Clues are always written as numbers.
+    #[clap(long, default_value = "digits", value_name = "ALPHABET")]
+    pub alphabet: Alphabet,
+    /// Checks the solution against the header before printing it.
+    ///
+    /// This is always enabled in debug builds.
+    #[clap(long, action)]
+    pub verify: bool,
+    /// The maximum amount of memory the solver may use, in megabytes.
+    ///
+    /// When the budget is exceeded, the search is aborted. This is ignored by the `ilp`
+    /// and `sat` algorithms.
+    #[clap(long, value_name = "MB", env = "SKYSCRAPPER_MAX_MEMORY")]
+    pub max_memory: Option<usize>,
+    /// The maximum number of nodes of the search tree that the solver may explore.
+    ///
+    /// When the budget is exceeded, the search is aborted and its progress is reported. Unlike
+    /// a timeout, this does not depend on the speed of the machine.
+    #[clap(long, value_name = "N")]
+    pub node_limit: Option<usize>,
+    /// The time the solver may take for each header, such as `30s`, `500ms` or `2m`. A number
+    /// without unit is a number of seconds.
+    ///
+    /// When the deadline passes, the search is aborted and the program exits with the status
+    /// code 6. Only the backtracking search and the SAT solver support this.
+    #[clap(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub timeout: Option<Duration>,
+    /// Prints the cells deduced without guessing when the search times out.
+    ///
+    /// Unknown cells are written as `0` (or `.` with other alphabets), such that the board printed
+    /// by `-o solution` can be given back to `--givens`.
+    #[clap(long, action, requires = "timeout", conflicts_with = "ndjson")]
+    pub partial: bool,
+    /// The number of threads used by the backtracking search. Defaults to the number of logical
+    /// CPUs.
+    ///
+    /// The solution does not depend on the number of threads. Animated, traced, checkpointed and
+    /// `--all` searches, along with the ones given a `--node-limit`, `--progress` or
+    /// `--random-order`, always use a single thread.
+    #[clap(long, short = 'j', value_name = "N")]
+    pub threads: Option<NonZeroUsize>,
+    /// Writes a trace of the search to the provided file.
+    ///
+    /// The trace uses the JSON Lines format described in the README.
+    #[clap(long, value_name = "FILE", conflicts_with = "animate")]
+    pub trace_json: Option<PathBuf>,
+    /// Periodically writes the number of explored nodes, the number of backtracks, the current
+    /// depth of the search and the elapsed time to the standard error.
+    ///
+    /// The search runs on a single thread.
+    #[clap(
+        long,
+        action,
+        conflicts_with_all = ["animate", "animate_to", "gif", "trace_json", "all"]
+    )]
+    pub progress: bool,
+    /// Logs every deduction made by the propagation of the constraints, along with every guess of
+    /// the search, to the provided file.
+    ///
+    /// Every deduction names the cell whose candidates changed and the clue, given cell or fixed
+    /// cell it follows from. The search runs on a single thread.
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "animate", "animate_to", "gif", "trace_json", "progress", "random_order", "all"
+        ]
+    )]
+    pub trace: Option<PathBuf>,
+    /// The format of the log written by `--trace`.
+    #[clap(long, value_enum, value_name = "FORMAT", default_value_t = TraceFormat::Text, requires = "trace")]
+    pub trace_format: TraceFormat,
+    /// Compresses the file provided through `--trace-json`.
+    #[clap(long, value_enum, requires = "trace_json")]
+    pub compress: Option<Compression>,
+    /// Prints every solution of the headers rather than the first one, followed by their count.
+    ///
+    /// The solutions are separated by an empty line. Only the backtracking search can
+    /// enumerate the solutions.
+    #[clap(long, action, conflicts_with_all = ["animate", "animate_to", "gif", "trace_json"])]
+    pub all: bool,
+    /// Prints the solutions found by `--all` or `--stdin` as JSON Lines, one
+    /// `{"header":[...],"board":[...]}` object per solution. The counts are written to the
+    /// standard error.
+    ///
+    /// With `--stdin`, every header produces a single line, which holds an `error` rather than a
+    /// `board` when no solution could be found.
+    #[clap(long, action)]
+    pub ndjson: bool,
+    /// Writes statistics about the search of every header to the standard error: the number of
+    /// cells deduced before any guess, the number of guesses, of backtracks, the maximum depth of
+    /// the search and the time it took.
+    #[clap(long, action, conflicts_with = "all")]
+    pub stats: bool,
+    /// The format of the statistics written by `--stats`. Implies `--stats`.
+    ///
+    /// With `json`, the statistics of every header are written as a single JSON object, on a line
+    /// of their own.
+    #[clap(long, value_enum, value_name = "FORMAT", conflicts_with = "all")]
+    pub stats_format: Option<StatsFormat>,
+    /// Also checks that the headers have a unique solution.
+    ///
+    /// The search goes on after the first solution to look for a second one. When a header has
+    /// other solutions, its first solution is still printed, and the program exits with the
+    /// status code 5.
+    #[clap(long, action, conflicts_with = "all")]
+    pub unique: bool,
+    /// Reads cells whose value is already known from the provided file, `-` being the standard
+    /// input.
+    ///
+    /// The file holds a partial board, empty cells being written as `.` or `0`. The solution keeps
+    /// the values of the filled cells. Only a single header can be solved with givens.
+    #[clap(long, value_name = "FILE")]
+    pub givens: Option<PathBuf>,
+    /// Tries the values of every cell in a random order rather than in increasing order.
+    ///
+    /// Headers with multiple solutions then produce one of them at random. The search runs on a
+    /// single thread.
+    #[clap(
+        long,
+        action,
+        conflicts_with_all = ["animate", "animate_to", "gif", "trace_json", "progress", "all"]
+    )]
+    pub random_order: bool,
+    /// Provides the seed of the order used by `--random-order`.
+    #[clap(long, requires = "random_order")]
+    pub seed: Option<u64>,
+    /// Writes the state of the search to the provided file when it is stopped before its end, such
+    /// as by a CTRL+C, such that it can go on later with `--resume`.
+    ///
+    /// Only a single header can be checkpointed, and the search runs on a single thread.
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "animate", "animate_to", "gif", "trace_json", "trace", "progress", "random_order",
+            "all"
+        ]
+    )]
+    pub checkpoint: Option<PathBuf>,
+    /// Also writes the state of the search at regular intervals, such as `60s`, in case the
+    /// program is killed.
+    #[clap(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub checkpoint_every: Option<Duration>,
+    /// Goes on with the search whose state has been written to the provided file by
+    /// `--checkpoint`.
+    ///
+    /// The header and the givens are read from the file. The state of the search is written back
+    /// to it, unless `--checkpoint` is given.
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "header", "header_file", "givens", "animate", "animate_to", "gif", "trace_json",
+            "trace", "progress", "random_order", "all"
+        ]
+    )]
+    pub resume: Option<PathBuf>,
+}
+
+/// The arguments of the [`Command::Check`] subcommand.
+#[derive(Debug, Clone, clap::Args)]
+pub struct CheckArgs {
+    /// The header that the board will be verified against.
+    ///
+    /// With `--rules-only`, the header may be omitted. A header of `-` is read from the first
+    /// non-empty line of the standard input, the board being read from the following lines.
+    #[clap(
+        required_unless_present_any = ["rules_only", "header_file"],
+        value_parser = parse_header
+    )]
+    pub header: Option<HeaderSource>,
+    /// Reads the header from a file rather than from the command line.
+    #[clap(long, value_name = "FILE", value_parser = read_header_file, conflicts_with = "header")]
+    pub header_file: Option<Header>,
+    /// Decrypts the standard input using the provided passphrase before checking it.
+    #[clap(long, value_name = "PASSPHRASE")]
+    pub decrypt: Option<String>,
+    /// Compares the board cell-by-cell with the solution stored in the provided file.
+    ///
+    /// The differing cells are reported before the board is validated against the header.
+    #[clap(long, value_name = "FILE")]
+    pub against_solution: Option<PathBuf>,
+    /// Also checks that the puzzle has a unique solution.
+    ///
+    /// When the board is valid but the puzzle has other solutions, the program exits with
+    /// the status code 5.
+    #[clap(long, action, conflicts_with = "rules_only")]
+    pub unique: bool,
+    /// Only checks the shape of the board, the range of its values, and that no value appears
+    /// twice in a row or a column, ignoring the clues.
+    ///
+    /// When no header is provided, the size of the board is deduced from its first line.
+    #[clap(long, action)]
+    pub rules_only: bool,
+    /// Rejects boards that are not formatted canonically.
+    ///
+    /// Cells must be separated by exactly one space, and every line, including the last one,
+    /// must end with a newline. By default, any number of spaces is accepted, empty lines are
+    /// ignored and the last newline is optional.
+    #[clap(long, action)]
+    pub strict: bool,
+    /// The symbols used to write the heights of the buildings.
+    ///
+    /// Accepts the same values as `generate --alphabet`.
+    #[clap(long, default_value = "digits", value_name = "ALPHABET")]
+    pub alphabet: Alphabet,
+    /// Accepts a partially filled board, and prints it completed by the solver.
+    ///
+    /// Empty cells are written as `.` or `0`. When the filled cells can't be part of a
+    /// solution, the program reports it and fails.
+    #[clap(
+        long,
+        action,
+        conflicts_with_all = ["rules_only", "unique", "against_solution"]
+    )]
+    pub complete: bool,
+    /// Watches the provided file, and checks the board it holds every time it is saved.
+    ///
+    /// The board is read from the file rather than from the standard input. The program runs
+    /// until it is interrupted.
+    #[clap(long, value_name = "FILE", conflicts_with = "decrypt")]
+    pub watch: Option<PathBuf>,
+}
+
+/// The arguments of the [`Command::Play`] subcommand.
+#[derive(Debug, Clone, clap::Args)]
+pub struct PlayArgs {
+    /// The size of the generated board, from 1 to 9.
+    ///
+    /// The size and the seed are ignored when the puzzle is given through `--header`, `--file` or
+    /// `--code`.
+    #[clap(
+        env = "SKYSCRAPPER_SIZE",
+        value_parser = parse_size,
+        required_unless_present_any = ["header", "file", "code"]
+    )]
+    pub size: Option<u8>,
+    /// Provides the seed used to generate the puzzle.
+    #[clap(long, env = "SKYSCRAPPER_SEED")]
+    pub seed: Option<u64>,
+    /// The difficulty of the generated puzzle, as rated by the `rate` subcommand.
+    ///
+    /// Puzzles are generated until one of this difficulty is found. Some difficulties are rare
+    /// or impossible for some sizes, in which case the program gives up after a while.
+    #[clap(long, value_enum, conflicts_with_all = ["header", "file", "code"])]
+    pub difficulty: Option<Difficulty>,
+    /// Plays the puzzle of this header rather than a generated one.
+    #[clap(
+        long,
+        value_parser = parse_header,
+        conflicts_with_all = ["file", "code"]
+    )]
+    pub header: Option<HeaderSource>,
+    /// Plays the puzzle saved in a `.sky` file rather than a generated one.
+    ///
+    /// The file holds the header on a single line, or the clues around the board as written by
+    /// `generate --output header`. Lines starting with `#` are ignored, and so are the cells of
+    /// the board when there are some.
+    #[clap(long, value_name = "FILE", conflicts_with = "code")]
+    pub file: Option<PathBuf>,
+    /// Plays the puzzle of a puzzle code rather than a generated one.
+    ///
+    /// Puzzle codes are the URLs written by `--output puzzlink`, which may be shortened to the
+    /// part that follows the `?`, such as `skyscrapers/4/4/...`. Puzzles with given cells are
+    /// not supported.
+    #[clap(long)]
+    pub code: Option<String>,
+}
+
+/// A subcommand of the [`Command::Samurai`] subcommand.
 #[derive(Debug, Clone, Subcommand)]
-pub enum Command {
-    /// Generate a random Skyscrapper header.
+pub enum SamuraiCommand {
+    /// Generates a random samurai puzzle.
     Generate {
-        /// Whether the solution should be displayed rather than the header.
-        #[clap(long, short = 'o', value_enum)]
-        output: Vec<OutputFormat>,
-        /// Provides the seed that should be used to generate the board.
+        /// The size of every grid.
+        #[clap(env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+        size: u8,
+        /// The number of grids.
+        #[clap(long, default_value_t = 2)]
+        grids: usize,
+        /// The size of the square shared by two consecutive grids.
+        ///
+        /// This must be at most half of the size. Defaults to a third of the size.
         #[clap(long)]
+        overlap: Option<usize>,
+        /// Provides the seed that should be used to generate the puzzle.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
         seed: Option<u64>,
-        /// The size of the board.
-        size: u8,
+        /// Only generates puzzles that have exactly one solution.
+        #[clap(long, action)]
+        unique: bool,
+        /// The generated output.
+        ///
+        /// `header-line` and `flat` print one line per grid.
+        #[clap(long, short = 'o', value_enum, default_value_t = OutputFormat::Both)]
+        output: OutputFormat,
     },
-    /// Solves a board given a specific header.
+    /// Solves a samurai puzzle, given the header of every grid.
     ///
-    /// The header must be provided using the same format as the one outputed by header-line.
+    /// The clues removed because of a neighbouring grid are ignored.
     Solve {
-        /// The header that will be solved.
-        header: Header,
-        /// Whether the process should be animated.
-        #[clap(long, short, action)]
-        animate: bool,
+        /// The headers of the grids, from the top-left one to the bottom-right one.
+        #[clap(required = true, num_args = 2..)]
+        headers: Vec<Header>,
+        /// The size of the square shared by two consecutive grids.
+        ///
+        /// Defaults to a third of the size.
+        #[clap(long)]
+        overlap: Option<usize>,
         /// The generated output.
         #[clap(long, short = 'o', value_enum, default_value_t = OutputFormat::Both)]
         output: OutputFormat,
     },
+}
+
+/// A subcommand of the [`Command::Batch`] subcommand.
+#[derive(Debug, Clone, Subcommand)]
+pub enum BatchCommand {
+    /// Solves every header of a file.
+    ///
+    /// The file holds one header per line, using the format of `header-line`. Empty lines and
+    /// lines starting with `#` are ignored. Headers that can't be parsed or solved are reported
+    /// with their line number and produce a `-` line, and the other ones are still solved.
+    Solve {
+        /// The file to read the headers from, or `-` for the standard input.
+        file: PathBuf,
+        /// The generated output.
+        ///
+        /// `flat` and `header-line` print one line per header. Other formats separate the
+        /// solutions with an empty line.
+        #[clap(long, short = 'o', value_enum, default_value_t = OutputFormat::Flat)]
+        output: OutputFormat,
+        /// The symbols used to write the heights of the buildings.
+        ///
+        /// Accepts the same values as `generate --alphabet`.
+        #[clap(long, default_value = "digits", value_name = "ALPHABET")]
+        alphabet: Alphabet,
+    },
+    /// Checks a set of boards against their headers.
+    ///
+    /// The file holds one pair per line: a header, using the format of `header-line`, followed
+    /// by `:` and the path of the board to check, relative to the file. Empty lines and lines
+    /// starting with `#` are ignored.
+    Check {
+        /// The file to read the pairs from, or `-` for the standard input.
+        file: PathBuf,
+        /// Writes a machine-readable report on the standard output instead of reporting errors.
+        #[clap(long, value_enum, value_name = "FORMAT")]
+        report: Option<ReportFormat>,
+        /// Only accepts boards formatted canonically.
+        ///
+        /// Has the same meaning as `check --strict`.
+        #[clap(long, action)]
+        strict: bool,
+        /// The symbols used to write the heights of the buildings.
+        ///
+        /// Accepts the same values as `generate --alphabet`.
+        #[clap(long, default_value = "digits", value_name = "ALPHABET")]
+        alphabet: Alphabet,
+    },
+}
+
+/// A format of the report written by `batch check --report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// One line per board, with a header line.
+    Csv,
+    /// A JSON document, `{"results":[...]}`.
+    Json,
+}
+
+/// A format of the [`Command::Render`] subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImageFormat {
+    /// A vector image.
+    Svg,
+    /// A grayscale raster image.
+    Png,
+}
+
+/// A format of the [`Command::Sheet`] subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SheetFormat {
+    /// A PDF document with six puzzles per A4 page.
+    Pdf,
+    /// An HTML page embedding the puzzles as SVG images.
+    Html,
+}
+
+/// How the [`Command::Judge`] subcommand gives the header to the judged program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum JudgeInput {
+    /// The header is a single argument, its clues being separated by spaces.
+    Arg,
+    /// Every clue of the header is a separate argument.
+    Args,
+    /// The header is written on the standard input, on a single line.
+    Stdin,
+}
+
+/// A format of the [`Command::Convert`] subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConvertFormat {
+    /// The cells of the board, one row per line.
+    Solution,
+    /// The cells of the board, on one single line (row by row).
+    Flat,
+    /// The header around an empty grid.
+    Header,
+    /// The header, on one single line.
+    HeaderLine,
+    /// The header around the cells of the board.
+    Both,
+    /// The header around an empty grid drawn with borders, ready to be filled by hand.
+    Blank,
+    /// A JSON object, `{"header":[...],"board":[...]}`. The board is omitted when unknown.
+    Json,
+    /// The cells of the board, one row per line, separated by commas.
+    Csv,
+    /// The URL of the puzzle on puzz.link. Only the header is read and written.
+    Puzzlink,
+}
+
+/// A possible command for the CLI tool.
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Generate a random Skyscrapper header.
+    Generate(GenerateArgs),
+    /// Solves a board given a specific header.
+    ///
+    /// The header must be provided using the same format as the one outputed by header-line.
+    Solve(SolveArgs),
+    /// Estimates the size of the search tree explored when solving a header.
+    ///
+    /// This is useful to predict whether solving a header will take a long time before actually
+    /// doing it.
+    Estimate {
+        /// The header that would be solved.
+        header: Header,
+        /// The number of random paths taken in the search tree.
+        #[clap(long, default_value_t = 1000)]
+        probes: usize,
+        /// Provides the seed used to choose the random paths.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
+        seed: Option<u64>,
+    },
+    /// Counts the solutions of a header.
+    ///
+    /// The search runs until every solution has been found, or until more than `--limit`
+    /// solutions have been found, in which case the limit is printed followed by a `+`.
+    Count {
+        /// The header whose solutions are counted.
+        header: Header,
+        /// Stops the search once more than this number of solutions has been found.
+        #[clap(long)]
+        limit: Option<usize>,
+    },
+    /// Rates the difficulty of a header.
+    ///
+    /// The puzzle is solved while tracking the techniques it requires: the values allowed by the
+    /// edge clues alone, singles, hidden singles, or backtracking. The grade is printed along with
+    /// the statistics of the solver.
+    Rate {
+        /// The header to rate.
+        header: Header,
+    },
+    /// Generates every board of a range of seeds, and reports statistics about them.
+    ///
+    /// The difficulty of a board is measured by the number of steps the solver needs to find its
+    /// solution.
+    Analyze {
+        /// The sizes of the generated boards, such as `5` or `4,5,6`.
+        ///
+        /// Every size is analyzed using the same range of seeds.
+        #[clap(long, required = true, value_delimiter = ',', env = "SKYSCRAPPER_SIZE")]
+        size: Vec<u8>,
+        /// The range of seeds to analyze, such as `0..10000` or `0..=9999`.
+        #[clap(long)]
+        seeds: SeedRange,
+        /// The number of hardest seeds listed in the report.
+        #[clap(long, default_value_t = 10)]
+        hardest: usize,
+        /// The format of the output.
+        #[clap(long, value_enum, default_value_t = AnalyzeFormat::Report)]
+        format: AnalyzeFormat,
+    },
+    /// Generates many boards of the same size and reports how the generator and the solver
+    /// behave on them.
+    ///
+    /// The report includes the frequency of every clue value, the fraction of headers with a
+    /// unique solution, and the number of steps and backtracks of the solver. The `i`-th board
+    /// is generated with the seed `seed + i`, like `generate --count` would.
+    Stats {
+        /// The size of the generated boards.
+        #[clap(long, default_value_t = 6, env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+        size: u8,
+        /// The number of generated boards.
+        #[clap(long, default_value_t = 1000)]
+        samples: usize,
+        /// Provides the seed of the first board.
+        #[clap(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Measures the time and the number of nodes the solver needs for random boards.
+    ///
+    /// For every size, `--iterations` boards are generated and solved, and the minimum, median
+    /// and 95th percentile of the solving times and node counts are reported. Generating the
+    /// boards is not measured.
+    ///
+    /// With `--compare`, the puzzles of `--corpus` are solved by every selected algorithm
+    /// instead, and the algorithms are reported side by side. With `--ab`, they are solved under
+    /// two configurations of the solver, which are compared puzzle by puzzle.
+    Bench {
+        /// The sizes of the benchmarked boards, such as `5`, `4..9` or `4..=8`.
+        #[clap(long, default_value = "4..=6")]
+        sizes: SizeRange,
+        /// The number of boards solved for every size.
+        #[clap(long, default_value_t = 10)]
+        iterations: usize,
+        /// Provides the seed used to generate the boards.
+        ///
+        /// The same seed always benchmarks the same boards, making runs comparable.
+        #[clap(long, default_value_t = 0)]
+        seed: u64,
+        /// The algorithms to compare, such as `backtracking,ilp`.
+        ///
+        /// Accepts the values of `solve --algorithm`, along with `backtrack` for `backtracking`.
+        #[clap(
+            long,
+            value_enum,
+            value_name = "ALGORITHMS",
+            value_delimiter = ',',
+            group = "corpus_mode",
+            requires = "corpus"
+        )]
+        compare: Vec<Algorithm>,
+        /// Compares two configurations of the solver, such as `heuristic=mrv heuristic=lex`.
+        ///
+        /// A configuration is a list of settings separated by commas, such as
+        /// `algorithm=backtracking,heuristic=lex`. `algorithm` accepts the values of `--compare`,
+        /// and `heuristic` is the way the backtracking search picks the cell to branch on: `mrv`
+        /// for the cell with the fewest candidates, or `lex` for the first undetermined cell. The
+        /// settings that are left out keep their default value.
+        #[clap(
+            long,
+            num_args = 2,
+            value_names = ["A", "B"],
+            value_parser = parse_solver_config,
+            group = "corpus_mode",
+            requires = "corpus"
+        )]
+        ab: Vec<SolverConfig>,
+        /// The puzzles solved by `--compare` and `--ab`.
+        ///
+        /// Either a pack written by the `pack` subcommand, or a JSON Lines file holding one
+        /// puzzle per line, such as `{"id":"a","header":[...]}`. The `id` names the puzzle in the
+        /// report of `--ab`, and is optional.
+        #[clap(
+            long,
+            value_name = "FILE",
+            requires = "corpus_mode",
+            conflicts_with_all = ["sizes", "iterations", "seed"]
+        )]
+        corpus: Option<PathBuf>,
+        /// Gives up on a puzzle of the corpus after the provided duration, counting it as a
+        /// failure.
+        ///
+        /// The ILP solver can't be given a timeout, such that the algorithm `ilp` can't be combined
+        /// with it.
+        #[clap(long, value_name = "DURATION", value_parser = parse_duration, requires = "corpus")]
+        timeout: Option<Duration>,
+    },
+    /// Validates the build by generating random boards and solving their headers again.
+    ///
+    /// Every generated solution is checked against its header, and the header is solved again.
+    /// The solution found by the solver must be valid too. Any mismatch is reported along with
+    /// the size and the seed of the offending board.
+    Selftest {
+        /// The sizes of the generated boards, such as `5`, `1..7` or `1..=6`.
+        #[clap(long, default_value = "1..=6")]
+        sizes: SizeRange,
+        /// The number of boards generated for every size.
+        #[clap(long, default_value_t = 100)]
+        iterations: usize,
+        /// Provides the seed of the first board of every size.
+        ///
+        /// The `i`-th board is generated with the seed `seed + i`. Defaults to a random seed.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
+        seed: Option<u64>,
+        /// Only generates boards whose header has exactly one solution, and requires the solver
+        /// to find exactly the generated solution.
+        #[clap(long, action)]
+        unique: bool,
+    },
+    /// Confirms that a seed reproduces a puzzle, from its generation to its solution.
+    ///
+    /// The board of the seed is generated, its header is computed and checked against it, and
+    /// the header is solved again. The number of solutions of the header is reported along with
+    /// the SHA-256 hashes of the header and of the board, as written by `-o header-line` and
+    /// `-o flat`.
+    Verify {
+        /// The seed of the puzzle.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
+        seed: u64,
+        /// The size of the puzzle.
+        #[clap(long, default_value_t = 6, env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+        size: u8,
+        /// Whether the puzzle has been generated with `--unique`.
+        ///
+        /// The solver must then find exactly the generated board.
+        #[clap(long, action)]
+        unique: bool,
+        /// The maximum number of solutions counted. A `+` follows the count when the header has
+        /// more.
+        #[clap(long, default_value_t = 1000)]
+        limit: usize,
+    },
     /// Determines whether a given response is valid.
     ///
     /// This command expects the board to be provided without its header in its standard input.
-    Check {
-        /// The header that the board will be verified against.
+    Check(CheckArgs),
+    /// Deduces the value of one cell of a partially filled board.
+    ///
+    /// The board is read from the standard input, empty cells being written as `.` or `0`. The
+    /// cell is printed along with the clues that force its value.
+    Hint {
+        /// The header of the puzzle.
+        header: Header,
+        /// The symbols used to write the heights of the buildings.
+        ///
+        /// Accepts the same values as `generate --alphabet`.
+        #[clap(long, default_value = "digits", value_name = "ALPHABET")]
+        alphabet: Alphabet,
+    },
+    /// Generates and solves samurai puzzles (experimental).
+    ///
+    /// A samurai puzzle is made of several grids arranged diagonally, each one sharing its
+    /// bottom-right corner with the top-left corner of the next one. Clues that would run into a
+    /// neighbouring grid are removed.
+    Samurai {
+        /// The selected subcommand.
+        #[clap(subcommand)]
+        command: SamuraiCommand,
+    },
+    /// Converts a board or a header from one format to another.
+    ///
+    /// The input is read from the standard input. When it only holds a header, the formats that
+    /// need the cells of the board can't be written. When it only holds cells, the header is
+    /// computed from them.
+    Convert {
+        /// The format of the input.
+        #[clap(long, value_enum)]
+        from: ConvertFormat,
+        /// The format of the output.
+        #[clap(long, value_enum)]
+        to: ConvertFormat,
+    },
+    /// Generates the puzzle of the day.
+    ///
+    /// The seed is derived from the date and the size, such that everyone gets the same puzzle
+    /// on a given day. For the date `YYYY-MM-DD`, the seed is `YYYYMMDD * 256 + size`, and the
+    /// puzzle is generated like `generate --unique --seed SEED SIZE` would.
+    Daily {
+        /// The date of the puzzle, as `YYYY-MM-DD`. Defaults to the current date, in UTC.
+        #[clap(long)]
+        date: Option<Date>,
+        /// The size of the board.
+        #[clap(long, default_value_t = 6, env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+        size: u8,
+        /// The generated output. Defaults to the clues around an empty board.
+        #[clap(
+            long,
+            short = 'o',
+            value_enum,
+            value_delimiter = ',',
+            default_value = "blank"
+        )]
+        output: Vec<OutputFormat>,
+    },
+    /// Writes a completion script for the provided shell to the standard output.
+    ///
+    /// For instance, `skyscrapper-cli completions bash > /etc/bash_completion.d/skyscrapper-cli`
+    /// installs the completions of Bash.
+    Completions {
+        /// The shell the script is written for.
+        #[clap(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Writes the manual page of the program in the roff format.
+    ///
+    /// The page describes the subcommands, the input formats and the exit codes. For instance,
+    /// `skyscrapper-cli man | man -l -` reads it.
+    Man {
+        /// Writes the page of the program along with one page per subcommand to this directory,
+        /// rather than writing the page of the program on the standard output.
+        #[clap(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+    /// Draws a puzzle to an image file.
+    Render {
+        /// The header of the puzzle.
+        header: Header,
+        /// The file the image is written to.
+        #[clap(long, value_name = "FILE")]
+        out: PathBuf,
+        /// Solves the puzzle and draws its solution in the grid.
+        #[clap(long, action)]
+        solution: bool,
+        /// The format of the image. Defaults to the extension of `--out`.
+        #[clap(long, value_enum)]
+        format: Option<ImageFormat>,
+    },
+    /// Generates a printable worksheet of puzzles, followed by their answer key.
+    ///
+    /// The answer key starts on a new page. The `i`-th puzzle is generated with the seed
+    /// `seed + i`, like `generate --count` would.
+    Sheet {
+        /// The number of puzzles on the worksheet.
+        #[clap(long, default_value_t = 8)]
+        count: usize,
+        /// The size of the puzzles.
+        #[clap(long, default_value_t = 5, env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+        size: u8,
+        /// Provides the seed of the first puzzle.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
+        seed: Option<u64>,
+        /// Only generates puzzles that have exactly one solution, such that the answer key is
+        /// the only correct answer.
+        #[clap(long, action)]
+        unique: bool,
+        /// The file the worksheet is written to.
+        #[clap(long, value_name = "FILE")]
+        out: PathBuf,
+        /// The format of the worksheet. Defaults to the extension of `--out`.
+        #[clap(long, value_enum)]
+        format: Option<SheetFormat>,
+    },
+    /// Reads a skyscrapers puzzle from a puzz.link or pzv.jp URL.
+    ///
+    /// The header is printed on a single line. When the puzzle gives some numbers in the grid,
+    /// they are printed below it, row by row, empty cells being written as `.`. Clues that are
+    /// missing from the puzzle are written as `0`.
+    Import {
+        /// The URL of the puzzle, such as `https://puzz.link/p?skyscrapers/4/4/...`.
+        url: String,
+    },
+    /// Generates a set of puzzles of several sizes, along with their metadata, as a JSON
+    /// document.
+    ///
+    /// Every puzzle comes with an identifier, its seed, its difficulty, its header and its
+    /// solution. The `i`-th puzzle of every size is generated with the seed `seed + i`. Puzzles
+    /// are written as soon as they are rated, such that an interrupted pack keeps the puzzles
+    /// generated so far.
+    Pack {
+        /// The sizes of the puzzles, such as `4,5,6`.
+        #[clap(long, required = true, value_delimiter = ',', env = "SKYSCRAPPER_SIZE")]
+        sizes: Vec<u8>,
+        /// The number of puzzles of every size.
+        #[clap(long, default_value_t = 10)]
+        per_size: usize,
+        /// Provides the seed of the first puzzle of every size.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
+        seed: Option<u64>,
+        /// Only generates puzzles that have exactly one solution.
+        #[clap(long, action)]
+        unique: bool,
+        /// Writes the pack to the provided file rather than to the standard output.
+        #[clap(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Runs an external solver against generated puzzles, and grades its answers.
+    ///
+    /// Every test generates a header, gives it to the program and checks the board it prints
+    /// on its standard output, which must be formatted like the output of `solve -o solution`.
+    /// Programs that fail, time out or print an invalid board fail the test.
+    Judge {
+        /// The program to judge.
+        program: PathBuf,
+        /// Arguments given to the program before the header, after a `--`.
+        #[clap(last = true)]
+        args: Vec<String>,
+        /// The size of the generated puzzles.
+        #[clap(long, default_value_t = 4, env = "SKYSCRAPPER_SIZE", value_parser = parse_size)]
+        size: u8,
+        /// The number of tests.
+        #[clap(long, default_value_t = 10)]
+        tests: usize,
+        /// Provides the seed of the first puzzle.
+        ///
+        /// The `i`-th puzzle is generated with the seed `seed + i`. Defaults to a random seed.
+        #[clap(long, env = "SKYSCRAPPER_SEED")]
+        seed: Option<u64>,
+        /// Only generates puzzles that have exactly one solution.
+        #[clap(long, action)]
+        unique: bool,
+        /// How the header is given to the program.
+        #[clap(long, value_enum, default_value_t = JudgeInput::Arg)]
+        input: JudgeInput,
+        /// The time the program may take for a single test, in seconds.
+        #[clap(long, value_name = "SECONDS", default_value_t = 10.0)]
+        timeout: f64,
+    },
+    /// Serves the generator, the solver and the checker over HTTP.
+    ///
+    /// The endpoints are `GET /generate?size=N&seed=S&unique=true`, `POST /solve` with
+    /// `{"header":[...]}` and `POST /check` with `{"header":[...],"board":[...]}`. Responses are
+    /// JSON objects.
+    Serve {
+        /// The port to listen on.
+        #[clap(long, default_value_t = 8080)]
+        port: u16,
+        /// The address to listen on.
+        #[clap(long, default_value = "127.0.0.1")]
+        host: String,
+        /// The maximum number of nodes the solver may explore for a single request.
+        #[clap(long, default_value_t = 10_000_000)]
+        node_limit: usize,
+        /// The time the solver may take for a single request, such as `30s` or `500ms`.
+        #[clap(long, default_value = "10s", value_name = "DURATION", value_parser = parse_duration)]
+        timeout: Duration,
+        /// The largest size of the puzzles that may be generated or solved.
+        #[clap(long, default_value_t = 9, value_parser = parse_size)]
+        max_size: u8,
+        /// The number of threads handling requests.
+        ///
+        /// Defaults to the number of available CPUs.
+        #[clap(long, short = 'j')]
+        workers: Option<NonZeroUsize>,
+    },
+    /// Processes many puzzles read from a file in a single run.
+    Batch {
+        /// The selected subcommand.
+        #[clap(subcommand)]
+        command: BatchCommand,
+    },
+    /// Ranks the clues of a header by importance.
+    ///
+    /// Every clue is removed in turn, and the number of solutions of the remaining puzzle is
+    /// reported along with the number of cells that those solutions don't agree on. Clues that
+    /// leave no cell undetermined can be hidden without losing uniqueness.
+    AnalyzeClues {
+        /// The header of the puzzle.
+        header: Header,
+        /// The maximum number of solutions enumerated for each clue.
+        #[clap(long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// Prints the deductions that lead to the solution of a header, one per line.
+    ///
+    /// The clues are read first, then cells are filled when they have a single candidate left,
+    /// or when a value has a single place left in a row or a column. Guesses are only made when
+    /// no such deduction is possible, and the deductions that depend on them are indented.
+    Explain {
+        /// The header of the puzzle.
+        header: Header,
+        /// The maximum number of deductions printed.
+        #[clap(long, default_value_t = 10000)]
+        max_steps: usize,
+    },
+    /// Steps through the deductions of the solver interactively.
+    ///
+    /// Commands are read from the standard input, one per line. Type `help` to list them.
+    Debug {
+        /// The header of the puzzle.
+        header: Header,
+    },
+    /// Generates a puzzle and lets you solve it interactively in the terminal.
+    ///
+    /// A given puzzle may be played instead, such that several players can compete on the same
+    /// board, or a saved puzzle can be played again. Clues turn green once their line is correct,
+    /// and red as soon as they can't be satisfied anymore. The keys are listed below the board.
+    Play(PlayArgs),
+    /// Prints a stable hash identifying a puzzle.
+    ///
+    /// Puzzles that are rotations or reflections of each other have the same hash. The hash does
+    /// not depend on the platform nor on the version of the program.
+    Hash {
+        /// The header of the puzzle.
+        header: Header,
+        /// Also hashes the board provided in the standard input.
+        ///
+        /// The board must be a valid solution of the header.
+        #[clap(long, action)]
+        board: bool,
+    },
+    /// Decrypts a file written by `generate --encrypt`, and prints its content.
+    ///
+    /// Compressed files are printed as-is, and must be decompressed afterwards.
+    Decrypt {
+        /// The encrypted file.
+        file: PathBuf,
+        /// The passphrase used to encrypt the file.
+        #[clap(long, value_name = "PASSPHRASE")]
+        passphrase: String,
+    },
+    /// Determines whether a signature was produced for a given header and solution.
+    ///
+    /// This command expects the solution to be provided without its header in its standard
+    /// input.
+    VerifySignature {
+        /// The header of the puzzle.
         header: Header,
+        /// The key that was used to sign the puzzle.
+        #[clap(
+            long,
+            value_name = "KEY",
+            env = "SKYSCRAPPER_SIGN_KEY",
+            hide_env_values = true
+        )]
+        sign_key: String,
+        /// The signature, as written by `generate --sign-key`.
+        #[clap(long)]
+        signature: String,
     },
 }
 
-/// An error that might occur whilst parsing a [`Header`] instance.
+/// An error that might occur whilst parsing a [`SeedRange`] or a [`SizeRange`] instance.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ParseHeaderError {
+pub enum ParseRangeError {
     InvalidInteger,
-    InvalidViewCount,
-    TooManyViews,
-    ViewTooLarge,
-    ViewZero,
+    MissingSeparator,
+    Empty,
+    SizeTooLarge,
 }
 
-impl From<std::num::ParseIntError> for ParseHeaderError {
-    fn from(e: std::num::ParseIntError) -> Self {
-        use std::num::IntErrorKind::*;
-
-        if *e.kind() == PosOverflow {
-            Self::ViewTooLarge
-        } else {
-            Self::InvalidInteger
-        }
+impl From<std::num::ParseIntError> for ParseRangeError {
+    fn from(_: std::num::ParseIntError) -> Self {
+        Self::InvalidInteger
     }
 }
 
-impl Display for ParseHeaderError {
-    #[rustfmt::skip]
+impl Display for ParseRangeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::InvalidInteger => f.write_str("invalid integer found in header"),
-            Self::InvalidViewCount => f.write_str("invalid number of views (must be a multiple of 4)"),
-            Self::TooManyViews => f.write_str("it's not possible to solve a size larger than 255"),
-            Self::ViewTooLarge => f.write_str("views can't exceed the size of the board"),
-            Self::ViewZero => f.write_str("views can't be 0"),
+            Self::InvalidInteger => f.write_str("invalid integer found in range"),
+            Self::MissingSeparator => f.write_str("expected a range such as `0..100`"),
+            Self::Empty => f.write_str("the range is empty"),
+            Self::SizeTooLarge => write!(
+                f,
+                "it's not possible to solve a size larger than {MAX_SIZE}"
+            ),
         }
     }
 }
 
-impl std::error::Error for ParseHeaderError {}
+impl std::error::Error for ParseRangeError {}
+
+/// Parses a non-empty range from `start..end` (excluded) or `start..=end` (included).
+fn parse_range(s: &str) -> Result<std::ops::RangeInclusive<u64>, ParseRangeError> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or(ParseRangeError::MissingSeparator)?;
+    let start: u64 = start.trim().parse()?;
+
+    let end = match end.strip_prefix('=') {
+        Some(end) => end.trim().parse()?,
+        None => end
+            .trim()
+            .parse::<u64>()?
+            .checked_sub(1)
+            .ok_or(ParseRangeError::Empty)?,
+    };
+
+    if start > end {
+        return Err(ParseRangeError::Empty);
+    }
+
+    Ok(start..=end)
+}
 
-/// A simple wrapper around [`Box<[u8]>`] that gets parsed like a skyscrapper header line through
-/// its [`FromStr`] implementation.
+/// A non-empty range of seeds, parsed from `start..end` (excluded) or `start..=end` (included).
 #[derive(Clone, Debug)]
-pub struct Header(pub Box<[u8]>);
-
-// A string representing a "header" must follow the following properties:
-//
-// It's a space-separated list of numbers. The number of elements in that list must be divisible
-// by 4.
-//
-// Let call "n" the quarter of that size. Each element of the list must be between 1 and n
-// (included). n must fit in a u8.
-impl FromStr for Header {
-    type Err = ParseHeaderError;
+pub struct SeedRange(pub std::ops::RangeInclusive<u64>);
+
+impl FromStr for SeedRange {
+    type Err = ParseRangeError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut vec = Vec::new();
+        parse_range(s).map(SeedRange)
+    }
+}
 
-        // FIXME(nils): use try_collect() when stable.
-        for word in s.split_ascii_whitespace() {
-            let view = word.parse()?;
-            if view == 0 {
-                return Err(ParseHeaderError::ViewZero);
+/// A non-empty range of sizes, parsed from a single size, `start..end` (excluded) or
+/// `start..=end` (included).
+#[derive(Clone, Debug)]
+pub struct SizeRange(pub std::ops::RangeInclusive<u8>);
+
+impl FromStr for SizeRange {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = match s.contains("..") {
+            true => parse_range(s)?,
+            false => {
+                let size = s.trim().parse()?;
+                size..=size
             }
-            vec.push(view);
+        };
+
+        if *range.end() > MAX_SIZE as u64 {
+            return Err(ParseRangeError::SizeTooLarge);
+        }
+        Ok(SizeRange(*range.start() as u8..=*range.end() as u8))
+    }
+}
+
+/// A calendar date, parsed from `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Returns the current date, in UTC.
+    pub fn today() -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        // Converts a number of days since 1970-01-01 into a date of the proleptic Gregorian
+        // calendar (http://howardhinnant.github.io/date_algorithms.html#civil_from_days).
+        let days = secs / 86400 + 719468;
+        let era = days / 146097;
+        let doe = days % 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + u64::from(month <= 2);
+
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Returns the seed of the daily puzzle of the given size.
+    pub fn seed(self, size: u8) -> u64 {
+        let date = u64::from(self.year) * 10000 + u64::from(self.month) * 100 + u64::from(self.day);
+        date * 256 + u64::from(size)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// An error which might occur whilst parsing a [`Date`].
+#[derive(Debug, Clone)]
+pub enum ParseDateError {
+    /// The date is not written as `YYYY-MM-DD`.
+    InvalidFormat,
+    /// The month or the day does not exist.
+    OutOfRange,
+}
+
+impl Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => f.write_str("expected a date such as `2024-05-01`"),
+            Self::OutOfRange => f.write_str("this day does not exist"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('-');
+        let mut next = |len: usize| {
+            parts
+                .next()
+                .filter(|p| p.len() == len && p.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|p| p.parse().ok())
+                .ok_or(ParseDateError::InvalidFormat)
+        };
+
+        let year: u16 = next(4)?;
+        let month = next(2)? as u8;
+        let day = next(2)? as u8;
+        if parts.next().is_some() {
+            return Err(ParseDateError::InvalidFormat);
         }
 
-        if vec.len() % 4 != 0 {
-            return Err(ParseHeaderError::InvalidViewCount);
+        let leap =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return Err(ParseDateError::OutOfRange),
+        };
+        if day == 0 || day > days {
+            return Err(ParseDateError::OutOfRange);
+        }
+
+        Ok(Self { year, month, day })
+    }
+}
+
+/// A header given on the command line.
+#[derive(Debug, Clone)]
+pub enum HeaderSource {
+    /// The header itself.
+    Header(Header),
+    /// A header of `-`, to be read from the standard input.
+    ///
+    /// The standard input is only read once the arguments have been parsed successfully, such
+    /// that an invalid command line does not wait for it.
+    Stdin,
+}
+
+impl HeaderSource {
+    /// Returns the header, reading it from the next non-empty line of the standard input when
+    /// needed.
+    ///
+    /// Only that line is consumed, such that the rest of the standard input can still be read
+    /// afterwards.
+    pub fn read(self) -> Result<Header, String> {
+        if let Self::Header(header) = self {
+            return Ok(header);
         }
 
-        if vec.len() > 255 * 4 {
-            return Err(ParseHeaderError::TooManyViews);
+        let mut line = String::new();
+        while line.trim().is_empty() {
+            line.clear();
+            match std::io::stdin().read_line(&mut line) {
+                Ok(0) => return Err("the standard input holds no header".into()),
+                Ok(_) => (),
+                Err(err) => return Err(format!("failed to read the standard input: {err}")),
+            }
         }
 
-        let size = (vec.len() / 4) as u8;
+        line.parse()
+            .map_err(|err: ParseHeaderError| format!("invalid header on the standard input: {err}"))
+    }
+}
+
+/// Parses a header given on the command line, `-` standing for the standard input.
+fn parse_header(s: &str) -> Result<HeaderSource, String> {
+    match s {
+        "-" => Ok(HeaderSource::Stdin),
+        s => s
+            .parse()
+            .map(HeaderSource::Header)
+            .map_err(|err: ParseHeaderError| err.to_string()),
+    }
+}
+
+/// A configuration of the solver, as compared by `bench --ab`.
+#[derive(Debug, Clone)]
+pub struct SolverConfig {
+    /// The configuration, as written on the command line.
+    pub name: String,
+    /// The algorithm used to solve the puzzles.
+    pub algorithm: Algorithm,
+    /// The way the backtracking search picks the cell to branch on.
+    pub heuristic: Heuristic,
+}
 
-        if vec.iter().any(|&v| v > size) {
-            return Err(ParseHeaderError::ViewTooLarge);
+/// Parses a [`SolverConfig`] made of `key=value` settings separated by commas.
+fn parse_solver_config(s: &str) -> Result<SolverConfig, String> {
+    let mut config = SolverConfig {
+        name: s.to_owned(),
+        algorithm: Algorithm::Backtracking,
+        heuristic: Heuristic::default(),
+    };
+
+    for setting in s.split(',').filter(|setting| !setting.trim().is_empty()) {
+        let (key, value) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("expected `key=value`, found `{setting}`"))?;
+        let (key, value) = (key.trim(), value.trim());
+        let invalid = |_| format!("invalid {key} `{value}`");
+        match key {
+            "algorithm" => config.algorithm = ValueEnum::from_str(value, true).map_err(invalid)?,
+            "heuristic" => config.heuristic = ValueEnum::from_str(value, true).map_err(invalid)?,
+            key => {
+                return Err(format!(
+                    "unknown setting `{key}`, expected `algorithm` or `heuristic`"
+                ))
+            }
         }
+    }
+
+    Ok(config)
+}
 
-        Ok(Header(vec.into_boxed_slice()))
+/// Parses the size of a board, which the solver must support.
+fn parse_size(s: &str) -> Result<u8, String> {
+    let size: u8 = s.parse().map_err(|err| format!("{err}"))?;
+    if size as usize > MAX_SIZE {
+        return Err(format!(
+            "it's not possible to solve a size larger than {MAX_SIZE}"
+        ));
     }
+    Ok(size)
+}
+
+/// Parses a duration such as `30s`, `500ms`, `2m` or `1h`, a number without unit being a number
+/// of seconds.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("`{s}` is not a duration, such as `30s` or `500ms`"))?;
+    let scale = match unit.trim() {
+        "ms" => 0.001,
+        "" | "s" => 1.0,
+        "m" | "min" => 60.0,
+        "h" => 3600.0,
+        unit => {
+            return Err(format!(
+                "unknown unit `{unit}`, expected `ms`, `s`, `m` or `h`"
+            ))
+        }
+    };
+
+    Duration::try_from_secs_f64(number * scale).map_err(|err| err.to_string())
+}
+
+/// Reads a header from the file at `path`.
+fn read_header_file(path: &str) -> Result<Header, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|err| format!("failed to read `{path}`: {err}"))?;
+    text.parse()
+        .map_err(|err: ParseHeaderError| err.to_string())
 }
 
 /// Parses the arguments passed to the program and parses then into an instance of [`Args`]. If an
@@ -152,12 +1599,23 @@ impl FromStr for Header {
 ///
 /// In case of error, the values currently leaving on the stack will *not* be dropped.
 pub fn parse() -> Args {
-    match Parser::try_parse() {
+    let mut args: Args = match Parser::try_parse() {
         Ok(ok) => ok,
         Err(err) => {
             // If an error occur whilst printing, there is not much we can do about it.
             let _ = err.print();
             std::process::exit(2);
         }
+    };
+
+    // Headers read from a file are used like the ones given on the command line, which they
+    // conflict with. The header file of `solve` may be encrypted, and is read along with its
+    // givens.
+    if let Command::Check(args) = &mut args.command {
+        if let Some(header) = args.header_file.take() {
+            args.header = Some(HeaderSource::Header(header));
+        }
     }
+
+    args
 }
@@ -0,0 +1,505 @@
+//! A timing harness for the solver, used by the `bench` subcommand.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rand::RngCore;
+use serde::Deserialize;
+
+use skyscrapper::args::{Algorithm, SolverConfig};
+use skyscrapper::board::Header;
+use skyscrapper::{generate, ilp, solve};
+
+/// The measures taken for the boards of a single size.
+pub struct Measures {
+    /// The size of the boards.
+    pub size: u8,
+    /// The time needed to solve every board.
+    pub times: Vec<Duration>,
+    /// The number of nodes the solver explored for every board.
+    pub nodes: Vec<usize>,
+}
+
+/// Generates and solves `iterations` boards of size `size`, measuring every solve.
+///
+/// When the operation is interrupted, the measures taken so far are returned along with `false`.
+pub fn run(rng: &mut dyn RngCore, size: u8, iterations: usize) -> (Measures, bool) {
+    let mut measures = Measures {
+        size,
+        times: Vec::with_capacity(iterations),
+        nodes: Vec::with_capacity(iterations),
+    };
+
+    for _ in 0..iterations {
+        let header = match generate::generate_solution(rng, size) {
+            Ok(solution) => generate::solution_to_header(&solution),
+            Err(_) => return (measures, false),
+        };
+
+        let mut stats = solve::Stats::default();
+        let start = Instant::now();
+        if solve::solve_with_stats(&header, Default::default(), &mut stats).is_err() {
+            // Headers produced by the generator always have a solution.
+            return (measures, false);
+        }
+        measures.times.push(start.elapsed());
+        measures.nodes.push(stats.steps);
+    }
+
+    (measures, true)
+}
+
+/// A puzzle of a corpus.
+#[derive(Deserialize)]
+pub struct CorpusEntry {
+    /// The identifier of the puzzle, when the corpus provides one.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// The clues of the puzzle.
+    pub header: Header,
+}
+
+/// A pack, as written by the `pack` subcommand.
+#[derive(Deserialize)]
+struct Pack {
+    puzzles: Vec<CorpusEntry>,
+}
+
+/// Reads a corpus of puzzles: a pack written by the `pack` subcommand, or a JSON Lines document
+/// with one puzzle per line. Every puzzle is an object with a `header` field and an optional `id`,
+/// the other fields being ignored.
+///
+/// On error, the number of the offending line is returned along with the error, starting at 1.
+pub fn read_corpus(text: &str) -> Result<Vec<CorpusEntry>, (usize, serde_json::Error)> {
+    if let Ok(pack) = serde_json::from_str::<Pack>(text) {
+        return Ok(pack.puzzles);
+    }
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|err| (i + 1, err)))
+        .collect()
+}
+
+/// The outcome of solving a single puzzle.
+#[derive(Clone, Copy)]
+pub struct Run {
+    /// The time needed to solve the puzzle.
+    pub time: Duration,
+    /// The number of nodes explored, for the backtracking search.
+    pub nodes: Option<usize>,
+}
+
+/// Solves `header` with `algorithm`, giving up after `timeout` when provided. `heuristic` is
+/// only used by the backtracking search.
+///
+/// `None` is returned when the puzzle could not be solved. The only error is
+/// [`solve::SolutionError::Interrupted`].
+fn measure(
+    algorithm: Algorithm,
+    heuristic: solve::Heuristic,
+    header: &Header,
+    timeout: Option<Duration>,
+) -> Result<Option<Run>, solve::SolutionError> {
+    let limits = solve::Limits {
+        deadline: timeout.map(|timeout| Instant::now() + timeout),
+        heuristic,
+        ..Default::default()
+    };
+    let mut stats = solve::Stats::default();
+
+    let start = Instant::now();
+    let res = match algorithm {
+        Algorithm::Backtracking => solve::solve_with_stats(header, limits, &mut stats),
+        Algorithm::Ilp => ilp::solve(header),
+        #[cfg(feature = "sat")]
+        Algorithm::Sat => skyscrapper::sat::solve(header, limits),
+    };
+    let time = start.elapsed();
+
+    match res {
+        Ok(_) => Ok(Some(Run {
+            time,
+            nodes: (algorithm == Algorithm::Backtracking).then_some(stats.steps),
+        })),
+        Err(solve::SolutionError::Interrupted) => Err(solve::SolutionError::Interrupted),
+        Err(_) => Ok(None),
+    }
+}
+
+/// The measures taken for a single engine over a corpus.
+pub struct EngineMeasures {
+    /// The engine.
+    pub algorithm: Algorithm,
+    /// The time needed to solve every puzzle that did not fail.
+    pub times: Vec<Duration>,
+    /// The number of nodes explored for every puzzle that did not fail. This is empty for the
+    /// engines that do not explore a search tree.
+    pub nodes: Vec<usize>,
+    /// The number of puzzles the engine could not solve, or not in time.
+    pub failures: usize,
+}
+
+/// Solves every puzzle of `corpus` with every engine of `algorithms`, one engine after the
+/// other. Each puzzle is given `timeout` to be solved, when provided.
+///
+/// When the operation is interrupted, the measures taken so far are returned along with `false`.
+pub fn compare(
+    corpus: &[CorpusEntry],
+    algorithms: &[Algorithm],
+    timeout: Option<Duration>,
+) -> (Vec<EngineMeasures>, bool) {
+    let mut all = Vec::with_capacity(algorithms.len());
+
+    for &algorithm in algorithms {
+        all.push(EngineMeasures {
+            algorithm,
+            times: Vec::with_capacity(corpus.len()),
+            nodes: Vec::new(),
+            failures: 0,
+        });
+        let measures = all.last_mut().unwrap();
+
+        for entry in corpus {
+            match measure(algorithm, Default::default(), &entry.header, timeout) {
+                Ok(Some(run)) => {
+                    measures.times.push(run.time);
+                    measures.nodes.extend(run.nodes);
+                }
+                Ok(None) => measures.failures += 1,
+                Err(_) => return (all, false),
+            }
+        }
+    }
+
+    (all, true)
+}
+
+/// The runs of a puzzle under the configurations A and B of `bench --ab`, a run being `None`
+/// when the puzzle could not be solved.
+pub type RunPair = (Option<Run>, Option<Run>);
+
+/// Solves every puzzle of `corpus` under the configurations `a` and `b`.
+///
+/// Both configurations solve a puzzle before moving on to the next one, such that a change in the
+/// speed of the machine affects both of them. A goes first on the even puzzles and B on the odd
+/// ones, such that neither benefits from the caches warmed up by the other.
+///
+/// When the operation is interrupted, the runs made so far are returned along with `false`.
+pub fn ab(
+    corpus: &[CorpusEntry],
+    a: &SolverConfig,
+    b: &SolverConfig,
+    timeout: Option<Duration>,
+) -> (Vec<RunPair>, bool) {
+    let mut runs = Vec::with_capacity(corpus.len());
+
+    for (i, entry) in corpus.iter().enumerate() {
+        let run = |config: &SolverConfig| {
+            measure(config.algorithm, config.heuristic, &entry.header, timeout)
+        };
+        let pair = match i % 2 {
+            0 => {
+                let a = run(a);
+                (a, run(b))
+            }
+            _ => {
+                let b = run(b);
+                (run(a), b)
+            }
+        };
+        match pair {
+            (Ok(a), Ok(b)) => runs.push((a, b)),
+            _ => return (runs, false),
+        }
+    }
+
+    (runs, true)
+}
+
+/// Returns the two-sided p-value of the sign test, given the number of pairs in which one
+/// configuration did better and the number of pairs in which it did worse.
+///
+/// This is the probability of a split at least as uneven when both configurations are equally
+/// likely to do better.
+fn sign_test(better: usize, worse: usize) -> f64 {
+    let n = better + worse;
+    if n == 0 {
+        return 1.0;
+    }
+
+    // The terms of the binomial distribution are computed as logarithms, as `0.5^n` underflows
+    // for large corpora.
+    let mut ln_term = n as f64 * 0.5f64.ln();
+    let mut tail = 0.0;
+    for k in 0..=better.min(worse) {
+        tail += ln_term.exp();
+        ln_term += ((n - k) as f64).ln() - ((k + 1) as f64).ln();
+    }
+
+    (2.0 * tail).min(1.0)
+}
+
+/// Returns the relative difference between `a` and `b`, as a percentage of `a`.
+fn delta(a: f64, b: f64) -> String {
+    match a == 0.0 {
+        true if b == 0.0 => "+0.0%".into(),
+        true => "-".into(),
+        false => format!("{:+.1}%", (b - a) / a * 100.0),
+    }
+}
+
+/// Writes the report of `bench --ab`: the runs of every puzzle, the aggregates of both
+/// configurations over the puzzles they both solved, and whether their differences are
+/// significant. Times are written in milliseconds.
+pub fn write_ab(
+    w: &mut dyn Write,
+    corpus: &[CorpusEntry],
+    a: &SolverConfig,
+    b: &SolverConfig,
+    runs: &[RunPair],
+) -> io::Result<()> {
+    let ms = |d: Duration| format!("{:.3} ms", d.as_secs_f64() * 1000.0);
+    let time = |run: Option<Run>| run.map_or_else(|| "failed".into(), |run| ms(run.time));
+    let nodes = |run: Option<Run>| {
+        run.and_then(|run| run.nodes)
+            .map_or_else(|| "-".into(), |nodes| nodes.to_string())
+    };
+
+    writeln!(w, "A: {}", a.name)?;
+    writeln!(w, "B: {}", b.name)?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "{:<12} {:>11} {:>11} {:>8} {:>10} {:>10} {:>8}",
+        "puzzle", "A time", "B time", "delta", "A nodes", "B nodes", "delta",
+    )?;
+
+    for (i, (entry, &(run_a, run_b))) in corpus.iter().zip(runs).enumerate() {
+        let id = entry.id.clone().unwrap_or_else(|| format!("#{}", i + 1));
+        let (time_delta, nodes_delta) = match (run_a, run_b) {
+            (Some(run_a), Some(run_b)) => (
+                delta(run_a.time.as_secs_f64(), run_b.time.as_secs_f64()),
+                match (run_a.nodes, run_b.nodes) {
+                    (Some(x), Some(y)) => delta(x as f64, y as f64),
+                    _ => "-".into(),
+                },
+            ),
+            _ => ("-".into(), "-".into()),
+        };
+
+        writeln!(
+            w,
+            "{:<12} {:>11} {:>11} {:>8} {:>10} {:>10} {:>8}",
+            id,
+            time(run_a),
+            time(run_b),
+            time_delta,
+            nodes(run_a),
+            nodes(run_b),
+            nodes_delta,
+        )?;
+    }
+
+    // The aggregates only account for the puzzles that both configurations solved, such that
+    // they are compared on the same puzzles.
+    let solved: Vec<(Run, Run)> = runs
+        .iter()
+        .filter_map(|&(run_a, run_b)| Some((run_a?, run_b?)))
+        .collect();
+    let failures_a = runs.iter().filter(|(run_a, _)| run_a.is_none()).count();
+    let failures_b = runs.iter().filter(|(_, run_b)| run_b.is_none()).count();
+
+    writeln!(w)?;
+    writeln!(w, "{:<12} {:>11} {:>11} {:>8}", "", "A", "B", "delta")?;
+    writeln!(
+        w,
+        "{:<12} {:>11} {:>11}",
+        "failures", failures_a, failures_b
+    )?;
+    if solved.is_empty() {
+        return Ok(());
+    }
+
+    let mut times_a: Vec<Duration> = solved.iter().map(|(run_a, _)| run_a.time).collect();
+    let mut times_b: Vec<Duration> = solved.iter().map(|(_, run_b)| run_b.time).collect();
+    times_a.sort_unstable();
+    times_b.sort_unstable();
+    let mean = |times: &[Duration]| times.iter().sum::<Duration>().div_f64(times.len() as f64);
+    for (name, x, y) in [
+        ("mean time", mean(&times_a), mean(&times_b)),
+        (
+            "median time",
+            percentile(&times_a, 0.5),
+            percentile(&times_b, 0.5),
+        ),
+        (
+            "p95 time",
+            percentile(&times_a, 0.95),
+            percentile(&times_b, 0.95),
+        ),
+    ] {
+        writeln!(
+            w,
+            "{:<12} {:>11} {:>11} {:>8}",
+            name,
+            ms(x),
+            ms(y),
+            delta(x.as_secs_f64(), y.as_secs_f64()),
+        )?;
+    }
+
+    let node_pairs: Vec<(usize, usize)> = solved
+        .iter()
+        .filter_map(|(run_a, run_b)| Some((run_a.nodes?, run_b.nodes?)))
+        .collect();
+    if !node_pairs.is_empty() {
+        let mut nodes_a: Vec<usize> = node_pairs.iter().map(|&(x, _)| x).collect();
+        let mut nodes_b: Vec<usize> = node_pairs.iter().map(|&(_, y)| y).collect();
+        nodes_a.sort_unstable();
+        nodes_b.sort_unstable();
+        let mean = |nodes: &[usize]| nodes.iter().sum::<usize>() as f64 / nodes.len() as f64;
+        for (name, x, y) in [
+            ("mean nodes", mean(&nodes_a), mean(&nodes_b)),
+            (
+                "median nodes",
+                percentile(&nodes_a, 0.5) as f64,
+                percentile(&nodes_b, 0.5) as f64,
+            ),
+        ] {
+            writeln!(w, "{:<12} {:>11.1} {:>11.1} {:>8}", name, x, y, delta(x, y),)?;
+        }
+    }
+
+    // Ties carry no information about which configuration is better, and are left out.
+    writeln!(w)?;
+    let faster = solved.iter().filter(|(x, y)| y.time < x.time).count();
+    let slower = solved.iter().filter(|(x, y)| y.time > x.time).count();
+    write_significance(w, "time", faster, slower)?;
+    if !node_pairs.is_empty() {
+        let fewer = node_pairs.iter().filter(|(x, y)| y < x).count();
+        let more = node_pairs.iter().filter(|(x, y)| y > x).count();
+        write_significance(w, "nodes", fewer, more)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the result of the sign test of a measure, `better` and `worse` being the number of
+/// puzzles on which B did better and worse than A.
+fn write_significance(
+    w: &mut dyn Write,
+    measure: &str,
+    better: usize,
+    worse: usize,
+) -> io::Result<()> {
+    let p = sign_test(better, worse);
+    let verdict = match p < 0.05 {
+        true => "significant",
+        false => "not significant",
+    };
+    let p = match p < 0.0001 {
+        true => "p < 0.0001".into(),
+        false => format!("p = {p:.4}"),
+    };
+    writeln!(
+        w,
+        "{measure}: B is better on {better} puzzles and worse on {worse}, {p} ({verdict} at the 5% level, sign test)",
+    )
+}
+
+/// Returns the value at quantile `q` of the sorted slice `sorted`.
+fn percentile<T: Copy>(sorted: &[T], q: f64) -> T {
+    sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+/// Writes the measures of every size as an aligned table. Times are written in milliseconds.
+pub fn write_table(w: &mut dyn Write, measures: &[Measures]) -> io::Result<()> {
+    writeln!(
+        w,
+        "{:>4} {:>5} {:>11} {:>11} {:>11} {:>10} {:>10} {:>10}",
+        "size", "runs", "min", "median", "p95", "min nodes", "median", "p95",
+    )?;
+
+    for measures in measures {
+        if measures.times.is_empty() {
+            continue;
+        }
+
+        let mut times = measures.times.clone();
+        times.sort_unstable();
+        let mut nodes = measures.nodes.clone();
+        nodes.sort_unstable();
+
+        let ms = |d: Duration| format!("{:.3} ms", d.as_secs_f64() * 1000.0);
+        writeln!(
+            w,
+            "{:>4} {:>5} {:>11} {:>11} {:>11} {:>10} {:>10} {:>10}",
+            measures.size,
+            times.len(),
+            ms(times[0]),
+            ms(percentile(&times, 0.5)),
+            ms(percentile(&times, 0.95)),
+            nodes[0],
+            percentile(&nodes, 0.5),
+            percentile(&nodes, 0.95),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes the measures of every engine as an aligned table. Times are written in milliseconds.
+///
+/// The puzzles an engine failed to solve are only counted in the `failures` column.
+pub fn write_comparison(w: &mut dyn Write, measures: &[EngineMeasures]) -> io::Result<()> {
+    writeln!(
+        w,
+        "{:<12} {:>8} {:>11} {:>11} {:>11} {:>10} {:>10} {:>10}",
+        "engine", "failures", "mean", "median", "p95", "mean nodes", "median", "p95",
+    )?;
+
+    for measures in measures {
+        let mut times = measures.times.clone();
+        times.sort_unstable();
+        let mut nodes = measures.nodes.clone();
+        nodes.sort_unstable();
+
+        let ms = |d: Duration| format!("{:.3} ms", d.as_secs_f64() * 1000.0);
+        let (mean, median, p95) = match times.is_empty() {
+            true => ("-".into(), "-".into(), "-".into()),
+            false => (
+                ms(times.iter().sum::<Duration>().div_f64(times.len() as f64)),
+                ms(percentile(&times, 0.5)),
+                ms(percentile(&times, 0.95)),
+            ),
+        };
+        let (mean_nodes, median_nodes, p95_nodes) = match nodes.is_empty() {
+            true => ("-".into(), "-".into(), "-".into()),
+            false => (
+                format!(
+                    "{:.1}",
+                    nodes.iter().sum::<usize>() as f64 / nodes.len() as f64
+                ),
+                percentile(&nodes, 0.5).to_string(),
+                percentile(&nodes, 0.95).to_string(),
+            ),
+        };
+
+        writeln!(
+            w,
+            "{:<12} {:>8} {:>11} {:>11} {:>11} {:>10} {:>10} {:>10}",
+            measures.algorithm.name(),
+            measures.failures,
+            mean,
+            median,
+            p95,
+            mean_nodes,
+            median_nodes,
+            p95_nodes,
+        )?;
+    }
+
+    Ok(())
+}
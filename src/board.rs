@@ -0,0 +1,355 @@
+//! The [`Board`] and [`Header`] types, which take care of the index math of the flat arrays used
+//! to store boards and clues.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Display;
+use core::ops::Deref;
+use core::str::FromStr;
+
+/// A square board, storing the height of every cell in row-major order.
+///
+/// A height of `0` stands for a cell that is not known yet.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Board {
+    /// The size of the board.
+    size: usize,
+    /// The heights of the cells, `size * size` of them.
+    cells: Box<[u8]>,
+}
+
+impl Board {
+    /// Creates a new [`Board`] of size `size` from its cells, in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there isn't exactly `size * size` cells.
+    pub fn new(size: usize, cells: Box<[u8]>) -> Self {
+        assert_eq!(
+            cells.len(),
+            size * size,
+            "a board must have `size * size` cells"
+        );
+        Self { size, cells }
+    }
+
+    /// Creates a new [`Board`] of size `size` whose cells are all `0`.
+    pub fn empty(size: usize) -> Self {
+        Self::new(size, core::iter::repeat_n(0, size * size).collect())
+    }
+
+    /// Returns the size of the board.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the height of the cell at `(x, y)`.
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(x < self.size && y < self.size);
+        self.cells[x + y * self.size]
+    }
+
+    /// Sets the height of the cell at `(x, y)`.
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        assert!(x < self.size && y < self.size);
+        self.cells[x + y * self.size] = value;
+    }
+
+    /// Returns the cells of the row `y`, from left to right.
+    pub fn row(&self, y: usize) -> &[u8] {
+        &self.cells[y * self.size..(y + 1) * self.size]
+    }
+
+    /// Returns the cells of the column `x`, from top to bottom.
+    pub fn col(&self, x: usize) -> impl DoubleEndedIterator<Item = u8> + '_ {
+        assert!(x < self.size);
+        self.cells.iter().skip(x).step_by(self.size).copied()
+    }
+
+    /// Returns the rows of the board, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.cells.chunks_exact(self.size)
+    }
+
+    /// Returns the columns of the board, from left to right.
+    pub fn cols(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = u8> + '_> {
+        (0..self.size).map(|x| self.col(x))
+    }
+
+    /// Returns the cells of the board, in row-major order.
+    #[inline]
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// Returns the cells of the board, in row-major order.
+    #[inline]
+    pub fn cells_mut(&mut self) -> &mut [u8] {
+        &mut self.cells
+    }
+
+    /// Returns the cells of the board, in row-major order.
+    #[inline]
+    pub fn into_cells(self) -> Box<[u8]> {
+        self.cells
+    }
+}
+
+impl Deref for Board {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.cells
+    }
+}
+
+// A board is serialized as the flat list of its cells, in row-major order. Its size is the square
+// root of the length of that list.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.cells.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let cells = Box::<[u8]>::deserialize(deserializer)?;
+        let size = cells.len().isqrt();
+
+        if size * size != cells.len() {
+            return Err(D::Error::custom(
+                "the number of cells must be a perfect square",
+            ));
+        }
+        if size > 255 {
+            return Err(D::Error::custom(
+                "it's not possible to solve a size larger than 255",
+            ));
+        }
+        if cells.iter().any(|&v| v as usize > size) {
+            return Err(D::Error::custom("cells can't exceed the size of the board"));
+        }
+
+        Ok(Board::new(size, cells))
+    }
+}
+
+/// The largest size of a header.
+///
+/// The solver stores the values allowed for every cell in a single `u64`.
+pub const MAX_SIZE: usize = 64;
+
+/// The clues surrounding a board.
+///
+/// The clues seen from the top, the bottom, the left and the right of the board are stored in
+/// that order. The top and bottom clues are listed from left to right, and the left and right
+/// clues from top to bottom. A clue of `0` is missing.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Header {
+    /// The size of the board.
+    size: usize,
+    /// The clues, `size * 4` of them.
+    clues: Box<[u8]>,
+}
+
+impl Header {
+    /// Creates a new [`Header`] from its clues.
+    ///
+    /// Clues that do not come from the program itself should go through [`Header::try_from`]
+    /// instead, which reports invalid headers as errors.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of clues is not a multiple of 4, or if the size of the
+    /// board exceeds [`MAX_SIZE`].
+    pub fn new(clues: Box<[u8]>) -> Self {
+        assert!(
+            clues.len().is_multiple_of(4),
+            "a header must have `size * 4` clues"
+        );
+        assert!(
+            clues.len() <= MAX_SIZE * 4,
+            "a header can't describe a board larger than {MAX_SIZE}"
+        );
+        Self {
+            size: clues.len() / 4,
+            clues,
+        }
+    }
+
+    /// Creates a new [`Header`] for a board of size `size`, with every clue missing.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `size` exceeds [`MAX_SIZE`].
+    pub fn empty(size: usize) -> Self {
+        Self::new(core::iter::repeat_n(0, size * 4).collect())
+    }
+
+    /// Returns the size of the board.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the clues seen from the top of the board, from left to right.
+    #[inline]
+    pub fn top(&self) -> &[u8] {
+        &self.clues[..self.size]
+    }
+
+    /// Returns the clues seen from the bottom of the board, from left to right.
+    #[inline]
+    pub fn bottom(&self) -> &[u8] {
+        &self.clues[self.size..2 * self.size]
+    }
+
+    /// Returns the clues seen from the left of the board, from top to bottom.
+    #[inline]
+    pub fn left(&self) -> &[u8] {
+        &self.clues[2 * self.size..3 * self.size]
+    }
+
+    /// Returns the clues seen from the right of the board, from top to bottom.
+    #[inline]
+    pub fn right(&self) -> &[u8] {
+        &self.clues[3 * self.size..]
+    }
+
+    /// Returns every clue, in the order described in the documentation of [`Header`].
+    #[inline]
+    pub fn clues(&self) -> &[u8] {
+        &self.clues
+    }
+
+    /// Returns every clue, in the order described in the documentation of [`Header`].
+    #[inline]
+    pub fn into_clues(self) -> Box<[u8]> {
+        self.clues
+    }
+}
+
+impl Deref for Header {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.clues
+    }
+}
+
+impl TryFrom<Vec<u8>> for Header {
+    type Error = ParseHeaderError;
+
+    /// Creates a new [`Header`] from its clues, checking that they make a valid header.
+    fn try_from(clues: Vec<u8>) -> Result<Self, Self::Error> {
+        check_clues(&clues)?;
+        Ok(Header::new(clues.into_boxed_slice()))
+    }
+}
+
+// A header is serialized as the flat list of its clues, in the order described in the
+// documentation of `Header`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Header {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.clues.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Header {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Header::try_from(Vec::<u8>::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// An error that might occur whilst parsing a [`Header`] instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseHeaderError {
+    InvalidInteger,
+    InvalidViewCount,
+    TooManyViews,
+    ViewTooLarge,
+}
+
+impl From<core::num::ParseIntError> for ParseHeaderError {
+    fn from(e: core::num::ParseIntError) -> Self {
+        use core::num::IntErrorKind::*;
+
+        if *e.kind() == PosOverflow {
+            Self::ViewTooLarge
+        } else {
+            Self::InvalidInteger
+        }
+    }
+}
+
+impl Display for ParseHeaderError {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidInteger => f.write_str("invalid integer found in header"),
+            Self::InvalidViewCount => f.write_str("invalid number of views (must be a multiple of 4)"),
+            Self::TooManyViews => write!(f, "it's not possible to solve a size larger than {MAX_SIZE}"),
+            Self::ViewTooLarge => f.write_str("views can't exceed the size of the board"),
+        }
+    }
+}
+
+impl core::error::Error for ParseHeaderError {}
+
+// A string representing a "header" must follow the following properties:
+//
+// It's a space-separated list of numbers. The number of elements in that list must be divisible
+// by 4.
+//
+// Let call "n" the quarter of that size. Each element of the list must be between 0 and n
+// (included), 0 standing for a missing clue. Missing clues may also be written as `?` or `.`. n
+// must fit in a u8.
+impl FromStr for Header {
+    type Err = ParseHeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut vec = Vec::new();
+
+        // FIXME(nils): use try_collect() when stable.
+        for word in s.split_ascii_whitespace() {
+            let view = match word {
+                "?" | "." => 0,
+                word => word.parse()?,
+            };
+            vec.push(view);
+        }
+
+        Header::try_from(vec)
+    }
+}
+
+/// Checks that `clues` make a valid header, whose clues do not exceed its size.
+fn check_clues(clues: &[u8]) -> Result<(), ParseHeaderError> {
+    if !clues.len().is_multiple_of(4) {
+        return Err(ParseHeaderError::InvalidViewCount);
+    }
+
+    if clues.len() > MAX_SIZE * 4 {
+        return Err(ParseHeaderError::TooManyViews);
+    }
+
+    let size = (clues.len() / 4) as u8;
+
+    if clues.iter().any(|&v| v > size) {
+        return Err(ParseHeaderError::ViewTooLarge);
+    }
+
+    Ok(())
+}
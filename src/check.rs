@@ -1,28 +1,60 @@
 //! Provides ways to check whether a given board is valid.
 
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+use core::fmt::Display;
+
+use crate::alphabet::Alphabet;
+use crate::board::{Board, Header};
+
 /// A kind of [`BoardError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum BoardErrorKind {
     /// The number is invalid.
     InvalidNumber,
     /// There is not enough columns.
-    ColumnCount { expected: u8, given: u8 },
+    ColumnCount { expected: usize, given: usize },
     /// There is not enough rows.
-    RowCount { expected: u8, given: u8 },
+    RowCount { expected: usize, given: usize },
     /// Invalid character found in the input.
     UnexpectedCharacter(u8),
-    /// Invalid view count from top to bottom.
-    TopToBottom { expected: u8, given: u8 },
-    /// Invalid view count from bottom to top.
-    BottomToTop { expected: u8, given: u8 },
-    /// Invalid view count from left to right.
-    LeftToRight { expected: u8, given: u8 },
-    /// Invalid view count from right to left.
-    RightToLeft { expected: u8, given: u8 },
+    /// Invalid view count from top to bottom, in the column `column`.
+    TopToBottom {
+        column: usize,
+        expected: u8,
+        given: u8,
+    },
+    /// Invalid view count from bottom to top, in the column `column`.
+    BottomToTop {
+        column: usize,
+        expected: u8,
+        given: u8,
+    },
+    /// Invalid view count from left to right, in the row `row`.
+    LeftToRight { row: usize, expected: u8, given: u8 },
+    /// Invalid view count from right to left, in the row `row`.
+    RightToLeft { row: usize, expected: u8, given: u8 },
     /// Doubles found.
     Doubles,
+    /// The board is not formatted canonically.
+    Strict(StrictError),
+    /// The board differs from the reference solution.
+    ///
+    /// Each cell that differs is provided as `(x, y, expected)`.
+    Mismatch(Vec<(usize, usize, u8)>),
 }
 
 /// An error which might occur when checking a board.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoardError {
     /// The kind of the error.
     pub kind: BoardErrorKind,
@@ -31,7 +63,8 @@ pub struct BoardError {
 }
 
 /// A span.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     pub start: usize,
     pub end: usize,
@@ -44,117 +77,524 @@ pub struct BoardCell {
     span: Span,
 }
 
-fn parse(number: &[u8]) -> Option<u8> {
-    let mut result = 0u8;
+/// How strictly the formatting of a board is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Cells may be separated by any number of spaces, tabs or commas, numbers may be padded with
+    /// zeros, decorative borders are ignored, lines may end with `\r\n`, empty lines are ignored
+    /// and the last line does not have to end with a newline.
+    #[default]
+    Lenient,
+    /// Only the canonical formatting is accepted: cells are separated by exactly one space (or
+    /// all left-aligned on boards of size 10 or more), numbers have no leading zeros, and every
+    /// line, including the last one, ends with a single `\n`.
+    Strict,
+}
 
-    for &b in number {
-        let value = b.wrapping_sub(b'0');
-        if value > 9 {
-            return None;
+/// A formatting error only reported in [`Mode::Strict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum StrictError {
+    /// A line starts with a space.
+    LeadingSpace,
+    /// A line ends with a space.
+    TrailingSpace,
+    /// Two cells are separated by more than one space.
+    DoubleSpace,
+    /// The cells of a board of size 10 or more are not consistently separated.
+    ///
+    /// Either every cell is padded with spaces to the number of digits of the size, or every cell
+    /// is separated from the next one by a single space.
+    Alignment,
+    /// A number starts with a zero.
+    LeadingZero,
+    /// A line is empty.
+    EmptyLine,
+    /// The last line does not end with a newline.
+    MissingNewline,
+}
+
+impl Display for StrictError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LeadingSpace => f.write_str("lines can't start with a space"),
+            Self::TrailingSpace => f.write_str("lines can't end with a space"),
+            Self::DoubleSpace => f.write_str("cells must be separated by a single space"),
+            Self::Alignment => {
+                f.write_str("cells must all be left-aligned or separated by a single space")
+            }
+            Self::LeadingZero => f.write_str("numbers can't have leading zeros"),
+            Self::EmptyLine => f.write_str("empty lines are not allowed"),
+            Self::MissingNewline => f.write_str("the last line must end with a newline"),
         }
-        result = result.checked_mul(10)?.checked_add(value)?;
     }
+}
 
-    Some(result)
+impl BoardErrorKind {
+    /// Returns the name of this kind of error, as used when it is serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::InvalidNumber => "invalid-number",
+            Self::ColumnCount { .. } => "column-count",
+            Self::RowCount { .. } => "row-count",
+            Self::UnexpectedCharacter(_) => "unexpected-character",
+            Self::TopToBottom { .. } => "top-to-bottom",
+            Self::BottomToTop { .. } => "bottom-to-top",
+            Self::LeftToRight { .. } => "left-to-right",
+            Self::RightToLeft { .. } => "right-to-left",
+            Self::Doubles => "doubles",
+            Self::Strict(_) => "strict",
+            Self::Mismatch(_) => "mismatch",
+        }
+    }
 }
 
-/// Parses the provided ASCII board into an actual board.
-fn parse_board(board: &[u8], size: u8) -> Result<Box<[BoardCell]>, BoardError> {
-    let mut result = Vec::new();
+// Rows and columns are numbered from 1 in messages, like `check` reports them.
+impl Display for BoardErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidNumber => f.write_str("invalid number"),
+            Self::ColumnCount { expected, given } => {
+                write!(f, "expected {expected} columns, found {given}")
+            }
+            Self::RowCount { expected, given } => {
+                write!(f, "expected {expected} rows, found {given}")
+            }
+            Self::UnexpectedCharacter(c) => {
+                write!(f, "character `{}` was not expected", c.escape_ascii())
+            }
+            Self::TopToBottom {
+                column,
+                expected,
+                given,
+            } => write!(
+                f,
+                "in column {}, from top to bottom, expected view count of {expected}, got {given}",
+                column + 1,
+            ),
+            Self::BottomToTop {
+                column,
+                expected,
+                given,
+            } => write!(
+                f,
+                "in column {}, from bottom to top, expected view count of {expected}, got {given}",
+                column + 1,
+            ),
+            Self::LeftToRight {
+                row,
+                expected,
+                given,
+            } => write!(
+                f,
+                "in row {}, from left to right, expected view count of {expected}, got {given}",
+                row + 1,
+            ),
+            Self::RightToLeft {
+                row,
+                expected,
+                given,
+            } => write!(
+                f,
+                "in row {}, from right to left, expected view count of {expected}, got {given}",
+                row + 1,
+            ),
+            Self::Doubles => f.write_str("found twice the same number on the same row/column"),
+            Self::Strict(err) => Display::fmt(err, f),
+            Self::Mismatch(cells) => {
+                write!(
+                    f,
+                    "{} cells differ from the reference solution",
+                    cells.len()
+                )
+            }
+        }
+    }
+}
 
-    let mut in_number = false;
-    let mut l_start = 0;
-    let mut n_start = 0;
-    let mut numbers_on_line = 0;
-    let mut lines = 0;
-    let mut i = 0;
-    while i < board.len() {
-        if !in_number {
-            match board[i] {
-                b' ' => i += 1,
-                b'\n' => {
-                    if numbers_on_line != size {
-                        return Err(BoardError {
-                            kind: BoardErrorKind::ColumnCount {
-                                expected: size,
-                                given: numbers_on_line,
-                            },
-                            spans: vec![Span {
-                                start: l_start,
-                                end: i,
-                            }],
+impl Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl core::error::Error for BoardError {}
+
+/// A token of the ASCII representation of a board.
+enum Token {
+    /// The digits of a number, or a symbol of the alphabet.
+    Number(Span),
+    /// The end of a line.
+    Newline(Span),
+}
+
+/// Splits the ASCII representation of a board into [`Token`]s.
+struct Tokenizer<'a> {
+    input: &'a [u8],
+    mode: Mode,
+    alphabet: &'a Alphabet,
+    pos: usize,
+    /// The number of characters of the longest symbol of the board.
+    width: usize,
+    /// The number of spaces required to left-align the last number in [`Mode::Strict`].
+    padding: usize,
+    /// Whether the cells of the board are left-aligned in [`Mode::Strict`], or `None` until a
+    /// separator tells.
+    aligned: Option<bool>,
+    /// Whether no number has been found on the current line yet.
+    line_start: bool,
+    /// Whether the end of the input has been reached.
+    done: bool,
+    /// Whether `.` is accepted as the symbol of an empty cell.
+    partial: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a [u8], mode: Mode, alphabet: &'a Alphabet, size: u8) -> Self {
+        Self {
+            input,
+            mode,
+            alphabet,
+            width: alphabet.width(size),
+            padding: 0,
+            aligned: None,
+            pos: 0,
+            line_start: true,
+            done: false,
+            partial: false,
+        }
+    }
+
+    /// Accepts `.` as the symbol of an empty cell.
+    fn partial(mut self) -> Self {
+        self.partial = true;
+        self
+    }
+
+    /// Creates a [`BoardError`] of kind [`BoardErrorKind::Strict`].
+    fn strict_error(error: StrictError, start: usize, end: usize) -> BoardError {
+        BoardError {
+            kind: BoardErrorKind::Strict(error),
+            spans: vec![Span { start, end }],
+        }
+    }
+
+    /// Returns the length of the separator found at the start of `rest`, or 0 if `rest` does not
+    /// start with a separator.
+    ///
+    /// In [`Mode::Lenient`], tabs and commas (as found in boards exported from spreadsheets) are
+    /// accepted along with spaces, and so are the vertical lines of decorative borders, which
+    /// may surround the cells of a line or separate its columns.
+    ///
+    /// Symbols of the alphabet are never considered to be separators.
+    fn separator_len(&self, rest: &[u8]) -> usize {
+        if self.alphabet.symbol_len(rest) != 0 {
+            return 0;
+        }
+
+        match (self.mode, rest) {
+            (_, [b' ', ..]) => 1,
+            (Mode::Lenient, [b'\t' | b',', ..]) => 1,
+            (Mode::Lenient, _) => match border_len(rest) {
+                Some((len, true)) => len,
+                _ => 0,
+            },
+            _ => 0,
+        }
+    }
+
+    /// Returns the length of the line starting at the current position if it is only made of
+    /// decorative borders, not including its newline.
+    ///
+    /// Such lines (`+---+---+`, `|===|===|`, or their box-drawing equivalents) are skipped in
+    /// [`Mode::Lenient`], meaning that they are considered to be empty. Outside of them, only
+    /// vertical lines are ignored, so that `-` or `+` characters between the numbers of a line are
+    /// still reported.
+    fn rule_line_len(&self) -> Option<usize> {
+        let line = &self.input[self.pos..];
+        let mut len = 0;
+        let mut borders = false;
+        loop {
+            let rest = &line[len..];
+            if matches!(rest, [] | [b'\n', ..]) {
+                return borders.then_some(len);
+            }
+            if self.alphabet.symbol_len(rest) != 0 {
+                return None;
+            }
+            len += match rest {
+                [b' ' | b'\t' | b'\r', ..] => 1,
+                _ => {
+                    borders = true;
+                    border_len(rest)?.0
+                }
+            };
+        }
+    }
+
+    /// Skips the separators starting at the current position.
+    fn skip_separators(&mut self) -> Result<(), BoardError> {
+        let start = self.pos;
+        loop {
+            let len = self.separator_len(&self.input[self.pos..]);
+            if len == 0 {
+                break;
+            }
+            self.pos += len;
+        }
+
+        if self.mode == Mode::Lenient {
+            if self.line_start {
+                if let Some(len) = self.rule_line_len() {
+                    self.pos += len;
+                }
+            }
+            if self.input[self.pos..].starts_with(b"\r\n") {
+                self.pos += 1;
+            }
+            return Ok(());
+        }
+
+        // Boards of size 10 or more may be aligned by padding every cell with spaces, exactly
+        // like they are written by the program, or have their cells separated by a single space,
+        // like the `flat` output. The first separator which tells them apart decides for the
+        // whole board.
+        let spaces = self.pos - start;
+        let error = if self.line_start {
+            (spaces != 0).then_some(StrictError::LeadingSpace)
+        } else {
+            let end = matches!(self.input.get(self.pos), None | Some(b'\n'));
+            let (aligned, packed) = if end {
+                (self.padding, 0)
+            } else {
+                (self.padding + 1, 1)
+            };
+            if self.aligned.is_none()
+                && aligned != packed
+                && (spaces == aligned || spaces == packed)
+            {
+                self.aligned = Some(spaces == aligned);
+            }
+
+            let expected = if self.aligned == Some(true) {
+                aligned
+            } else {
+                packed
+            };
+            if spaces == expected {
+                None
+            } else if end && spaces > expected {
+                Some(StrictError::TrailingSpace)
+            } else if self.width == 1 {
+                Some(StrictError::DoubleSpace)
+            } else {
+                Some(StrictError::Alignment)
+            }
+        };
+
+        match error {
+            Some(error) => Err(Self::strict_error(error, start, self.pos)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the length of the decorative border character found at the start of `rest`, and whether
+/// it is a vertical line, or `None` if `rest` does not start with one.
+///
+/// Borders are drawn with `|`, `+`, `-` and `=` characters, or with unicode box-drawing characters.
+fn border_len(rest: &[u8]) -> Option<(usize, bool)> {
+    match *rest {
+        [b'|', ..] => Some((1, true)),
+        [b'+' | b'-' | b'=', ..] => Some((1, false)),
+        // The box-drawing block ranges from U+2500 to U+257F.
+        [0xE2, a @ (0x94 | 0x95), b @ 0x80..=0xBF, ..] => {
+            let c = 0x2500 + (((a - 0x94) as u32) << 6 | (b - 0x80) as u32);
+            let vertical = matches!(
+                c,
+                0x2502 | 0x2503 | 0x2506 | 0x2507 | 0x250A | 0x250B | 0x254E | 0x254F | 0x2551
+            );
+            Some((3, vertical))
+        }
+        _ => None,
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Result<Token, BoardError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            if let Err(err) = self.skip_separators() {
+                self.done = true;
+                return Some(Err(err));
+            }
+
+            let start = self.pos;
+            let len = match self.alphabet.symbol_len(&self.input[self.pos..]) {
+                0 if self.partial && self.input.get(self.pos) == Some(&b'.') => 1,
+                len => len,
+            };
+            if len != 0 {
+                self.pos += len;
+
+                if self.mode == Mode::Strict
+                    && self.alphabet == &Alphabet::Digits
+                    && len > 1
+                    && self.input[start] == b'0'
+                {
+                    self.done = true;
+                    return Some(Err(Self::strict_error(
+                        StrictError::LeadingZero,
+                        start,
+                        self.pos,
+                    )));
+                }
+
+                let chars = String::from_utf8_lossy(&self.input[start..self.pos])
+                    .chars()
+                    .count();
+                self.padding = self.width.saturating_sub(chars);
+                self.line_start = false;
+                return Some(Ok(Token::Number(Span {
+                    start,
+                    end: self.pos,
+                })));
+            }
+
+            match self.input.get(self.pos) {
+                None => {
+                    self.done = true;
+
+                    // The last line is not terminated.
+                    if !self.line_start {
+                        self.line_start = true;
+                        return Some(match self.mode {
+                            Mode::Lenient => Ok(Token::Newline(Span { start, end: start })),
+                            Mode::Strict => Err(Self::strict_error(
+                                StrictError::MissingNewline,
+                                start,
+                                start,
+                            )),
                         });
                     }
 
-                    i += 1;
-                    numbers_on_line = 0;
-                    l_start = i;
-                    lines += 1;
+                    return None;
                 }
-                b'0'..=b'9' => {
-                    n_start = i;
-                    in_number = true;
+                Some(b'\n') => {
+                    self.pos += 1;
+
+                    if self.line_start {
+                        match self.mode {
+                            Mode::Lenient => continue,
+                            Mode::Strict => {
+                                self.done = true;
+                                return Some(Err(Self::strict_error(
+                                    StrictError::EmptyLine,
+                                    start,
+                                    self.pos,
+                                )));
+                            }
+                        }
+                    }
+
+                    self.line_start = true;
+                    return Some(Ok(Token::Newline(Span {
+                        start,
+                        end: self.pos,
+                    })));
                 }
-                c => {
-                    return Err(BoardError {
+                Some(&c) => {
+                    self.done = true;
+                    return Some(Err(BoardError {
                         kind: BoardErrorKind::UnexpectedCharacter(c),
                         spans: vec![Span {
-                            start: i,
-                            end: i + 1,
+                            start,
+                            end: start + 1,
                         }],
-                    });
+                    }));
                 }
             }
-        } else {
-            match board[i] {
-                b'0'..=b'9' => i += 1,
-                _ => match parse(&board[n_start..i]) {
-                    Some(value) => {
-                        if value > size || value == 0 {
-                            return Err(BoardError {
-                                kind: BoardErrorKind::InvalidNumber,
-                                spans: vec![Span {
-                                    start: n_start,
-                                    end: i,
-                                }],
-                            });
-                        }
-                        numbers_on_line += 1;
-                        result.push(BoardCell {
-                            value,
-                            span: Span {
-                                start: n_start,
-                                end: i,
-                            },
-                        });
-                        in_number = false;
-                    }
-                    None => {
+        }
+    }
+}
+
+/// Parses the provided ASCII board into an actual board.
+///
+/// The board may also be provided on a single line, in row-major order. When `partial` is set,
+/// empty cells may be written as `.` or `0`, and are given the value `0`.
+fn parse_board(
+    board: &[u8],
+    size: u8,
+    mode: Mode,
+    alphabet: &Alphabet,
+    partial: bool,
+) -> Result<Box<[BoardCell]>, BoardError> {
+    let size = size as usize;
+    let mut result = Vec::new();
+
+    let mut l_start = None;
+    let mut numbers_on_line = 0;
+    let mut lines = 0;
+    let mut tokenizer = Tokenizer::new(board, mode, alphabet, size as u8);
+    if partial {
+        tokenizer = tokenizer.partial();
+    }
+    for token in tokenizer {
+        match token? {
+            Token::Number(span) => {
+                let symbol = &board[span.start..span.end];
+                let value = match alphabet.parse(symbol) {
+                    _ if partial && symbol == b"." => 0,
+                    Some(0) if partial => 0,
+                    Some(value) if value != 0 && value as usize <= size => value,
+                    _ => {
                         return Err(BoardError {
                             kind: BoardErrorKind::InvalidNumber,
-                            spans: vec![Span {
-                                start: n_start,
-                                end: i,
-                            }],
+                            spans: vec![span],
                         });
                     }
-                },
+                };
+
+                l_start.get_or_insert(span.start);
+                numbers_on_line += 1;
+                result.push(BoardCell { value, span });
             }
-        }
-    }
+            Token::Newline(span) => {
+                // The whole board is on the first line.
+                if lines == 0 && numbers_on_line == size * size {
+                    numbers_on_line = 0;
+                    l_start = None;
+                    lines = size;
+                    continue;
+                }
 
-    if lines == 0 {
-        return Err(BoardError {
-            kind: BoardErrorKind::RowCount {
-                expected: size,
-                given: lines,
-            },
-            spans: vec![Span { start: 0, end: 0 }],
-        });
-    }
+                if numbers_on_line != size {
+                    return Err(BoardError {
+                        kind: BoardErrorKind::ColumnCount {
+                            expected: size,
+                            given: numbers_on_line,
+                        },
+                        spans: vec![Span {
+                            start: l_start.unwrap_or(span.start),
+                            end: span.start,
+                        }],
+                    });
+                }
 
-    if numbers_on_line != 0 {
-        lines += 1;
+                numbers_on_line = 0;
+                l_start = None;
+                lines += 1;
+            }
+        }
     }
 
     if lines != size {
@@ -170,6 +610,42 @@ fn parse_board(board: &[u8], size: u8) -> Result<Box<[BoardCell]>, BoardError> {
     Ok(result.into_boxed_slice())
 }
 
+/// Parses the provided ASCII board, returning the value of each of its cells.
+///
+/// Only the shape of the board and the range of its values are checked.
+pub fn parse_values(
+    board: &[u8],
+    size: usize,
+    mode: Mode,
+    alphabet: &Alphabet,
+) -> Result<Board, BoardError> {
+    let cells = parse_board(board, size as u8, mode, alphabet, false)?
+        .iter()
+        .map(|cell| cell.value)
+        .collect();
+
+    Ok(Board::new(size, cells))
+}
+
+/// Parses the provided ASCII board, which may have empty cells, returning the value of each of its
+/// cells.
+///
+/// Empty cells are written as `.` or `0`, and are given the value `0`. Only the shape of the board
+/// and the range of its values are checked.
+pub fn parse_partial(
+    board: &[u8],
+    size: usize,
+    mode: Mode,
+    alphabet: &Alphabet,
+) -> Result<Board, BoardError> {
+    let cells = parse_board(board, size as u8, mode, alphabet, true)?
+        .iter()
+        .map(|cell| cell.value)
+        .collect();
+
+    Ok(Board::new(size, cells))
+}
+
 fn count_viewed(size: u8, get_number: &mut dyn FnMut(usize) -> u8) -> u8 {
     let mut max = 0;
     let mut count = 0;
@@ -189,12 +665,87 @@ fn count_viewed(size: u8, get_number: &mut dyn FnMut(usize) -> u8) -> u8 {
     count
 }
 
-/// Checks whether `board` is valid.
+/// Compares `board` cell-by-cell with `reference`.
 ///
-/// `board` is the ASCII representation of the board.
-pub fn check(header: &[u8], size: usize, board: &[u8]) -> Result<(), BoardError> {
-    let board = parse_board(board, size as u8)?;
+/// `board` is the ASCII representation of the board. When some cells differ, every one of them is
+/// reported.
+pub fn compare(
+    board: &[u8],
+    reference: &Board,
+    mode: Mode,
+    alphabet: &Alphabet,
+) -> Result<(), BoardError> {
+    let size = reference.size();
+    let board = parse_board(board, size as u8, mode, alphabet, false)?;
+
+    let mut cells = Vec::new();
+    let mut spans = Vec::new();
+    for (i, (cell, &expected)) in board.iter().zip(reference.cells()).enumerate() {
+        if cell.value != expected {
+            cells.push((i % size, i / size, expected));
+            spans.push(cell.span);
+        }
+    }
 
+    if cells.is_empty() {
+        Ok(())
+    } else {
+        Err(BoardError {
+            kind: BoardErrorKind::Mismatch(cells),
+            spans,
+        })
+    }
+}
+
+/// Guesses the size of the provided ASCII board from the number of cells on its first line.
+///
+/// A board written on a single line is recognized when it holds a square number of cells. `None`
+/// is returned when the board holds no number.
+pub fn infer_size(board: &[u8], alphabet: &Alphabet) -> Option<usize> {
+    let mut first_line = 0;
+    let mut lines = 0;
+
+    for token in Tokenizer::new(board, Mode::Lenient, alphabet, u8::MAX) {
+        match token {
+            Ok(Token::Number(_)) if lines == 0 => first_line += 1,
+            // The board spans several lines.
+            Ok(Token::Number(_)) => return Some(first_line),
+            Ok(Token::Newline(_)) => lines += 1,
+            // The error is reported when the board is parsed.
+            Err(_) if lines == 0 => return (first_line != 0).then_some(first_line),
+            Err(_) => break,
+        }
+    }
+
+    if first_line == 0 {
+        return None;
+    }
+
+    // Only the first line holds numbers, whether it ends with a newline or not.
+    let side = first_line.isqrt();
+    if side * side == first_line {
+        Some(side)
+    } else {
+        Some(first_line)
+    }
+}
+
+/// Checks whether `board` follows the rules of the game, without looking at any clue.
+///
+/// The shape of the board and the range of its values are checked, along with the uniqueness of
+/// the values of every row and column.
+pub fn check_rules(
+    board: &[u8],
+    size: usize,
+    mode: Mode,
+    alphabet: &Alphabet,
+) -> Result<(), BoardError> {
+    let board = parse_board(board, size as u8, mode, alphabet, false)?;
+    check_doubles(&board, size)
+}
+
+/// Checks that no value appears twice in a row or a column of `board`.
+fn check_doubles(board: &[BoardCell], size: usize) -> Result<(), BoardError> {
     for k in 0..size {
         for i in 0..size {
             for j in i + 1..size {
@@ -215,13 +766,31 @@ pub fn check(header: &[u8], size: usize, board: &[u8]) -> Result<(), BoardError>
         }
     }
 
+    Ok(())
+}
+
+/// Checks whether `board` is valid.
+///
+/// `board` is the ASCII representation of the board. A clue of `0` is missing, and accepts any
+/// number of views.
+pub fn check(
+    header: &Header,
+    board: &[u8],
+    mode: Mode,
+    alphabet: &Alphabet,
+) -> Result<(), BoardError> {
+    let size = header.size();
+    let board = parse_board(board, size as u8, mode, alphabet, false)?;
+    check_doubles(&board, size)?;
+
     for i in 0..size {
         // top-to-bottom
         let from_top = count_viewed(size as u8, &mut |y| board[i + y * size].value);
-        if from_top != header[i] {
+        if header.top()[i] != 0 && from_top != header.top()[i] {
             return Err(BoardError {
                 kind: BoardErrorKind::TopToBottom {
-                    expected: header[i],
+                    column: i,
+                    expected: header.top()[i],
                     given: from_top,
                 },
                 spans: (0..size).map(|y| board[i + y * size].span).collect(),
@@ -230,10 +799,11 @@ pub fn check(header: &[u8], size: usize, board: &[u8]) -> Result<(), BoardError>
 
         // bottom-to-top
         let from_bottom = count_viewed(size as u8, &mut |y| board[i + (size - y - 1) * size].value);
-        if from_bottom != header[size + i] {
+        if header.bottom()[i] != 0 && from_bottom != header.bottom()[i] {
             return Err(BoardError {
                 kind: BoardErrorKind::BottomToTop {
-                    expected: header[size + i],
+                    column: i,
+                    expected: header.bottom()[i],
                     given: from_bottom,
                 },
                 spans: (0..size).map(|y| board[i + y * size].span).collect(),
@@ -242,10 +812,11 @@ pub fn check(header: &[u8], size: usize, board: &[u8]) -> Result<(), BoardError>
 
         // left-to-right
         let from_left = count_viewed(size as u8, &mut |x| board[x + i * size].value);
-        if from_left != header[size * 2 + i] {
+        if header.left()[i] != 0 && from_left != header.left()[i] {
             return Err(BoardError {
                 kind: BoardErrorKind::LeftToRight {
-                    expected: header[size * 2 + i],
+                    row: i,
+                    expected: header.left()[i],
                     given: from_left,
                 },
                 spans: vec![Span {
@@ -257,10 +828,11 @@ pub fn check(header: &[u8], size: usize, board: &[u8]) -> Result<(), BoardError>
 
         // right-to-left
         let from_right = count_viewed(size as u8, &mut |x| board[(size - x - 1) + i * size].value);
-        if from_right != header[size * 3 + i] {
+        if header.right()[i] != 0 && from_right != header.right()[i] {
             return Err(BoardError {
                 kind: BoardErrorKind::RightToLeft {
-                    expected: header[size * 3 + i],
+                    row: i,
+                    expected: header.right()[i],
                     given: from_right,
                 },
                 spans: vec![board[i * size].span, board[i * size + size - 1].span],
@@ -270,3 +842,206 @@ pub fn check(header: &[u8], size: usize, board: &[u8]) -> Result<(), BoardError>
 
     Ok(())
 }
+
+/// Checks whether `solution` is valid.
+///
+/// The solution is first written the way the `solution` output format writes it, and that text is
+/// then validated exactly like a board provided by the user would be, using [`Mode::Strict`] as
+/// the program is expected to write canonical boards. When the solution is invalid,
+/// the written board is returned along with the error, as the spans of the error refer to it.
+pub fn check_solution(header: &Header, solution: &Board) -> Result<(), (Vec<u8>, BoardError)> {
+    let width = Alphabet::Digits.width(solution.size() as u8);
+    let mut board = Vec::new();
+
+    for row in solution.rows() {
+        for (x, &value) in row.iter().enumerate() {
+            if x != 0 {
+                board.push(b' ');
+            }
+            board.extend_from_slice(format!("{value:<width$}").as_bytes());
+        }
+        board.push(b'\n');
+    }
+
+    match check(header, &board, Mode::Strict, &Alphabet::Digits) {
+        Ok(()) => Ok(()),
+        Err(err) => Err((board, err)),
+    }
+}
+
+/// The state of a clue of a board that is being filled, as tracked by an [`IncrementalChecker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClueState {
+    /// The clue may still be satisfied, or violated, depending on the cells that remain empty.
+    Pending,
+    /// The line of the clue is complete, and has the right number of views.
+    Satisfied,
+    /// The clue can't be satisfied anymore, whatever the values of the empty cells.
+    Violated,
+}
+
+/// The consequences of a single edit made through [`IncrementalChecker::set`].
+#[derive(Debug, Clone, Default)]
+pub struct Changes {
+    /// The cells that now hold the same value as the edited cell, in the same row or column.
+    pub added_duplicates: Vec<(usize, usize)>,
+    /// The cells that held the same value as the edited cell, in the same row or column, before
+    /// the edit.
+    pub removed_duplicates: Vec<(usize, usize)>,
+    /// The clues whose state changed, along with their new state, as `(index, state)`.
+    pub clues: Vec<(usize, ClueState)>,
+}
+
+/// Validates a board one cell at a time.
+///
+/// Rather than re-checking the whole board after every edit, only the row and the column of the
+/// edited cell are looked at, making each edit take `O(size)` time. This is meant for interactive
+/// front-ends.
+pub struct IncrementalChecker {
+    /// The header of the board. A clue of `0` is missing.
+    header: Header,
+    /// The size of the board.
+    size: usize,
+    /// The values of the cells, `0` when empty.
+    board: Board,
+    /// The state of every clue of `header`.
+    clues: Box<[ClueState]>,
+    /// The number of pairs of cells holding the same value in the same row or column.
+    duplicates: usize,
+}
+
+impl IncrementalChecker {
+    /// Creates a new [`IncrementalChecker`] for an empty board surrounded by `header`.
+    pub fn new(header: &Header) -> Self {
+        let size = header.size();
+
+        Self {
+            header: header.clone(),
+            size,
+            board: Board::empty(size),
+            clues: core::iter::repeat_n(ClueState::Pending, size * 4).collect(),
+            duplicates: 0,
+        }
+    }
+
+    /// Returns the current values of the cells of the board, `0` when empty.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the state of the clue at `index` in the header.
+    pub fn clue(&self, index: usize) -> ClueState {
+        self.clues[index]
+    }
+
+    /// Returns whether the board is complete and valid.
+    pub fn is_solved(&self) -> bool {
+        self.duplicates == 0 && self.clues.iter().all(|&c| c == ClueState::Satisfied)
+    }
+
+    /// Returns the cells of the row and the column of `(x, y)`, except `(x, y)` itself, that hold
+    /// `value`.
+    fn same_value(&self, x: usize, y: usize, value: u8) -> Vec<(usize, usize)> {
+        if value == 0 {
+            return Vec::new();
+        }
+
+        let row = (0..self.size).filter(|&i| i != x).map(|i| (i, y));
+        let column = (0..self.size).filter(|&i| i != y).map(|i| (x, i));
+
+        row.chain(column)
+            .filter(|&(x, y)| self.board.get(x, y) == value)
+            .collect()
+    }
+
+    /// Computes the state of the clue at `index` from the current board.
+    fn compute_clue(&self, index: usize) -> ClueState {
+        let size = self.size;
+        let k = index % size;
+        let clue = self.header[index];
+
+        let cell = |i: usize| {
+            let (x, y) = match index / size {
+                0 => (k, i),
+                1 => (k, size - 1 - i),
+                2 => (i, k),
+                _ => (size - 1 - i, k),
+            };
+            self.board.get(x, y)
+        };
+
+        // The buildings visible among the cells that are filled, starting from the clue. Empty
+        // cells may only add views after them.
+        let mut max = 0;
+        let mut views = 0;
+        let mut complete = true;
+        for i in 0..size {
+            let value = cell(i);
+            if value == 0 {
+                complete = false;
+                break;
+            }
+            if value > max {
+                max = value;
+                views += 1;
+            }
+        }
+
+        if clue == 0 {
+            return if complete {
+                ClueState::Satisfied
+            } else {
+                ClueState::Pending
+            };
+        }
+
+        // Once the tallest building is seen, the number of views can't change anymore. Until
+        // then, it will add at least one view.
+        let settled = complete || max as usize == size;
+        let minimum = if settled { views } else { views + 1 };
+
+        if minimum > clue || (settled && views != clue) {
+            ClueState::Violated
+        } else if complete {
+            ClueState::Satisfied
+        } else {
+            ClueState::Pending
+        }
+    }
+
+    /// Sets the cell at `(x, y)` to `value`, or clears it when `value` is `0`, and reports the
+    /// consequences of that edit.
+    pub fn set(&mut self, x: usize, y: usize, value: u8) -> Changes {
+        assert!(x < self.size && y < self.size);
+        assert!(value as usize <= self.size);
+
+        let previous = self.board.get(x, y);
+        if previous == value {
+            return Changes::default();
+        }
+
+        let removed_duplicates = self.same_value(x, y, previous);
+        self.board.set(x, y, value);
+        let added_duplicates = self.same_value(x, y, value);
+
+        self.duplicates -= removed_duplicates.len();
+        self.duplicates += added_duplicates.len();
+
+        // Only the clues of the row and the column of the edited cell may change.
+        let size = self.size;
+        let mut clues = Vec::new();
+        for index in [x, size + x, 2 * size + y, 3 * size + y] {
+            let state = self.compute_clue(index);
+            if state != self.clues[index] {
+                self.clues[index] = state;
+                clues.push((index, state));
+            }
+        }
+
+        Changes {
+            added_duplicates,
+            removed_duplicates,
+            clues,
+        }
+    }
+}
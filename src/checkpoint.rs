@@ -0,0 +1,52 @@
+//! Saves and restores the state of long searches, used by `solve --checkpoint` and `--resume`.
+//!
+//! A checkpoint is a JSON object holding the header being solved, its givens and the point the
+//! search has reached, as described by [`solve::Checkpoint`].
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use skyscrapper::board::{Board, Header};
+use skyscrapper::solve;
+
+/// The version of the checkpoint format. It is incremented every time the format, or the order in
+/// which the solver explores the search tree, changes.
+pub const VERSION: u32 = 2;
+
+/// The content of a checkpoint file.
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    /// The version of the format, see [`VERSION`].
+    pub version: u32,
+    /// The header being solved.
+    pub header: Header,
+    /// The cells given by `--givens`, if any.
+    pub givens: Option<Board>,
+    /// The point the search has reached.
+    #[serde(flatten)]
+    pub checkpoint: solve::Checkpoint,
+}
+
+/// Reads the checkpoint at `path`.
+pub fn read(path: &Path) -> io::Result<State> {
+    let text = std::fs::read(path)?;
+    serde_json::from_slice(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Writes a checkpoint to `path`.
+///
+/// The checkpoint is written next to `path` first, and then moved over it, such that the previous
+/// checkpoint is kept when the program is killed while writing.
+pub fn write(path: &Path, state: &State) -> io::Result<()> {
+    let mut temp = PathBuf::from(path);
+    temp.as_mut_os_string().push(".tmp");
+
+    let mut file = io::BufWriter::new(std::fs::File::create(&temp)?);
+    serde_json::to_writer(&mut file, state)?;
+    writeln!(file)?;
+    file.into_inner()?.sync_all()?;
+
+    std::fs::rename(&temp, path)
+}
@@ -0,0 +1,248 @@
+//! Reads and writes boards and headers in the formats of the `convert` subcommand.
+
+use std::fmt;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use skyscrapper::alphabet::Alphabet;
+use skyscrapper::args::{ConvertFormat, OutputFormat};
+use skyscrapper::board::{Board, Header, ParseHeaderError};
+use skyscrapper::check::{self, BoardError};
+use skyscrapper::{format, generate, puzzlink};
+
+/// A header, along with the cells of its board when they are known.
+///
+/// This is also the layout of the JSON format: `{"header":[...],"board":[...]}`, where the board
+/// is omitted when unknown.
+#[derive(Serialize, Deserialize)]
+pub struct Puzzle {
+    /// The clues of the puzzle.
+    pub header: Header,
+    /// The cells of the board, in row-major order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub board: Option<Board>,
+}
+
+/// An error that might occur whilst reading a [`Puzzle`].
+pub enum ReadError {
+    /// The input is empty.
+    Empty,
+    /// The cells of the board are invalid.
+    Board(BoardError),
+    /// The header is invalid.
+    Header(ParseHeaderError),
+    /// The JSON document is invalid.
+    Json(serde_json::Error),
+    /// The input does not have the expected shape.
+    Shape(String),
+    /// The puzz.link URL is invalid.
+    Url(puzzlink::ImportError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the input is empty"),
+            // Board errors are printed along with the input.
+            Self::Board(_) => f.write_str("the board is invalid"),
+            Self::Header(err) => write!(f, "{err}"),
+            Self::Json(err) => write!(f, "invalid JSON: {err}"),
+            Self::Shape(message) => f.write_str(message),
+            Self::Url(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// Reads a board surrounded by its clues, as written by the `both`, `header` and `blank` formats.
+///
+/// Decorative borders are ignored. The rows between the clues may hold no cell at all, in which
+/// case only the header is read.
+fn read_decorated(input: &str) -> Result<Puzzle, ReadError> {
+    let lines: Vec<Vec<&str>> = input
+        .lines()
+        .map(|line| {
+            line.split(|c: char| c.is_ascii_whitespace() || matches!(c, '|' | '+' | '-'))
+                .filter(|word| !word.is_empty())
+                .collect()
+        })
+        .filter(|words: &Vec<&str>| !words.is_empty())
+        .collect();
+
+    let (top, rest) = lines.split_first().ok_or(ReadError::Empty)?;
+    let (bottom, rows) = rest
+        .split_last()
+        .ok_or_else(|| ReadError::Shape("expected the bottom clues after the top ones".into()))?;
+    let size = top.len();
+
+    if rows.len() != size || bottom.len() != size {
+        return Err(ReadError::Shape(format!(
+            "expected {size} rows and {size} bottom clues, found {} rows and {} bottom clues",
+            rows.len(),
+            bottom.len(),
+        )));
+    }
+
+    let parse = |word: &str| word.parse::<u8>().map_err(ParseHeaderError::from);
+    let mut left = Vec::with_capacity(size);
+    let mut right = Vec::with_capacity(size);
+    let mut cells = Vec::new();
+
+    for (y, row) in rows.iter().enumerate() {
+        match row.len() {
+            2 => (),
+            n if n == size + 2 => {
+                for &word in &row[1..=size] {
+                    cells.push(parse(word).map_err(ReadError::Header)?);
+                }
+            }
+            n => {
+                return Err(ReadError::Shape(format!(
+                    "row {} has {} cells, expected {size} or none",
+                    y + 1,
+                    n.saturating_sub(2),
+                )));
+            }
+        }
+        left.push(parse(row[0]).map_err(ReadError::Header)?);
+        right.push(parse(row[row.len() - 1]).map_err(ReadError::Header)?);
+    }
+
+    let mut clues = Vec::with_capacity(size * 4);
+    for words in [top, bottom] {
+        for &word in words {
+            clues.push(parse(word).map_err(ReadError::Header)?);
+        }
+    }
+    clues.extend(left);
+    clues.extend(right);
+    let header = Header::try_from(clues).map_err(ReadError::Header)?;
+
+    let board = match cells.len() {
+        0 => None,
+        n if n == size * size => {
+            if cells.iter().any(|&v| v == 0 || v as usize > size) {
+                return Err(ReadError::Shape(format!(
+                    "cells must be between 1 and {size}"
+                )));
+            }
+            Some(Board::new(size, cells.into_boxed_slice()))
+        }
+        _ => {
+            return Err(ReadError::Shape(
+                "either every row or none of them must hold cells".into(),
+            ));
+        }
+    };
+
+    Ok(Puzzle { header, board })
+}
+
+/// Reads a [`Puzzle`] written in the format `from`.
+pub fn read(input: &[u8], from: ConvertFormat) -> Result<Puzzle, ReadError> {
+    match from {
+        ConvertFormat::Solution | ConvertFormat::Flat | ConvertFormat::Csv => {
+            let size = check::infer_size(input, &Alphabet::Digits).ok_or(ReadError::Empty)?;
+            let board = check::parse_values(input, size, check::Mode::Lenient, &Alphabet::Digits)
+                .map_err(ReadError::Board)?;
+
+            Ok(Puzzle {
+                header: generate::solution_to_header(&board),
+                board: Some(board),
+            })
+        }
+        ConvertFormat::HeaderLine => {
+            let header: Header = String::from_utf8_lossy(input)
+                .parse()
+                .map_err(ReadError::Header)?;
+            if header.is_empty() {
+                return Err(ReadError::Empty);
+            }
+
+            Ok(Puzzle {
+                header,
+                board: None,
+            })
+        }
+        ConvertFormat::Header | ConvertFormat::Both | ConvertFormat::Blank => {
+            read_decorated(&String::from_utf8_lossy(input))
+        }
+        ConvertFormat::Json => {
+            let puzzle: Puzzle = serde_json::from_slice(input).map_err(ReadError::Json)?;
+
+            if let Some(board) = &puzzle.board {
+                if board.size() != puzzle.header.size() {
+                    return Err(ReadError::Shape(format!(
+                        "a board of size {} can't have a header of size {}",
+                        board.size(),
+                        puzzle.header.size(),
+                    )));
+                }
+            }
+
+            Ok(puzzle)
+        }
+        ConvertFormat::Puzzlink => {
+            let (header, _) =
+                puzzlink::decode(&String::from_utf8_lossy(input)).map_err(ReadError::Url)?;
+
+            Ok(Puzzle {
+                header,
+                board: None,
+            })
+        }
+    }
+}
+
+/// Returns whether the format `to` writes the cells of the board.
+pub fn needs_board(to: ConvertFormat) -> bool {
+    matches!(
+        to,
+        ConvertFormat::Solution | ConvertFormat::Flat | ConvertFormat::Both | ConvertFormat::Csv
+    )
+}
+
+/// Writes `puzzle` in the format `to`.
+///
+/// When [`needs_board`] returns `true` for `to`, the board of the puzzle must be known.
+pub fn write(
+    w: &mut dyn termcolor::WriteColor,
+    puzzle: &Puzzle,
+    to: ConvertFormat,
+) -> io::Result<()> {
+    let header = &puzzle.header;
+    let empty;
+    let board = match &puzzle.board {
+        Some(board) => board,
+        None => {
+            empty = Board::empty(header.size());
+            &empty
+        }
+    };
+
+    let output = match to {
+        ConvertFormat::Solution => OutputFormat::Solution,
+        ConvertFormat::Flat => OutputFormat::Flat,
+        ConvertFormat::Header => OutputFormat::Header,
+        ConvertFormat::Both => OutputFormat::Both,
+        ConvertFormat::Blank => OutputFormat::Blank,
+        ConvertFormat::HeaderLine => {
+            let clues: Vec<String> = header.iter().map(u8::to_string).collect();
+            return writeln!(w, "{}", clues.join(" "));
+        }
+        ConvertFormat::Csv => {
+            for row in board.rows() {
+                let cells: Vec<String> = row.iter().map(u8::to_string).collect();
+                writeln!(w, "{}", cells.join(","))?;
+            }
+            return Ok(());
+        }
+        ConvertFormat::Json => {
+            serde_json::to_writer(&mut *w, puzzle)?;
+            return writeln!(w);
+        }
+        ConvertFormat::Puzzlink => OutputFormat::Puzzlink,
+    };
+
+    format::print_solution(w, board, header, &output, &Alphabet::Digits)
+}
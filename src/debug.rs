@@ -0,0 +1,227 @@
+//! An interactive session to step through the deductions of the solver.
+//!
+//! Cells are referred to as `rNcM`, the cell of the `N`-th row and the `M`-th column, both
+//! starting at 1. A whole row is referred to as `rN`, and a whole column as `cM`.
+
+use std::io::{self, BufRead};
+
+use termcolor::{Color, ColorSpec, WriteColor};
+
+use skyscrapper::board::Header;
+use skyscrapper::solve::{Session, SolutionError};
+
+/// The help message of the session.
+const HELP: &str = "\
+commands:
+  show                 prints the board, fixed cells only
+  candidates [REF]     lists the candidates of a cell, a row, a column, or the whole board
+  assign rNcM VALUE    fixes a cell to one of its candidates
+  propagate            removes the value of every fixed cell from its row and column,
+                       and applies the clues again
+  branch               fixes the cell the solver would branch on to its first candidate,
+                       and propagates that choice
+  undo                 reverts the last edit
+  help                 prints this message
+  quit                 ends the session
+";
+
+/// A part of the board, as referred to by the user.
+enum Ref {
+    /// A single cell, as `(x, y)`.
+    Cell(usize, usize),
+    /// A row.
+    Row(usize),
+    /// A column.
+    Column(usize),
+}
+
+/// Parses a reference to a part of the board, such as `r2c3`, `r2` or `c3`.
+fn parse_ref(s: &str, size: usize) -> Option<Ref> {
+    let index = |s: &str| match s.parse::<usize>() {
+        Ok(n @ 1..) if n <= size => Some(n - 1),
+        _ => None,
+    };
+
+    if let Some(rest) = s.strip_prefix('r') {
+        match rest.split_once('c') {
+            Some((row, column)) => Some(Ref::Cell(index(column)?, index(row)?)),
+            None => Some(Ref::Row(index(rest)?)),
+        }
+    } else {
+        Some(Ref::Column(index(s.strip_prefix('c')?)?))
+    }
+}
+
+/// Writes an error message.
+fn write_error(w: &mut dyn WriteColor, message: std::fmt::Arguments) -> io::Result<()> {
+    w.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+    write!(w, "error")?;
+    w.reset()?;
+    writeln!(w, ": {message}")
+}
+
+/// Writes the outcome of an edit that may have led to a contradiction.
+fn write_outcome(
+    w: &mut dyn WriteColor,
+    session: &Session,
+    result: Result<(), SolutionError>,
+) -> io::Result<()> {
+    if result.is_err() {
+        return writeln!(w, "contradiction: a cell has no candidate left");
+    }
+
+    let fixed = session.board().iter().filter(|&&v| v != 0).count();
+    write!(
+        w,
+        "{fixed} of {} cells fixed",
+        session.size() * session.size()
+    )?;
+    if session.is_solved() {
+        write!(w, ", solved")?;
+    }
+    writeln!(w)
+}
+
+/// Prints the board with its header, leaving the cells that are not fixed blank.
+fn show(w: &mut dyn WriteColor, session: &Session, header: &Header) -> io::Result<()> {
+    let size = session.size();
+    let len = skyscrapper::alphabet::log10(size as u8);
+    let board = session.board();
+
+    let clue = |w: &mut dyn WriteColor, clue: u8| -> io::Result<()> {
+        w.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+        write!(w, "{clue:<len$}")?;
+        w.reset()
+    };
+
+    write!(w, "{:len$}", "")?;
+    for &c in header.top() {
+        w.write_all(b" ")?;
+        clue(w, c)?;
+    }
+    writeln!(w)?;
+
+    for (y, row) in board.rows().enumerate() {
+        clue(w, header.left()[y])?;
+        for &value in row {
+            match value {
+                0 => write!(w, " {:<len$}", ".")?,
+                _ => write!(w, " {value:<len$}")?,
+            }
+        }
+        w.write_all(b" ")?;
+        clue(w, header.right()[y])?;
+        writeln!(w)?;
+    }
+
+    write!(w, "{:len$}", "")?;
+    for &c in header.bottom() {
+        w.write_all(b" ")?;
+        clue(w, c)?;
+    }
+    writeln!(w)
+}
+
+/// Lists the candidates of the cells referred to by `r`, or of every cell.
+fn candidates(w: &mut dyn WriteColor, session: &Session, r: Option<Ref>) -> io::Result<()> {
+    let size = session.size();
+    let cells: Vec<(usize, usize)> = match r {
+        Some(Ref::Cell(x, y)) => vec![(x, y)],
+        Some(Ref::Row(y)) => (0..size).map(|x| (x, y)).collect(),
+        Some(Ref::Column(x)) => (0..size).map(|y| (x, y)).collect(),
+        None => (0..size * size).map(|i| (i % size, i / size)).collect(),
+    };
+
+    for (x, y) in cells {
+        write!(w, "r{}c{}:", y + 1, x + 1)?;
+        let candidates = session.candidates(x, y);
+        if candidates.len() == 0 {
+            write!(w, " none")?;
+        }
+        for value in candidates {
+            write!(w, " {value}")?;
+        }
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+/// Runs a debugging session over the board of `header`, reading commands from `input` until it
+/// ends or a `quit` command is found.
+///
+/// When `prompt` is set, a prompt is written before reading every command.
+pub fn run(
+    header: &Header,
+    input: &mut dyn BufRead,
+    w: &mut dyn WriteColor,
+    prompt: bool,
+) -> io::Result<()> {
+    let size = header.size();
+    let mut session = Session::new(header);
+    let mut line = String::new();
+
+    loop {
+        if prompt {
+            write!(w, "> ")?;
+            w.flush()?;
+        }
+
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let words: Vec<&str> = line.split_ascii_whitespace().collect();
+        match words.as_slice() {
+            [] => (),
+            ["help"] => w.write_all(HELP.as_bytes())?,
+            ["quit" | "exit"] => return Ok(()),
+            ["show"] => show(w, &session, header)?,
+            ["candidates"] => candidates(w, &session, None)?,
+            ["candidates", r] => match parse_ref(r, size) {
+                Some(r) => candidates(w, &session, Some(r))?,
+                None => write_error(w, format_args!("`{r}` is not a cell, a row or a column"))?,
+            },
+            ["assign", r, value] => {
+                let (x, y) = match parse_ref(r, size) {
+                    Some(Ref::Cell(x, y)) => (x, y),
+                    _ => {
+                        write_error(w, format_args!("`{r}` is not a cell"))?;
+                        continue;
+                    }
+                };
+                match value.parse::<u8>() {
+                    Ok(value) if session.candidates(x, y).any(|v| v == value) => {
+                        let result = session.assign(x, y, value);
+                        write_outcome(w, &session, result)?;
+                    }
+                    _ => write_error(
+                        w,
+                        format_args!("`{value}` is not a candidate of r{}c{}", y + 1, x + 1),
+                    )?,
+                }
+            }
+            ["propagate"] => {
+                let result = session.propagate();
+                write_outcome(w, &session, result)?;
+            }
+            ["branch"] => match session.branch() {
+                Some(branch) => {
+                    writeln!(w, "r{}c{} = {}", branch.y + 1, branch.x + 1, branch.value)?;
+                    write_outcome(w, &session, branch.result)?;
+                }
+                None => write_error(w, format_args!("every cell is already fixed"))?,
+            },
+            ["undo"] => {
+                if !session.undo() {
+                    write_error(w, format_args!("there is nothing to undo"))?;
+                }
+            }
+            _ => write_error(
+                w,
+                format_args!("unknown command `{}`, try `help`", line.trim()),
+            )?,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! Passphrase-based encryption of the files written by the program.
+//!
+//! Files are encrypted using the [age](https://age-encryption.org) format, and can therefore also
+//! be decrypted using any other age implementation.
+
+use std::io::{self, Read, Write};
+
+use age::secrecy::SecretString;
+use age::stream::StreamWriter;
+
+/// Starts encrypting the data written to `output` using `passphrase`.
+///
+/// The returned writer must be finished, otherwise the encrypted file is truncated.
+pub fn encrypt<W: Write>(output: W, passphrase: &str) -> io::Result<StreamWriter<W>> {
+    age::Encryptor::with_user_passphrase(SecretString::from(passphrase.to_owned()))
+        .wrap_output(output)
+}
+
+/// Decrypts the provided age-encrypted data using `passphrase`.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, age::DecryptError> {
+    let identity = age::scrypt::Identity::new(SecretString::from(passphrase.to_owned()));
+
+    let mut reader = age::Decryptor::new(data)?.decrypt(std::iter::once(&identity as _))?;
+    let mut result = Vec::new();
+    reader.read_to_end(&mut result)?;
+
+    Ok(result)
+}
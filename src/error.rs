@@ -0,0 +1,93 @@
+//! Errors shared by the modules of the library.
+
+use core::fmt;
+use core::fmt::Display;
+
+use crate::alphabet::ParseAlphabetError;
+use crate::board::ParseHeaderError;
+use crate::check::BoardError;
+use crate::solve::SolutionError;
+
+/// The operation has been interrupted by the user, usually with CTRL+C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interrupted;
+
+impl Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the operation has been interrupted")
+    }
+}
+
+impl core::error::Error for Interrupted {}
+
+/// Any error reported by the library.
+///
+/// Every module reports its own error type, which converts into this one. The original error is
+/// available through [`core::error::Error::source`], along with its structured data.
+#[derive(Debug)]
+pub enum Error {
+    /// A header could not be parsed.
+    Header(ParseHeaderError),
+    /// An alphabet could not be parsed.
+    Alphabet(ParseAlphabetError),
+    /// A board does not follow the rules, or its clues.
+    Board(BoardError),
+    /// A header could not be solved.
+    Solution(SolutionError),
+    /// The operation has been interrupted.
+    Interrupted(Interrupted),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Header(err) => Display::fmt(err, f),
+            Self::Alphabet(err) => Display::fmt(err, f),
+            Self::Board(err) => Display::fmt(err, f),
+            Self::Solution(err) => Display::fmt(err, f),
+            Self::Interrupted(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Header(err) => Some(err),
+            Self::Alphabet(err) => Some(err),
+            Self::Board(err) => Some(err),
+            Self::Solution(err) => Some(err),
+            Self::Interrupted(err) => Some(err),
+        }
+    }
+}
+
+impl From<ParseHeaderError> for Error {
+    fn from(value: ParseHeaderError) -> Self {
+        Self::Header(value)
+    }
+}
+
+impl From<ParseAlphabetError> for Error {
+    fn from(value: ParseAlphabetError) -> Self {
+        Self::Alphabet(value)
+    }
+}
+
+impl From<BoardError> for Error {
+    fn from(value: BoardError) -> Self {
+        Self::Board(value)
+    }
+}
+
+impl From<SolutionError> for Error {
+    fn from(value: SolutionError) -> Self {
+        Self::Solution(value)
+    }
+}
+
+impl From<Interrupted> for Error {
+    fn from(value: Interrupted) -> Self {
+        Self::Interrupted(value)
+    }
+}
@@ -0,0 +1,82 @@
+//! Lists of the puzzles that have already been used, and must not be generated again.
+//!
+//! Each line of the file describes a puzzle, through the seed that was used to generate it,
+//! followed by the canonical hash of its header (as printed by the `hash` subcommand). Empty lines
+//! and lines starting with `#` are ignored.
+
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// A list of used puzzles, to which newly used puzzles are appended.
+pub struct ExclusionList {
+    /// The seeds that have already been used.
+    seeds: HashSet<u64>,
+    /// The canonical hashes of the puzzles that have already been used.
+    hashes: HashSet<u64>,
+    /// The file to which new puzzles are appended.
+    file: BufWriter<File>,
+}
+
+impl ExclusionList {
+    /// Reads the list stored at `path`, creating it if it does not exist.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut seeds = HashSet::new();
+        let mut hashes = HashSet::new();
+
+        for (index, line) in BufReader::new(&file).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid entry on line {}", index + 1),
+                )
+            };
+
+            let mut words = line.split_ascii_whitespace();
+            let seed = words.next().ok_or_else(invalid)?;
+            seeds.insert(seed.parse().map_err(|_| invalid())?);
+            if let Some(hash) = words.next() {
+                hashes.insert(u64::from_str_radix(hash, 16).map_err(|_| invalid())?);
+            }
+            if words.next().is_some() {
+                return Err(invalid());
+            }
+        }
+
+        Ok(Self {
+            seeds,
+            hashes,
+            file: BufWriter::new(file),
+        })
+    }
+
+    /// Returns whether the seed or the puzzle have already been used.
+    pub fn contains(&self, seed: u64, hash: u64) -> bool {
+        self.seeds.contains(&seed) || self.hashes.contains(&hash)
+    }
+
+    /// Adds a puzzle to the list.
+    pub fn insert(&mut self, seed: u64, hash: u64) -> io::Result<()> {
+        self.seeds.insert(seed);
+        self.hashes.insert(hash);
+        writeln!(self.file, "{seed} {hash:016x}")
+    }
+
+    /// Flushes the newly used puzzles to the file.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
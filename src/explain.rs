@@ -0,0 +1,546 @@
+//! Explains how a puzzle can be solved, one human-readable deduction at a time.
+//!
+//! Unlike the solver, which is tuned for speed, the deductions made here are the ones a person
+//! would make: the clues are read first, then cells are filled when they have a single candidate
+//! left, or when a value has a single place left in a row or a column. Only when no such deduction
+//! is possible is a value guessed, and the guess is undone as soon as it leads to a contradiction.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Display;
+
+use crate::board::{Board, Header};
+use crate::sigint;
+use crate::solve::SolutionError;
+
+/// A side of the board, from which clues are seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// A clue of a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clue {
+    /// The side of the board the clue is on.
+    pub side: Side,
+    /// The column of the clue for the top and bottom sides, or its row for the left and right
+    /// sides.
+    pub line: usize,
+    /// The number of buildings seen from the clue.
+    pub value: u8,
+}
+
+impl Clue {
+    /// Returns the clue found at `index` in `header`.
+    pub fn new(header: &Header, index: usize) -> Self {
+        let size = header.size();
+        let side = match index / size {
+            0 => Side::Top,
+            1 => Side::Bottom,
+            2 => Side::Left,
+            _ => Side::Right,
+        };
+
+        Self {
+            side,
+            line: index % size,
+            value: header[index],
+        }
+    }
+
+    /// Returns the cells of the line of this clue, as `(x, y)`, starting from the one next to the
+    /// clue.
+    pub fn cells(&self, size: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (side, line) = (self.side, self.line);
+        (0..size).map(move |i| match side {
+            Side::Top => (line, i),
+            Side::Bottom => (line, size - 1 - i),
+            Side::Left => (i, line),
+            Side::Right => (size - 1 - i, line),
+        })
+    }
+}
+
+impl Display for Clue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = match self.side {
+            Side::Top => "above column",
+            Side::Bottom => "below column",
+            Side::Left => "left of row",
+            Side::Right => "right of row",
+        };
+        write!(f, "the clue {} {side} {}", self.value, self.line + 1)
+    }
+}
+
+/// A row or a column of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Row(y) => write!(f, "row {}", y + 1),
+            Self::Column(x) => write!(f, "column {}", x + 1),
+        }
+    }
+}
+
+/// The cell at `(x, y)`, written `rNcM`.
+struct Cell(usize, usize);
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "r{}c{}", self.1 + 1, self.0 + 1)
+    }
+}
+
+/// Why a guess can't be right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contradiction {
+    /// The cell at `(x, y)` has no candidate left.
+    NoCandidate { x: usize, y: usize },
+    /// The value can't be placed anywhere in the line.
+    NoPlace { line: Line, value: u8 },
+    /// The line of the clue is complete, but does not satisfy it.
+    Clue(Clue),
+}
+
+impl Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoCandidate { x, y } => write!(f, "{} has no candidate left", Cell(*x, *y)),
+            Self::NoPlace { line, value } => write!(f, "{line} has no place left for {value}"),
+            Self::Clue(clue) => write!(f, "{clue} is not satisfied"),
+        }
+    }
+}
+
+/// A single deduction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// A clue equal to the size of the board fixes its whole line, the heights increasing away
+    /// from it.
+    FullClue(Clue),
+    /// A clue of `1` puts the tallest building next to it.
+    TallestFirst {
+        clue: Clue,
+        x: usize,
+        y: usize,
+        value: u8,
+    },
+    /// A clue rules out the tallest buildings close to it. Every cell whose candidates changed is
+    /// listed as `(x, y, max)`, where `max` is the largest height it still accepts.
+    Limit {
+        clue: Clue,
+        limits: Vec<(usize, usize, u8)>,
+    },
+    /// The cell has a single candidate left.
+    NakedSingle { x: usize, y: usize, value: u8 },
+    /// The value has a single place left in the line.
+    HiddenSingle {
+        line: Line,
+        x: usize,
+        y: usize,
+        value: u8,
+    },
+    /// No deduction is left, and a value is guessed.
+    Guess { x: usize, y: usize, value: u8 },
+    /// The guess made on the cell leads to a contradiction, and the value is ruled out.
+    Contradiction {
+        x: usize,
+        y: usize,
+        value: u8,
+        reason: Contradiction,
+    },
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FullClue(clue) => {
+                write!(f, "{clue} forces its line to 1")?;
+                for value in 2..=clue.value {
+                    write!(f, ", {value}")?;
+                }
+                Ok(())
+            }
+            Self::TallestFirst { clue, x, y, value } => {
+                write!(f, "{clue} puts {value} in {}", Cell(*x, *y))
+            }
+            Self::Limit { clue, limits } => {
+                write!(f, "{clue} rules out values")?;
+                for (i, &(x, y, max)) in limits.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, " above {max} in {}", Cell(x, y))?;
+                }
+                Ok(())
+            }
+            Self::NakedSingle { x, y, value } => {
+                write!(f, "{} can only hold {value}", Cell(*x, *y))
+            }
+            Self::HiddenSingle { line, x, y, value } => {
+                write!(
+                    f,
+                    "{line} now has a single place for {value}: {}",
+                    Cell(*x, *y)
+                )
+            }
+            Self::Guess { x, y, value } => write!(f, "guess {} = {value}", Cell(*x, *y)),
+            Self::Contradiction {
+                x,
+                y,
+                value,
+                reason,
+            } => write!(
+                f,
+                "contradiction, {reason}: {} is not {value}",
+                Cell(*x, *y)
+            ),
+        }
+    }
+}
+
+/// A [`Step`], along with the number of guesses it depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The number of guesses that have been made and not undone yet.
+    pub depth: usize,
+    /// The deduction.
+    pub step: Step,
+}
+
+/// The explanation of a puzzle, as returned by [`explain`].
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    /// The deductions, in the order they have been made.
+    pub steps: Vec<Entry>,
+    /// The solution the deductions lead to.
+    pub solution: Board,
+}
+
+/// The candidates of every cell of a board being explained.
+#[derive(Clone)]
+struct Grid {
+    /// The size of the board.
+    size: usize,
+    /// Whether the value `v` is a candidate of the cell at index `i` is stored at
+    /// `i * (size + 1) + v`.
+    candidates: Vec<bool>,
+    /// The values of the cells that have been filled, `0` for the others.
+    values: Vec<u8>,
+}
+
+impl Grid {
+    /// Creates a new [`Grid`] whose cells accept every value.
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            candidates: vec![true; size * size * (size + 1)],
+            values: vec![0; size * size],
+        }
+    }
+
+    /// Returns whether the cell at `(x, y)` accepts `value`.
+    fn allows(&self, x: usize, y: usize, value: u8) -> bool {
+        self.candidates[(x + y * self.size) * (self.size + 1) + value as usize]
+    }
+
+    /// Removes `value` from the candidates of the cell at `(x, y)`, returning whether it was a
+    /// candidate.
+    fn forbid(&mut self, x: usize, y: usize, value: u8) -> bool {
+        let index = (x + y * self.size) * (self.size + 1) + value as usize;
+        core::mem::replace(&mut self.candidates[index], false)
+    }
+
+    /// Returns the candidates of the cell at `(x, y)`.
+    fn candidates(&self, x: usize, y: usize) -> impl Iterator<Item = u8> + '_ {
+        (1..=self.size as u8).filter(move |&value| self.allows(x, y, value))
+    }
+
+    /// Returns the value of the cell at `(x, y)`, `0` when it has not been filled.
+    fn value(&self, x: usize, y: usize) -> u8 {
+        self.values[x + y * self.size]
+    }
+
+    /// Fills the cell at `(x, y)` with `value`, and removes that value from the other cells of its
+    /// row and column.
+    fn place(&mut self, x: usize, y: usize, value: u8) {
+        self.values[x + y * self.size] = value;
+        for other in 1..=self.size as u8 {
+            if other != value {
+                self.forbid(x, y, other);
+            }
+        }
+        for i in 0..self.size {
+            if i != x {
+                self.forbid(i, y, value);
+            }
+            if i != y {
+                self.forbid(x, i, value);
+            }
+        }
+    }
+
+    /// Returns the cells of `line`, as `(x, y)`.
+    fn cells(&self, line: Line) -> impl Iterator<Item = (usize, usize)> {
+        (0..self.size).map(move |i| match line {
+            Line::Row(y) => (i, y),
+            Line::Column(x) => (x, i),
+        })
+    }
+
+    /// Returns every row, then every column.
+    fn lines(&self) -> impl Iterator<Item = Line> {
+        (0..self.size)
+            .map(Line::Row)
+            .chain((0..self.size).map(Line::Column))
+    }
+}
+
+/// Returns the number of buildings seen from `clue`, or `None` when its line is not complete.
+fn seen(grid: &Grid, clue: &Clue) -> Option<u8> {
+    let mut max = 0;
+    let mut count = 0;
+
+    for (x, y) in clue.cells(grid.size) {
+        let value = grid.value(x, y);
+        if value == 0 {
+            return None;
+        }
+        if value > max {
+            max = value;
+            count += 1;
+        }
+    }
+
+    Some(count)
+}
+
+/// The state shared by the whole explanation.
+struct Context<'a> {
+    /// The clues of the header, missing clues excluded.
+    clues: &'a [Clue],
+    /// The deductions made so far.
+    steps: Vec<Entry>,
+    /// The maximum number of deductions.
+    max_steps: Option<usize>,
+}
+
+impl Context<'_> {
+    /// Records a deduction.
+    fn push(&mut self, depth: usize, step: Step) -> Result<(), SolutionError> {
+        if sigint::occured() {
+            return Err(SolutionError::Interrupted);
+        }
+        if self.max_steps.is_some_and(|max| self.steps.len() >= max) {
+            return Err(SolutionError::NodeLimit);
+        }
+
+        self.steps.push(Entry { depth, step });
+        Ok(())
+    }
+}
+
+/// Returns the first contradiction found in `grid`, if any.
+fn contradiction(grid: &Grid, clues: &[Clue]) -> Option<Contradiction> {
+    let size = grid.size;
+
+    for (x, y) in (0..size * size).map(|i| (i % size, i / size)) {
+        if grid.candidates(x, y).next().is_none() {
+            return Some(Contradiction::NoCandidate { x, y });
+        }
+    }
+
+    for line in grid.lines() {
+        for value in 1..=size as u8 {
+            if !grid.cells(line).any(|(x, y)| grid.allows(x, y, value)) {
+                return Some(Contradiction::NoPlace { line, value });
+            }
+        }
+    }
+
+    clues
+        .iter()
+        .find(|clue| seen(grid, clue).is_some_and(|seen| seen != clue.value))
+        .map(|&clue| Contradiction::Clue(clue))
+}
+
+/// Returns a cell that has not been filled yet, but has a single candidate left.
+fn naked_single(grid: &Grid) -> Option<Step> {
+    let size = grid.size;
+
+    (0..size * size)
+        .map(|i| (i % size, i / size))
+        .filter(|&(x, y)| grid.value(x, y) == 0)
+        .find_map(|(x, y)| {
+            let mut candidates = grid.candidates(x, y);
+            match (candidates.next(), candidates.next()) {
+                (Some(value), None) => Some(Step::NakedSingle { x, y, value }),
+                _ => None,
+            }
+        })
+}
+
+/// Returns a value that has not been placed in a line yet, but has a single place left in it.
+fn hidden_single(grid: &Grid) -> Option<Step> {
+    for line in grid.lines() {
+        for value in 1..=grid.size as u8 {
+            let mut places = grid.cells(line).filter(|&(x, y)| grid.allows(x, y, value));
+            if let (Some((x, y)), None) = (places.next(), places.next()) {
+                if grid.value(x, y) == 0 {
+                    return Some(Step::HiddenSingle { line, x, y, value });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Makes deductions on `grid` until it is solved, guessing values when needed.
+///
+/// When `grid` can't be solved, the contradiction that proves it is returned.
+fn search(
+    mut grid: Grid,
+    depth: usize,
+    ctx: &mut Context,
+) -> Result<Result<Grid, Contradiction>, SolutionError> {
+    loop {
+        if let Some(reason) = contradiction(&grid, ctx.clues) {
+            return Ok(Err(reason));
+        }
+
+        if let Some(step) = naked_single(&grid).or_else(|| hidden_single(&grid)) {
+            if let Step::NakedSingle { x, y, value } | Step::HiddenSingle { x, y, value, .. } = step
+            {
+                grid.place(x, y, value);
+            }
+            ctx.push(depth, step)?;
+            continue;
+        }
+
+        // Guess a value for the cell with the fewest candidates.
+        let size = grid.size;
+        let cell = (0..size * size)
+            .map(|i| (i % size, i / size))
+            .filter(|&(x, y)| grid.value(x, y) == 0)
+            .min_by_key(|&(x, y)| grid.candidates(x, y).count());
+        let Some((x, y)) = cell else {
+            return Ok(Ok(grid));
+        };
+        let value = grid.candidates(x, y).next().unwrap();
+
+        ctx.push(depth, Step::Guess { x, y, value })?;
+        let mut guess = grid.clone();
+        guess.place(x, y, value);
+
+        match search(guess, depth + 1, ctx)? {
+            Ok(solved) => return Ok(Ok(solved)),
+            Err(reason) => {
+                grid.forbid(x, y, value);
+                ctx.push(
+                    depth,
+                    Step::Contradiction {
+                        x,
+                        y,
+                        value,
+                        reason,
+                    },
+                )?;
+            }
+        }
+    }
+}
+
+/// Solves the puzzle of `header`, recording every deduction made along the way.
+///
+/// At most `max_steps` deductions are made, after which [`SolutionError::NodeLimit`] is
+/// returned.
+pub fn explain(header: &Header, max_steps: Option<usize>) -> Result<Explanation, SolutionError> {
+    let size = header.size();
+    let clues: Vec<Clue> = (0..size * 4)
+        .map(|index| Clue::new(header, index))
+        .filter(|clue| clue.value != 0)
+        .collect();
+
+    let mut grid = Grid::new(size);
+    let mut ctx = Context {
+        clues: &clues,
+        steps: Vec::new(),
+        max_steps,
+    };
+
+    for &clue in &clues {
+        let value = clue.value as usize;
+
+        if value == size {
+            if clue
+                .cells(size)
+                .enumerate()
+                .all(|(i, (x, y))| grid.value(x, y) == i as u8 + 1)
+            {
+                continue;
+            }
+            for (i, (x, y)) in clue.cells(size).enumerate() {
+                if !grid.allows(x, y, i as u8 + 1) {
+                    return Err(SolutionError::NoSolution);
+                }
+                grid.place(x, y, i as u8 + 1);
+            }
+            ctx.push(0, Step::FullClue(clue))?;
+        } else if value == 1 {
+            let (x, y) = clue.cells(size).next().unwrap();
+            if grid.value(x, y) == size as u8 {
+                continue;
+            }
+            if !grid.allows(x, y, size as u8) {
+                return Err(SolutionError::NoSolution);
+            }
+            grid.place(x, y, size as u8);
+            ctx.push(
+                0,
+                Step::TallestFirst {
+                    clue,
+                    x,
+                    y,
+                    value: size as u8,
+                },
+            )?;
+        } else {
+            // The cell `i` cells away from the clue can't hold more than `size - clue + 1 + i`.
+            let mut limits = Vec::new();
+            for (i, (x, y)) in clue.cells(size).enumerate().take(value - 1) {
+                let max = (size - value + 1 + i) as u8;
+                let mut changed = false;
+                for other in max + 1..=size as u8 {
+                    changed |= grid.forbid(x, y, other);
+                }
+                if changed {
+                    limits.push((x, y, max));
+                }
+            }
+            if !limits.is_empty() {
+                ctx.push(0, Step::Limit { clue, limits })?;
+            }
+        }
+    }
+
+    match search(grid, 0, &mut ctx)? {
+        Ok(grid) => Ok(Explanation {
+            steps: ctx.steps,
+            solution: Board::new(size, grid.values.into_boxed_slice()),
+        }),
+        Err(_) => Err(SolutionError::NoSolution),
+    }
+}
@@ -0,0 +1,174 @@
+//! C bindings to the engine, enabled by the `ffi` feature.
+//!
+//! Boards and headers cross the boundary as flat arrays of bytes, laid out exactly like
+//! [`Board::cells`] and [`Header::clues`]: a board of size `size` has `size * size` cells in
+//! row-major order, and its header has `size * 4` clues. The C declarations of this module are
+//! generated into `include/skyscrapper.h` by `cbindgen`, using `cbindgen.toml`.
+//!
+//! The `capi` feature also exports the same functions under shorter names: `sky_solve`,
+//! `sky_generate` and `sky_check`. They are declared when `SKYSCRAPPER_CAPI` is defined.
+
+use std::slice;
+
+use rand::SeedableRng;
+use rand_xoshiro::Xoroshiro128StarStar;
+
+use crate::board::{Board, Header, MAX_SIZE};
+use crate::solve::SolutionError;
+
+/// The outcome of a call to one of the exported functions.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The call succeeded.
+    Ok = 0,
+    /// A pointer is null, the size is `0` or larger than 64, or a clue exceeds the size of the
+    /// board.
+    InvalidArgument = 1,
+    /// The header has no solution.
+    NoSolution = 2,
+    /// The board does not solve the header.
+    WrongSolution = 3,
+    /// The operation has been interrupted before it could complete.
+    Interrupted = 4,
+}
+
+/// Reads the `size * 4` clues found at `clues`, or returns `None` if they do not make a valid
+/// header.
+///
+/// # Safety
+///
+/// When it is not null, `clues` must be valid for reads of `size * 4` bytes.
+unsafe fn read_header(clues: *const u8, size: usize) -> Option<Header> {
+    if clues.is_null() || !(1..=MAX_SIZE).contains(&size) {
+        return None;
+    }
+
+    Header::try_from(slice::from_raw_parts(clues, size * 4).to_vec()).ok()
+}
+
+/// Solves the header whose `size * 4` clues are found at `clues`, and writes the `size * size`
+/// cells of the solution to `out`.
+///
+/// A clue of `0` is missing. Nothing is written to `out` unless `SKYSCRAPPER_STATUS_OK` is
+/// returned.
+///
+/// # Safety
+///
+/// `clues` must be valid for reads of `size * 4` bytes, and `out` must be valid for writes of
+/// `size * size` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn skyscrapper_solve(clues: *const u8, size: usize, out: *mut u8) -> Status {
+    let header = match read_header(clues, size) {
+        Some(header) if !out.is_null() => header,
+        _ => return Status::InvalidArgument,
+    };
+
+    match crate::solve::solve(&header, Default::default()) {
+        Ok(solution) => {
+            slice::from_raw_parts_mut(out, size * size).copy_from_slice(solution.cells());
+            Status::Ok
+        }
+        Err(SolutionError::NoSolution) => Status::NoSolution,
+        Err(_) => Status::Interrupted,
+    }
+}
+
+/// Generates a random solved board of size `size`, and writes its `size * size` cells to
+/// `out_cells` and its `size * 4` clues to `out_clues`.
+///
+/// The same `seed` always produces the same board, the one `skyscrapper-cli generate --seed`
+/// produces. Nothing is written unless `SKYSCRAPPER_STATUS_OK` is returned.
+///
+/// # Safety
+///
+/// `out_cells` must be valid for writes of `size * size` bytes, and `out_clues` must be valid for
+/// writes of `size * 4` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn skyscrapper_generate(
+    size: usize,
+    seed: u64,
+    out_cells: *mut u8,
+    out_clues: *mut u8,
+) -> Status {
+    if out_cells.is_null() || out_clues.is_null() || !(1..=MAX_SIZE).contains(&size) {
+        return Status::InvalidArgument;
+    }
+
+    let mut rng = Xoroshiro128StarStar::seed_from_u64(seed);
+    let solution = match crate::generate::generate_solution(&mut rng, size as u8) {
+        Ok(solution) => solution,
+        Err(_) => return Status::Interrupted,
+    };
+    let header = crate::generate::solution_to_header(&solution);
+
+    slice::from_raw_parts_mut(out_cells, size * size).copy_from_slice(solution.cells());
+    slice::from_raw_parts_mut(out_clues, size * 4).copy_from_slice(header.clues());
+    Status::Ok
+}
+
+/// Checks whether the `size * size` cells found at `cells` solve the header whose `size * 4`
+/// clues are found at `clues`.
+///
+/// Returns `SKYSCRAPPER_STATUS_OK` when they do, and `SKYSCRAPPER_STATUS_WRONG_SOLUTION` when
+/// they don't.
+///
+/// # Safety
+///
+/// `clues` must be valid for reads of `size * 4` bytes, and `cells` must be valid for reads of
+/// `size * size` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn skyscrapper_check(
+    clues: *const u8,
+    size: usize,
+    cells: *const u8,
+) -> Status {
+    let header = match read_header(clues, size) {
+        Some(header) if !cells.is_null() => header,
+        _ => return Status::InvalidArgument,
+    };
+    let board = Board::new(size, slice::from_raw_parts(cells, size * size).into());
+
+    match crate::check::check_solution(&header, &board) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::WrongSolution,
+    }
+}
+
+/// Same as [`skyscrapper_solve`].
+///
+/// # Safety
+///
+/// See [`skyscrapper_solve`].
+#[cfg(feature = "capi")]
+#[no_mangle]
+pub unsafe extern "C" fn sky_solve(clues: *const u8, size: usize, out: *mut u8) -> Status {
+    skyscrapper_solve(clues, size, out)
+}
+
+/// Same as [`skyscrapper_generate`].
+///
+/// # Safety
+///
+/// See [`skyscrapper_generate`].
+#[cfg(feature = "capi")]
+#[no_mangle]
+pub unsafe extern "C" fn sky_generate(
+    size: usize,
+    seed: u64,
+    out_cells: *mut u8,
+    out_clues: *mut u8,
+) -> Status {
+    skyscrapper_generate(size, seed, out_cells, out_clues)
+}
+
+/// Same as [`skyscrapper_check`].
+///
+/// # Safety
+///
+/// See [`skyscrapper_check`].
+#[cfg(feature = "capi")]
+#[no_mangle]
+pub unsafe extern "C" fn sky_check(clues: *const u8, size: usize, cells: *const u8) -> Status {
+    skyscrapper_check(clues, size, cells)
+}
@@ -3,19 +3,11 @@
 use std::fmt::Display;
 use std::io;
 
+use crate::alphabet::{log10, Alphabet};
 use crate::args;
+use crate::board::{Board, Header};
 use crate::generate;
 
-/// Compute the floored 10-th logarithm of `size`.
-fn log10(mut size: u8) -> usize {
-    let mut log10 = 0;
-    while size != 0 {
-        size /= 10;
-        log10 += 1;
-    }
-    log10
-}
-
 /// Writes the elements of the provided iterator to the standard output. Each element is separated
 /// by exactly `max_len + 1` spaces.
 fn print_iterator<I: IntoIterator>(
@@ -40,13 +32,18 @@ where
 }
 
 /// Prints the provided solution according to the provided output format.
+///
+/// The cells of the solution are written using `alphabet`, while the clues are always written as
+/// numbers.
 pub fn print_solution(
     w: &mut dyn termcolor::WriteColor,
-    solution: &[u8],
-    header: &[u8],
-    size: u8,
+    solution: &Board,
+    header: &Header,
     output: &args::OutputFormat,
+    alphabet: &Alphabet,
 ) -> io::Result<()> {
+    let size = solution.size() as u8;
+
     match output {
         args::OutputFormat::Solution => {
             w.set_color(
@@ -54,24 +51,179 @@ pub fn print_solution(
                     .set_fg(Some(termcolor::Color::Blue))
                     .set_intense(true),
             )?;
-            for chunk in solution.chunks_exact(size as usize) {
-                print_iterator(w, chunk, log10(size))?;
+            for row in solution.rows() {
+                print_iterator(
+                    w,
+                    row.iter().map(|&value| alphabet.symbol(value)),
+                    alphabet.width(size),
+                )?;
                 w.write_all(b"\n")?;
             }
             w.reset()?;
         }
+        args::OutputFormat::Flat => {
+            w.set_color(
+                termcolor::ColorSpec::new()
+                    .set_fg(Some(termcolor::Color::Blue))
+                    .set_intense(true),
+            )?;
+            print_iterator(w, solution.iter().map(|&value| alphabet.symbol(value)), 0)?;
+            w.reset()?;
+            w.write_all(b"\n")?;
+        }
         args::OutputFormat::HeaderLine => {
-            let header = generate::solution_to_header(solution, size);
+            let header = generate::solution_to_header(solution);
             w.set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))?;
-            print_iterator(w, header.as_ref(), 0)?;
+            print_iterator(w, header.clues(), 0)?;
             w.reset()?;
             w.write_all(b"\n")?;
         }
         args::OutputFormat::Header => {
-            print_both(w, solution, header, size, false)?;
+            print_both(w, solution, header, false, alphabet)?;
         }
         args::OutputFormat::Both => {
-            print_both(w, solution, header, size, true)?;
+            print_both(w, solution, header, true, alphabet)?;
+        }
+        args::OutputFormat::Blank => {
+            print_blank(w, header)?;
+        }
+        args::OutputFormat::BlankSvg => {
+            crate::svg::write_puzzle(w, header)?;
+        }
+        args::OutputFormat::BlankPdf => {
+            crate::pdf::write_puzzle(w, header)?;
+        }
+        args::OutputFormat::Puzzlink => {
+            writeln!(w, "{}", crate::puzzlink::encode(header, None))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the provided solution once for each of the provided output formats, separating them by
+/// an empty line.
+///
+/// If `outputs` is empty, the [`args::OutputFormat::Both`] format is used. When `accessible` is
+/// set, the boards are described using [`print_description`].
+pub fn print_outputs(
+    w: &mut dyn termcolor::WriteColor,
+    solution: &Board,
+    header: &Header,
+    outputs: &[args::OutputFormat],
+    alphabet: &Alphabet,
+    accessible: bool,
+) -> io::Result<()> {
+    let print = if accessible {
+        print_description
+    } else {
+        print_solution
+    };
+
+    let mut iter = outputs.iter();
+
+    match iter.next() {
+        Some(first) => print(w, solution, header, first, alphabet)?,
+        None => print(w, solution, header, &args::OutputFormat::Both, alphabet)?,
+    }
+
+    for output in iter {
+        w.write_all(b"\n")?;
+        print(w, solution, header, output, alphabet)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the provided values separated by commas.
+fn write_list<I: IntoIterator>(w: &mut dyn termcolor::WriteColor, it: I) -> io::Result<()>
+where
+    I::Item: Display,
+{
+    for (i, item) in it.into_iter().enumerate() {
+        if i != 0 {
+            w.write_all(b", ")?;
+        }
+        write!(w, "{item}")?;
+    }
+
+    Ok(())
+}
+
+/// The text used to describe a clue, `none` standing for a missing clue.
+fn describe_clue(clue: u8) -> String {
+    match clue {
+        0 => "none".to_string(),
+        _ => clue.to_string(),
+    }
+}
+
+/// Describes the provided solution with sentences rather than grid art, such that it can be read
+/// by a screen reader.
+///
+/// Every row is written on its own line, labeled with its number and followed by its clues. The
+/// formats that already fit on a single line are printed as usual, without colors.
+pub fn print_description(
+    w: &mut dyn termcolor::WriteColor,
+    solution: &Board,
+    header: &Header,
+    output: &args::OutputFormat,
+    alphabet: &Alphabet,
+) -> io::Result<()> {
+    let s = header.size();
+
+    let (show_clues, show_cells) = match output {
+        args::OutputFormat::Solution => (false, true),
+        args::OutputFormat::Header | args::OutputFormat::Blank => (true, false),
+        args::OutputFormat::Both => (true, true),
+        _ => {
+            return print_solution(
+                &mut termcolor::NoColor::new(w),
+                solution,
+                header,
+                output,
+                alphabet,
+            );
+        }
+    };
+
+    if show_clues {
+        let mut sides = vec![
+            ("above columns", header.top()),
+            ("below columns", header.bottom()),
+        ];
+        if !show_cells {
+            sides.extend([
+                ("left of rows", header.left()),
+                ("right of rows", header.right()),
+            ]);
+        }
+
+        for (side, clues) in sides {
+            write!(w, "Clues {side} 1 to {s}: ")?;
+            write_list(w, clues.iter().map(|&c| describe_clue(c)))?;
+            w.write_all(b".\n")?;
+        }
+    }
+
+    if show_cells {
+        for (i, row) in solution.rows().enumerate() {
+            write!(w, "Row {}: ", i + 1)?;
+            write_list(w, row.iter().map(|&value| alphabet.symbol(value)))?;
+            w.write_all(b".")?;
+
+            if show_clues {
+                write!(
+                    w,
+                    " Clue left of row {}: {}. Clue right of row {}: {}.",
+                    i + 1,
+                    describe_clue(header.left()[i]),
+                    i + 1,
+                    describe_clue(header.right()[i]),
+                )?;
+            }
+
+            w.write_all(b"\n")?;
         }
     }
 
@@ -83,20 +235,22 @@ pub fn print_solution(
 /// If `actually_display_solution` is `false`, only the surronding header is displayed.
 pub fn print_both(
     mut w: &mut dyn termcolor::WriteColor,
-    solution: &[u8],
-    header: &[u8],
-    size: u8,
+    solution: &Board,
+    header: &Header,
     actually_display_solution: bool,
+    alphabet: &Alphabet,
 ) -> io::Result<()> {
-    let s = size as usize;
-    let size_len = log10(size);
+    let s = header.size();
+    let size = s as u8;
+    // The clues and the cells share the same columns.
+    let size_len = log10(size).max(alphabet.width(size));
 
     // First Line
     for _ in 0..size_len + 1 {
         w.write_all(b" ")?;
     }
     w.set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))?;
-    print_iterator(&mut w, &header[0..s], size_len)?;
+    print_iterator(&mut w, header.top(), size_len)?;
     w.reset()?;
     for _ in 0..size_len + 1 {
         w.write_all(b" ")?;
@@ -104,9 +258,9 @@ pub fn print_both(
     w.write_all(b"\n")?;
 
     // Middle Lines
-    for (i, chunk) in solution.chunks_exact(s).enumerate() {
+    for (i, row) in solution.rows().enumerate() {
         w.set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))?;
-        write!(w, "{:<size_len$} ", header[2 * s + i])?;
+        write!(w, "{:<size_len$} ", header.left()[i])?;
         w.reset()?;
 
         if actually_display_solution {
@@ -115,7 +269,7 @@ pub fn print_both(
                     .set_fg(Some(termcolor::Color::Blue))
                     .set_intense(true),
             )?;
-            print_iterator(w, chunk, size_len)?;
+            print_iterator(w, row.iter().map(|&value| alphabet.symbol(value)), size_len)?;
             w.reset()?;
         } else {
             for _ in 0..s * (size_len + 1) - 1 {
@@ -124,7 +278,7 @@ pub fn print_both(
         }
 
         w.set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))?;
-        write!(w, " {:<size_len$}\n", header[3 * s + i])?;
+        write!(w, " {:<size_len$}\n", header.right()[i])?;
         w.reset()?;
     }
 
@@ -133,7 +287,7 @@ pub fn print_both(
         w.write_all(b" ")?;
     }
     w.set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))?;
-    print_iterator(w, &header[s..2 * s], size_len)?;
+    print_iterator(w, header.bottom(), size_len)?;
     w.reset()?;
     for _ in 0..size_len + 1 {
         w.write_all(b" ")?;
@@ -142,3 +296,56 @@ pub fn print_both(
 
     Ok(())
 }
+
+/// Prints the header around an empty grid, drawn with ASCII characters so that every cell is
+/// large enough to be filled by hand.
+///
+/// Clues of `0` are left out.
+pub fn print_blank(w: &mut dyn termcolor::WriteColor, header: &Header) -> io::Result<()> {
+    let s = header.size();
+    let len = log10(s as u8);
+
+    // The clues on the left are aligned to the right, and the other ones to the left, such that
+    // they all stick to the grid. Blank clues at the end of a line are not written.
+    let print_clue = |w: &mut dyn termcolor::WriteColor, clue: u8, last: bool| -> io::Result<()> {
+        w.set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))?;
+        match (clue, last) {
+            (0, true) => (),
+            (0, false) => write!(w, "{:len$}", "")?,
+            (_, true) => write!(w, " {clue}")?,
+            (_, false) => write!(w, "{clue:>len$}")?,
+        }
+        w.reset()
+    };
+    let print_clues = |w: &mut dyn termcolor::WriteColor, clues: &[u8]| -> io::Result<()> {
+        write!(w, "{:1$}", "", len + 1)?;
+        for (i, &clue) in clues.iter().enumerate() {
+            w.write_all(if i == 0 { b"  " } else { b"   " })?;
+            print_clue(w, clue, false)?;
+        }
+        w.write_all(b"\n")
+    };
+    let print_border = |w: &mut dyn termcolor::WriteColor| -> io::Result<()> {
+        write!(w, "{:1$}+", "", len + 1)?;
+        for _ in 0..s {
+            write!(w, "{:-<1$}+", "", len + 2)?;
+        }
+        w.write_all(b"\n")
+    };
+
+    print_clues(w, header.top())?;
+    print_border(w)?;
+    for (&left, &right) in header.left().iter().zip(header.right()) {
+        print_clue(w, left, false)?;
+        w.write_all(b" |")?;
+        for _ in 0..s {
+            write!(w, "{:1$}|", "", len + 2)?;
+        }
+        print_clue(w, right, true)?;
+        w.write_all(b"\n")?;
+        print_border(w)?;
+    }
+    print_clues(w, header.bottom())?;
+
+    Ok(())
+}
@@ -1,15 +1,35 @@
 //! Implements functionalities for the `generate` subcommand.
 
+use alloc::vec::Vec;
+
 use rand::{Rng, RngCore};
 
+use crate::board::{Board, Header};
+use crate::error::Interrupted;
+use crate::solve::{Limits, SolutionError};
+
 /// Generates a random Skyscrapper solution.
 ///
-/// `None` is returned when the operation has been interrupted.
-pub fn generate_solution(rng: &mut dyn RngCore, size: u8) -> Option<Box<[u8]>> {
+/// [`Interrupted`] is returned when the operation has been interrupted.
+pub fn generate_solution(rng: &mut dyn RngCore, size: u8) -> Result<Board, Interrupted> {
+    generate_solution_with(rng, size, &[])
+}
+
+/// Generates a random Skyscrapper solution that agrees with `givens`.
+///
+/// `givens` is either empty, or holds one value per cell of the board. Cells whose given value is
+/// `0` are free. The givens must be part of at least one solution.
+///
+/// [`Interrupted`] is returned when the operation has been interrupted.
+pub fn generate_solution_with(
+    rng: &mut dyn RngCore,
+    size: u8,
+    givens: &[u8],
+) -> Result<Board, Interrupted> {
     let size = size as usize;
 
     // The solution that's being created.
-    let mut solution: Box<[u8]> = std::iter::repeat(0).take(size * size).collect();
+    let mut solution = Board::empty(size);
 
     // A simple stack that keeps track of which numbers can be added at a specific position.
     let mut stack: Vec<u8> = Vec::new();
@@ -32,17 +52,19 @@ pub fn generate_solution(rng: &mut dyn RngCore, size: u8) -> Option<Box<[u8]>> {
 
     while index != size * size {
         if crate::sigint::occured() {
-            return None;
+            return Err(Interrupted);
         }
 
         // Compute the numbers available for the next slice.
         let x = index % size;
         let y = index / size;
         stack_slices.push(stack.len());
+        let given = givens.get(index).copied().unwrap_or(0);
         stack.extend(
             (1..=size as u8)
-                .filter(|&c| (0..x).all(|i| solution[i + y * size] != c))
-                .filter(|&c| (0..y).all(|i| solution[x + i * size] != c)),
+                .filter(|&c| given == 0 || c == given)
+                .filter(|&c| (0..x).all(|i| solution.get(i, y) != c))
+                .filter(|&c| (0..y).all(|i| solution.get(x, i) != c)),
         );
 
         while stack.len() == *stack_slices.last().unwrap() {
@@ -55,26 +77,146 @@ pub fn generate_solution(rng: &mut dyn RngCore, size: u8) -> Option<Box<[u8]>> {
 
         // Choose a number on the top of the stack.
         let choosen_index = rng.gen_range(*stack_slices.last().unwrap()..stack.len());
-        solution[index] = stack.swap_remove(choosen_index);
+        solution.cells_mut()[index] = stack.swap_remove(choosen_index);
         index += 1;
     }
 
-    Some(solution)
+    Ok(solution)
+}
+
+/// Generates a random Skyscrapper solution whose header has exactly one solution.
+///
+/// Solutions are generated until one of them is found to be unique.
+///
+/// [`Interrupted`] is returned when the operation has been interrupted.
+pub fn generate_unique_solution(rng: &mut dyn RngCore, size: u8) -> Result<Board, Interrupted> {
+    generate_unique_solution_with(rng, size, Limits::default()).map_err(|_| Interrupted)
+}
+
+/// Like [`generate_unique_solution`], but the uniqueness of every generated solution is checked
+/// within `limits`.
+///
+/// The deadline of `limits` applies to the whole generation, while its node budget applies to
+/// each check. The first error of a check is returned.
+pub fn generate_unique_solution_with(
+    rng: &mut dyn RngCore,
+    size: u8,
+    limits: Limits,
+) -> Result<Board, SolutionError> {
+    loop {
+        let solution = generate_solution(rng, size)?;
+        let header = solution_to_header(&solution);
+
+        match crate::solve::count_solutions(&header, 2, limits) {
+            Ok(1) => return Ok(solution),
+            Ok(_) => (),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// The number of perturbations tried by [`generate_extreme_solution`].
+const EXTREME_ITERATIONS: usize = 200;
+
+/// The number of random paths used to estimate the difficulty of a header in
+/// [`generate_extreme_solution`].
+const EXTREME_PROBES: usize = 64;
+
+/// Applies a random perturbation to `solution`, which remains a valid solution: either two rows,
+/// two columns or two values are swapped.
+fn perturb(rng: &mut dyn RngCore, solution: &mut Board) {
+    let size = solution.size();
+    let solution = solution.cells_mut();
+    let a = rng.gen_range(0..size);
+    let b = (a + rng.gen_range(1..size)) % size;
+
+    match rng.gen_range(0..3) {
+        0 => {
+            for x in 0..size {
+                solution.swap(x + a * size, x + b * size);
+            }
+        }
+        1 => {
+            for y in 0..size {
+                solution.swap(a + y * size, b + y * size);
+            }
+        }
+        _ => {
+            let (a, b) = (a as u8 + 1, b as u8 + 1);
+            for value in solution.iter_mut() {
+                if *value == a {
+                    *value = b;
+                } else if *value == b {
+                    *value = a;
+                }
+            }
+        }
+    }
 }
 
-fn count_viewed(size: u8, get_number: &mut dyn FnMut(usize) -> u8) -> u8 {
+/// Generates a random Skyscrapper solution whose header is as hard as possible for the solver.
+///
+/// Starting from a random solution, random perturbations are tried, and the ones that do not
+/// decrease the estimated size of the search tree (see [`crate::solve::estimate_nodes`]) are
+/// kept. When `unique` is set, only solutions whose header has exactly one solution are
+/// considered.
+///
+/// [`Interrupted`] is returned when the operation has been interrupted.
+pub fn generate_extreme_solution(
+    rng: &mut dyn RngCore,
+    size: u8,
+    unique: bool,
+) -> Result<Board, Interrupted> {
+    let score = |rng: &mut dyn RngCore, solution: &Board| -> Result<f64, Interrupted> {
+        let header = solution_to_header(solution);
+        crate::solve::estimate_nodes(&header, rng, EXTREME_PROBES).map_err(|_| Interrupted)
+    };
+
+    let mut best = if unique {
+        generate_unique_solution(rng, size)?
+    } else {
+        generate_solution(rng, size)?
+    };
+
+    // With less than two values, there is nothing to perturb.
+    if size < 2 {
+        return Ok(best);
+    }
+
+    let mut best_score = score(rng, &best)?;
+
+    for _ in 0..EXTREME_ITERATIONS {
+        let mut candidate = best.clone();
+        perturb(rng, &mut candidate);
+
+        if unique {
+            let header = solution_to_header(&candidate);
+            match crate::solve::count_solutions(&header, 2, Default::default()) {
+                Ok(1) => (),
+                Ok(_) => continue,
+                Err(_) => return Err(Interrupted),
+            }
+        }
+
+        let candidate_score = score(rng, &candidate)?;
+        if candidate_score >= best_score {
+            best = candidate;
+            best_score = candidate_score;
+        }
+    }
+
+    Ok(best)
+}
+
+/// Counts the buildings seen when walking along `line`, from its first cell to its last one.
+fn count_viewed(line: impl Iterator<Item = u8>) -> u8 {
     let mut max = 0;
     let mut count = 0;
 
-    for i in 0..size as usize {
-        let n = get_number(i);
+    for n in line {
         if n > max {
             max = n;
             count += 1;
-
-            if max == size {
-                break;
-            }
         }
     }
 
@@ -82,27 +224,17 @@ fn count_viewed(size: u8, get_number: &mut dyn FnMut(usize) -> u8) -> u8 {
 }
 
 /// Converts an existing Skyscrapper solution into a Skyscrapper header.
-pub fn solution_to_header(solution: &[u8], size: u8) -> Box<[u8]> {
-    let s = size as usize;
-
-    let mut header: Box<[u8]> = std::iter::repeat(0).take(s * 4).collect();
-
-    // Up
-    for x in 0..size as usize {
-        header[x] = count_viewed(size, &mut |i| solution[x + i * s]);
-    }
-    // Down
-    for x in 0..size as usize {
-        header[s + x] = count_viewed(size, &mut |i| solution[x + (s - i - 1) * s]);
-    }
-    // Left
-    for y in 0..size as usize {
-        header[2 * s + y] = count_viewed(size, &mut |i| solution[i + y * s]);
-    }
-    // Right
-    for y in 0..size as usize {
-        header[3 * s + y] = count_viewed(size, &mut |i| solution[s - i - 1 + y * s]);
-    }
-
-    header
+pub fn solution_to_header(solution: &Board) -> Header {
+    let mut clues = Vec::with_capacity(solution.size() * 4);
+
+    clues.extend(solution.cols().map(count_viewed));
+    clues.extend(solution.cols().map(|col| count_viewed(col.rev())));
+    clues.extend(solution.rows().map(|row| count_viewed(row.iter().copied())));
+    clues.extend(
+        solution
+            .rows()
+            .map(|row| count_viewed(row.iter().rev().copied())),
+    );
+
+    Header::new(clues.into_boxed_slice())
 }
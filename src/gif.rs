@@ -0,0 +1,246 @@
+//! Records the animation of a search as an animated GIF image, used by `solve --gif`.
+//!
+//! Frames are drawn like the images of `render --format png`. Every frame only holds the rectangle
+//! that changed since the previous one, the rest of the image being left as it was.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use skyscrapper::board::{Board, Header};
+
+use crate::png::{self, Image};
+
+/// The time a frame stays on screen, in hundredths of a second.
+const FRAME_DELAY: u16 = 2;
+
+/// The time the last frame stays on screen before the animation starts over, in hundredths of a
+/// second.
+const LAST_DELAY: u16 = 300;
+
+/// The colors of the global color table, in the order of their index. The table must hold a power
+/// of two of them.
+const PALETTE: [[u8; 3]; 4] = [
+    [0xFF, 0xFF, 0xFF],
+    [0x00, 0x00, 0x00],
+    [0x60, 0x60, 0x60],
+    [0xFF, 0xFF, 0xFF],
+];
+
+/// The number of bits of the smallest code of the LZW compression, enough to hold an index of
+/// [`PALETTE`].
+const MIN_CODE_SIZE: u8 = 2;
+
+/// The largest number of bits of a code of the LZW compression.
+const MAX_CODE_SIZE: u8 = 12;
+
+/// Returns the index of the color of a pixel drawn by [`png::draw_board`] in [`PALETTE`].
+fn color_index(pixel: u8) -> u8 {
+    match pixel {
+        0x00 => 1,
+        0x60 => 2,
+        _ => 0,
+    }
+}
+
+/// Packs the codes of the LZW compression into bytes, least significant bits first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    /// Appends `code`, which is `size` bits long.
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Returns the written bytes, padding the last one with zeros.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits != 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compresses color indices using the variant of the LZW algorithm used by GIF images.
+fn compress(indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+
+    let mut w = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    // Maps a known sequence, followed by an index, to the code of the resulting sequence.
+    let mut table = HashMap::new();
+    let mut next = end + 1;
+    let mut size = MIN_CODE_SIZE + 1;
+
+    w.write(clear, size);
+
+    let mut indices = indices.iter();
+    let Some(&first) = indices.next() else {
+        w.write(end, size);
+        return w.finish();
+    };
+    let mut current = first as u16;
+
+    for &index in indices {
+        if let Some(&code) = table.get(&(current, index)) {
+            current = code;
+            continue;
+        }
+
+        w.write(current, size);
+        if next == 1 << MAX_CODE_SIZE {
+            // The table is full, start over with a new one.
+            w.write(clear, size);
+            table.clear();
+            next = end + 1;
+            size = MIN_CODE_SIZE + 1;
+        } else {
+            table.insert((current, index), next);
+            next += 1;
+            // The decoder learns about a code one step after the encoder does.
+            if next > 1 << size && size < MAX_CODE_SIZE {
+                size += 1;
+            }
+        }
+        current = index as u16;
+    }
+
+    w.write(current, size);
+    w.write(end, size);
+    w.finish()
+}
+
+/// Writes an animated GIF image, one frame at a time.
+pub struct Animation<W: Write> {
+    w: W,
+    header: Header,
+    /// The image shown by the previous frame.
+    previous: Image,
+    /// The encoded previous frame, which is only written once its delay is known.
+    pending: Vec<u8>,
+    /// The delay of the previous frame.
+    delay: u16,
+}
+
+impl<W: Write> Animation<W> {
+    /// Starts an animation of the search of `header`, whose first frame is the empty grid.
+    pub fn new(mut w: W, header: &Header) -> io::Result<Self> {
+        let image = png::draw_board(header, None);
+
+        w.write_all(b"GIF89a")?;
+        w.write_all(&(image.width as u16).to_le_bytes())?;
+        w.write_all(&(image.height as u16).to_le_bytes())?;
+        // A global color table of 4 colors, no background color and square pixels.
+        w.write_all(&[0x91, 0, 0])?;
+        for color in PALETTE {
+            w.write_all(&color)?;
+        }
+        // Loops forever.
+        w.write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+        let mut pending = Vec::new();
+        encode_frame(&mut pending, &image, 0, 0, image.width, image.height);
+
+        Ok(Self {
+            w,
+            header: header.clone(),
+            previous: image,
+            pending,
+            delay: FRAME_DELAY,
+        })
+    }
+
+    /// Adds a frame showing `board`, whose unknown cells are `0`.
+    pub fn frame(&mut self, board: &Board) -> io::Result<()> {
+        let image = png::draw_board(&self.header, Some(board));
+
+        let width = image.width;
+        let changed = |(i, (a, b)): (usize, (&u8, &u8))| (a != b).then_some((i % width, i / width));
+        let mut pixels = image.pixels.iter().zip(&self.previous.pixels).enumerate();
+        let Some((x, y)) = pixels.find_map(changed) else {
+            // Nothing changed, the previous frame just stays longer on screen.
+            self.delay = self.delay.saturating_add(FRAME_DELAY);
+            return Ok(());
+        };
+        let (mut left, mut right, top, mut bottom) = (x, x, y, y);
+        for (x, y) in pixels.filter_map(changed) {
+            left = left.min(x);
+            right = right.max(x);
+            bottom = y;
+        }
+
+        self.flush(self.delay)?;
+        encode_frame(
+            &mut self.pending,
+            &image,
+            left,
+            top,
+            right + 1 - left,
+            bottom + 1 - top,
+        );
+        self.previous = image;
+        self.delay = FRAME_DELAY;
+        Ok(())
+    }
+
+    /// Writes the last frame and the end of the image, and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush(LAST_DELAY)?;
+        self.w.write_all(b"\x3B")?;
+        Ok(self.w)
+    }
+
+    /// Writes the pending frame, which stays on screen for `delay`.
+    fn flush(&mut self, delay: u16) -> io::Result<()> {
+        // The frame is drawn over the previous ones, which are not disposed of.
+        self.w.write_all(b"\x21\xF9\x04\x04")?;
+        self.w.write_all(&delay.to_le_bytes())?;
+        self.w.write_all(&[0, 0])?;
+        self.w.write_all(&self.pending)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// Appends the descriptor and the compressed data of the rectangle of `image` starting at
+/// `(left, top)` to `out`.
+fn encode_frame(
+    out: &mut Vec<u8>,
+    image: &Image,
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+) {
+    out.push(0x2C);
+    for value in [left, top, width, height] {
+        out.extend_from_slice(&(value as u16).to_le_bytes());
+    }
+    // No local color table, no interlacing.
+    out.push(0);
+
+    let indices: Vec<u8> = (top..top + height)
+        .flat_map(|y| &image.pixels[y * image.width + left..y * image.width + left + width])
+        .map(|&pixel| color_index(pixel))
+        .collect();
+
+    out.push(MIN_CODE_SIZE);
+    for block in compress(&indices).chunks(255) {
+        out.push(block.len() as u8);
+        out.extend_from_slice(block);
+    }
+    out.push(0);
+}
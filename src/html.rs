@@ -0,0 +1,61 @@
+//! Writes printable worksheets as HTML pages.
+//!
+//! Puzzles are embedded as SVG images, and the answer key is moved to a new page when printed.
+
+use std::io::{self, Write};
+
+use crate::board::{Board, Header};
+use crate::svg;
+
+/// The style of the page. Puzzles are laid out in two columns, and never split across pages.
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+.puzzles { display: grid; grid-template-columns: repeat(2, 1fr); gap: 2em; }
+.puzzle { break-inside: avoid; }
+.puzzle svg { width: 100%; height: auto; }
+.answers { break-before: page; }
+";
+
+/// Writes a section of the worksheet, each puzzle below a title made of `title` and its number.
+fn write_section(
+    w: &mut dyn Write,
+    puzzles: &[(Header, Board)],
+    title: &str,
+    solved: bool,
+) -> io::Result<()> {
+    writeln!(w, r#"<div class="puzzles">"#)?;
+    for (i, (header, solution)) in puzzles.iter().enumerate() {
+        writeln!(w, r#"<div class="puzzle">"#)?;
+        writeln!(w, "<h2>{title} {}</h2>", i + 1)?;
+        let solution = if solved { Some(solution) } else { None };
+        svg::write_element(w, header, solution)?;
+        writeln!(w, "</div>")?;
+    }
+    writeln!(w, "</div>")
+}
+
+/// Writes a worksheet: the headers of `puzzles` surrounding empty grids, followed by an answer
+/// key starting on a new page.
+pub fn write_sheet(w: &mut dyn Write, puzzles: &[(Header, Board)]) -> io::Result<()> {
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, r#"<html lang="en">"#)?;
+    writeln!(w, "<head>")?;
+    writeln!(w, r#"<meta charset="utf-8">"#)?;
+    writeln!(w, "<title>Skyscrapers</title>")?;
+    writeln!(w, "<style>\n{STYLE}</style>")?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+
+    writeln!(w, "<section>")?;
+    writeln!(w, "<h1>Skyscrapers</h1>")?;
+    write_section(w, puzzles, "Puzzle", false)?;
+    writeln!(w, "</section>")?;
+
+    writeln!(w, r#"<section class="answers">"#)?;
+    writeln!(w, "<h1>Answer Key</h1>")?;
+    write_section(w, puzzles, "Solution", true)?;
+    writeln!(w, "</section>")?;
+
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")
+}
@@ -0,0 +1,171 @@
+//! Solves skyscrapper problems by encoding them as integer linear programs.
+//!
+//! Unlike the search implemented in [`crate::solve`], this backend only describes what a solution
+//! looks like and lets a generic MILP solver find it. It is mostly useful as a point of comparison.
+//!
+//! # Model
+//!
+//! Every cell `(x, y)` and height `h` gets a binary variable that is set when the cell holds that
+//! height. Every cell holds exactly one height, and every height appears exactly once per row and
+//! per column.
+//!
+//! For every clue, the cells it looks at are numbered from the closest one, and two more families
+//! of binary variables are introduced:
+//!
+//! - `taller(i, h)` is set when one of the first `i + 1` cells holds a height of `h` or more.
+//! - `visible(i, h)` is set when cell `i` holds the height `h` and is visible from the clue, that
+//!   is, when no previous cell holds a height greater than `h`.
+//!
+//! The clue is then the sum of the `visible` variables of its line.
+
+use microlp::{ComparisonOp, OptimizationDirection, Problem, Variable};
+
+use crate::board::{Board, Header};
+use crate::solve::SolutionError;
+
+/// Returns the position of the `i`-th cell seen by the clue at `index` in a header of size `size`.
+pub(crate) fn line_cell(index: usize, i: usize, size: usize) -> (usize, usize) {
+    let k = index % size;
+    let last = size - 1;
+
+    match index / size {
+        // From the top.
+        0 => (k, i),
+        // From the bottom.
+        1 => (k, last - i),
+        // From the left.
+        2 => (i, k),
+        // From the right.
+        _ => (last - i, k),
+    }
+}
+
+/// Solves the provided header using an integer linear program.
+///
+/// The underlying solver cannot be interrupted, and it does not respect any limit.
+pub fn solve(header: &Header) -> Result<Board, SolutionError> {
+    let size = header.size();
+    let mut problem = Problem::new(OptimizationDirection::Minimize);
+
+    // `cells[(x + y * size) * size + h]` is set when `(x, y)` holds the height `h + 1`.
+    let cells: Vec<Variable> = (0..size * size * size)
+        .map(|_| problem.add_binary_var(0.0))
+        .collect();
+    let cell = |x: usize, y: usize, h: usize| cells[(x + y * size) * size + h];
+
+    for a in 0..size {
+        for b in 0..size {
+            // Every cell holds exactly one height.
+            problem.add_constraint(
+                (0..size).map(|h| (cell(a, b, h), 1.0)),
+                ComparisonOp::Eq,
+                1.0,
+            );
+            // Every height appears exactly once per row...
+            problem.add_constraint(
+                (0..size).map(|x| (cell(x, a, b), 1.0)),
+                ComparisonOp::Eq,
+                1.0,
+            );
+            // ...and per column.
+            problem.add_constraint(
+                (0..size).map(|y| (cell(a, y, b), 1.0)),
+                ComparisonOp::Eq,
+                1.0,
+            );
+        }
+    }
+
+    for (index, &clue) in header.iter().enumerate() {
+        // A clue of `0` is missing.
+        if clue == 0 {
+            continue;
+        }
+
+        let at = |i: usize, h: usize| {
+            let (x, y) = line_cell(index, i, size);
+            cell(x, y, h)
+        };
+
+        let taller: Vec<Variable> = (0..size * size)
+            .map(|_| problem.add_binary_var(0.0))
+            .collect();
+        let taller = |i: usize, h: usize| taller[i * size + h];
+
+        let mut visible = Vec::with_capacity(size * size);
+
+        for i in 0..size {
+            for h in 0..size {
+                // The cells of the line that hold a height of `h` or more. At most one of them
+                // is set.
+                let reached = (h..size).map(|g| (at(i, g), 1.0));
+
+                // `taller(i, h)` is the disjunction of `taller(i - 1, h)` and `reached`.
+                problem.add_constraint(
+                    reached.clone().chain([(taller(i, h), -1.0)]),
+                    ComparisonOp::Le,
+                    0.0,
+                );
+                let mut upper: Vec<_> = reached.map(|(var, _)| (var, -1.0)).collect();
+                upper.push((taller(i, h), 1.0));
+                if i > 0 {
+                    problem.add_constraint(
+                        [(taller(i - 1, h), 1.0), (taller(i, h), -1.0)],
+                        ComparisonOp::Le,
+                        0.0,
+                    );
+                    upper.push((taller(i - 1, h), -1.0));
+                }
+                problem.add_constraint(upper, ComparisonOp::Le, 0.0);
+
+                // `visible(i, h)` is set when the cell holds `h` and no previous cell is taller.
+                let var = match (i, h + 1 < size) {
+                    (0, _) | (_, false) => at(i, h),
+                    _ => {
+                        let var = problem.add_binary_var(0.0);
+                        let hidden = taller(i - 1, h + 1);
+                        problem.add_constraint(
+                            [(var, 1.0), (at(i, h), -1.0)],
+                            ComparisonOp::Le,
+                            0.0,
+                        );
+                        problem.add_constraint([(var, 1.0), (hidden, 1.0)], ComparisonOp::Le, 1.0);
+                        problem.add_constraint(
+                            [(var, 1.0), (at(i, h), -1.0), (hidden, 1.0)],
+                            ComparisonOp::Ge,
+                            0.0,
+                        );
+                        var
+                    }
+                };
+                visible.push((var, 1.0));
+            }
+        }
+
+        problem.add_constraint(visible, ComparisonOp::Eq, clue as f64);
+
+        // The model above is enough, but its relaxation is weak. At least `clue - 1` buildings
+        // must be visible after the cell `i`, so it can't be taller than `size - clue + i + 1`.
+        // Stating it explicitly saves the solver a lot of branching.
+        for i in 0..size {
+            for h in (size + i + 1).saturating_sub(clue as usize)..size {
+                problem.add_constraint([(at(i, h), 1.0)], ComparisonOp::Eq, 0.0);
+            }
+        }
+    }
+
+    let solution = match problem.solve() {
+        Ok(ok) => ok,
+        Err(_) => return Err(SolutionError::NoSolution),
+    };
+
+    let board = (0..size * size)
+        .map(|i| {
+            (0..size)
+                .find(|&h| solution[cells[i * size + h]] > 0.5)
+                .map_or(0, |h| h as u8 + 1)
+        })
+        .collect();
+
+    Ok(Board::new(size, board))
+}
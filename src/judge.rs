@@ -0,0 +1,152 @@
+//! Runs external solvers against generated puzzles, used by the `judge` subcommand.
+
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use skyscrapper::alphabet::Alphabet;
+use skyscrapper::args::JudgeInput;
+use skyscrapper::board::Header;
+use skyscrapper::check::{self, BoardError};
+
+/// The outcome of a single test.
+pub enum Outcome {
+    /// The program printed a valid solution.
+    Pass,
+    /// The program printed an invalid solution.
+    Wrong(BoardError),
+    /// The program exited with an error.
+    Failed(ExitStatus),
+    /// The program did not exit in time, and has been killed.
+    Timeout,
+}
+
+impl Outcome {
+    /// Returns whether the test passed.
+    pub fn is_pass(&self) -> bool {
+        matches!(self, Self::Pass)
+    }
+}
+
+/// The result of a single test.
+pub struct Test {
+    /// How the program behaved.
+    pub outcome: Outcome,
+    /// The time the program took to exit.
+    pub time: Duration,
+}
+
+/// Runs `program` on the provided header, and checks the solution it prints.
+///
+/// `args` are passed to the program before the header. An error is returned when the program
+/// can't be started.
+pub fn run(
+    program: &Path,
+    args: &[String],
+    input: JudgeInput,
+    header: &Header,
+    timeout: Duration,
+) -> io::Result<Test> {
+    let clues: Vec<String> = header.iter().map(u8::to_string).collect();
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    match input {
+        JudgeInput::Arg => {
+            command.arg(clues.join(" "));
+        }
+        JudgeInput::Args => {
+            command.args(&clues);
+        }
+        JudgeInput::Stdin => (),
+    }
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+
+    // The standard input is closed right away, such that programs reading it don't wait forever.
+    if let Some(mut stdin) = child.stdin.take() {
+        if input == JudgeInput::Stdin {
+            // The program may exit without reading its input, which is its own problem.
+            let _ = writeln!(stdin, "{}", clues.join(" "));
+        }
+    }
+
+    // The output is read on another thread, such that a program filling the pipe can't block
+    // while it is waited for.
+    let mut stdout = child.stdout.take().expect("the standard output is piped");
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    };
+    let time = start.elapsed();
+    let output = reader.join().unwrap_or_default();
+
+    let outcome = match status {
+        None => Outcome::Timeout,
+        Some(status) if !status.success() => Outcome::Failed(status),
+        Some(_) => match check::check(header, &output, check::Mode::Lenient, &Alphabet::Digits) {
+            Ok(()) => Outcome::Pass,
+            Err(err) => Outcome::Wrong(err),
+        },
+    };
+
+    Ok(Test { outcome, time })
+}
+
+/// Writes the line reporting a single test.
+pub fn write_test(
+    w: &mut dyn termcolor::WriteColor,
+    index: usize,
+    seed: u64,
+    test: &Test,
+) -> io::Result<()> {
+    use termcolor::{Color, ColorSpec};
+
+    write!(w, "test {:>3} (seed {seed}): ", index + 1)?;
+    let (color, verdict) = match test.outcome.is_pass() {
+        true => (Color::Green, "pass"),
+        false => (Color::Red, "FAIL"),
+    };
+    w.set_color(ColorSpec::new().set_fg(Some(color)))?;
+    w.write_all(verdict.as_bytes())?;
+    w.reset()?;
+    write!(w, " {:>10.3} ms", test.time.as_secs_f64() * 1000.0)?;
+
+    match &test.outcome {
+        Outcome::Pass => writeln!(w),
+        Outcome::Wrong(err) => writeln!(w, "  wrong answer: {err}"),
+        Outcome::Failed(status) => writeln!(w, "  the program failed: {status}"),
+        Outcome::Timeout => writeln!(w, "  timed out"),
+    }
+}
+
+/// Writes the summary of the tests.
+pub fn write_summary(w: &mut dyn Write, tests: &[Test]) -> io::Result<()> {
+    let passed = tests.iter().filter(|t| t.outcome.is_pass()).count();
+    let total: Duration = tests.iter().map(|t| t.time).sum();
+    let max = tests.iter().map(|t| t.time).max().unwrap_or_default();
+
+    writeln!(w)?;
+    writeln!(w, "passed:  {passed}/{}", tests.len())?;
+    writeln!(w, "total:   {:.3} ms", total.as_secs_f64() * 1000.0)?;
+    writeln!(w, "slowest: {:.3} ms", max.as_secs_f64() * 1000.0)
+}
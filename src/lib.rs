@@ -0,0 +1,74 @@
+//! The engine behind `skyscrapper-cli`: generates, solves and checks Skyscrapper puzzles.
+//!
+//! Boards are represented by [`board::Board`], which holds heights from `1` to the size of the
+//! board, and the clues surrounding them by [`board::Header`]. A clue of `0` is missing.
+//!
+//! The main entry points are [`solve::solve`], [`generate::generate_solution`] and
+//! [`check::check`]. [`solve::solutions`] enumerates every solution of a header, one at a time.
+//!
+//! ```
+//! use rand::SeedableRng;
+//!
+//! let mut rng = rand_xoshiro::Xoroshiro128StarStar::seed_from_u64(0);
+//! let solution = skyscrapper::generate::generate_solution(&mut rng, 4).unwrap();
+//! let header = skyscrapper::generate::solution_to_header(&solution);
+//! assert_eq!(header.top().len(), 4);
+//!
+//! let solved = skyscrapper::solve::solve(&header, Default::default()).unwrap();
+//! assert!(skyscrapper::check::check_solution(&header, &solved).is_ok());
+//! ```
+//!
+//! The `cli` feature, enabled by default, adds what the command-line interface needs on top of the
+//! engine: colored output, interrupt handling and terminal detection. Without it, the engine does
+//! not touch the terminal, and can be built for targets such as `wasm32-unknown-unknown`.
+//!
+//! The `std` feature, also enabled by default, is only needed by the modules that write documents
+//! or traces, by [`solve::estimate`] and by the ILP solver. Without it, the crate is `no_std` and
+//! only requires `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::write_with_newline)]
+
+extern crate alloc;
+
+pub mod alphabet;
+#[cfg(feature = "std")]
+pub mod analyze;
+pub mod board;
+pub mod check;
+pub mod error;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "cli")]
+pub mod format;
+pub mod generate;
+#[cfg(feature = "std")]
+pub mod html;
+#[cfg(feature = "std")]
+pub mod ilp;
+#[cfg(feature = "std")]
+pub mod pdf;
+#[cfg(feature = "std")]
+pub mod puzzlink;
+#[cfg(feature = "python")]
+mod python;
+pub mod samurai;
+#[cfg(feature = "sat")]
+pub mod sat;
+pub mod solve;
+#[cfg(feature = "std")]
+pub mod svg;
+pub mod symmetry;
+#[cfg(feature = "std")]
+pub mod trace;
+
+// Those modules are shared with the command-line interface, and are not part of the public API.
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod args;
+#[doc(hidden)]
+pub mod sigint;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod terminal;
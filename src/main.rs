@@ -1,24 +1,56 @@
 #![allow(clippy::write_with_newline)]
 
-use std::io::{Read, Write};
+use std::fmt::Display;
+use std::io::{self, Read, Write};
 use std::process::ExitCode;
 use std::time::Duration;
 
-use rand::SeedableRng;
+use rand::{RngCore, SeedableRng};
 use rand_xoshiro::Xoroshiro128StarStar;
 
-mod args;
-mod check;
-mod format;
-mod generate;
-mod solve;
+use skyscrapper::board::{Board, Header};
+use skyscrapper::{
+    alphabet, analyze, args, check, explain, format, generate, html, ilp, pdf, puzzlink, samurai,
+    sigint, solve, svg, symmetry, terminal, trace,
+};
 
-mod sigint;
+mod bench;
+mod checkpoint;
+mod convert;
+mod debug;
+mod encrypt;
+mod exclude;
+mod gif;
+mod judge;
+mod man;
+mod output;
+mod pack;
+mod play;
+mod png;
+mod progress;
+mod report;
+mod serve;
+mod sign;
+
+/// The exit code used when the program detects an internal inconsistency.
+const EXIT_INTERNAL_ERROR: u8 = 4;
+
+/// The exit code used by `check --unique` when the board is valid, but the puzzle has other
+/// solutions, and by `solve --unique` when a header has more than one solution.
+const EXIT_AMBIGUOUS: u8 = 5;
+
+/// The exit code used by `solve --timeout` when the solver did not finish in time.
+const EXIT_TIMEOUT: u8 = 6;
+
+/// The number of seeds `generate` may try for each board it has to write, and `play` for the
+/// puzzle it has to find, when some boards are discarded.
+const MAX_ATTEMPTS: usize = 100;
 
 /// The glorious entry point.
 fn main() -> ExitCode {
     sigint::initialize();
     let args = args::parse();
+    let accessible = args.accessible;
 
     let color_choice = if atty::is(atty::Stream::Stdout) {
         termcolor::ColorChoice::Auto
@@ -27,211 +59,3492 @@ fn main() -> ExitCode {
     };
 
     match args.command {
-        args::Command::Generate { output, seed, size } => {
-            if size == 0 {
+        args::Command::Generate(args) => generate(args, color_choice, accessible),
+        args::Command::Solve(args) => solve(args, color_choice, accessible),
+        args::Command::Samurai { command } => samurai(command, color_choice, accessible),
+        args::Command::Convert { from, to } => {
+            let input = match read_stdin(color_choice) {
+                Some(input) => input,
+                None => return ExitCode::FAILURE,
+            };
+
+            let puzzle = match convert::read(&input, from) {
+                Ok(ok) => ok,
+                Err(convert::ReadError::Board(err)) => {
+                    let stderr = termcolor::StandardStream::stderr(color_choice);
+                    let _ = print_board_error(&mut stderr.lock(), &input, &err, accessible);
+                    return ExitCode::FAILURE;
+                }
+                Err(err) => {
+                    print_error(color_choice, err);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            if puzzle.board.is_none() && convert::needs_board(to) {
+                print_error(
+                    color_choice,
+                    "the input only holds a header, which is not enough to write the board",
+                );
+                return ExitCode::from(2);
+            }
+
+            let stdout = termcolor::StandardStream::stdout(color_choice);
+            let _ = convert::write(&mut stdout.lock(), &puzzle, to);
+            ExitCode::SUCCESS
+        }
+        args::Command::Daily { date, size, output } => {
+            let date = date.unwrap_or_else(args::Date::today);
+            let seed = date.seed(size);
+            print_info(color_choice, format_args!("puzzle of {date} (seed {seed})"));
+
+            let args = args::GenerateArgs {
+                output,
+                seed: Some(seed),
+                unique: true,
+                ..args::GenerateArgs::new(size)
+            };
+            generate(args, color_choice, accessible)
+        }
+        args::Command::Completions { shell } => {
+            let mut command = <args::Args as clap::CommandFactory>::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+            ExitCode::SUCCESS
+        }
+        args::Command::Man { dir } => {
+            let command = <args::Args as clap::CommandFactory>::command();
+            let dir = match dir {
+                Some(dir) => dir,
+                None => {
+                    let _ = man::write_page(&mut io::stdout().lock(), command);
+                    return ExitCode::SUCCESS;
+                }
+            };
+
+            match man::write_pages(&dir, command) {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("{}", path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to write the manual to `{}`: {err}", dir.display()),
+                    );
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        args::Command::Render {
+            header,
+            out,
+            solution,
+            format,
+        } => {
+            if header.size() == 0 {
                 return ExitCode::from(3);
             }
 
-            // Setup a random number generator.
-            // If the user provided a set seed, create the pRNG with it, otherwise generate a
-            // random seed.
-            let mut rng = match seed {
-                Some(seed) => Xoroshiro128StarStar::seed_from_u64(seed),
-                None => Xoroshiro128StarStar::from_entropy(),
+            let extension = out.extension().and_then(|e| e.to_str());
+            let format = match (format, extension) {
+                (Some(format), _) => format,
+                (None, Some(e)) if e.eq_ignore_ascii_case("svg") => args::ImageFormat::Svg,
+                (None, Some(e)) if e.eq_ignore_ascii_case("png") => args::ImageFormat::Png,
+                (None, _) => {
+                    print_error(
+                        color_choice,
+                        "can't guess the format of the image from its extension, use `--format`",
+                    );
+                    return ExitCode::from(2);
+                }
             };
 
-            // Generate the solution.
-            let solution = match generate::generate_solution(&mut rng, size) {
-                Some(s) => s,
-                // The operation has been interrupted by a CTRL+C.
-                None => return ExitCode::SUCCESS,
+            let solution = match solution {
+                true => match solve::solve(&header, solve::Limits::default()) {
+                    Ok(ok) => Some(ok),
+                    // The operation has been interrupted by a CTRL+C.
+                    Err(solve::SolutionError::Interrupted) => return ExitCode::SUCCESS,
+                    Err(err) => {
+                        print_error(color_choice, err);
+                        return ExitCode::FAILURE;
+                    }
+                },
+                false => None,
             };
 
-            let header = generate::solution_to_header(&solution, size);
+            let result = std::fs::File::create(&out).and_then(|file| {
+                let mut w = io::BufWriter::new(file);
+                match format {
+                    args::ImageFormat::Svg => svg::write_board(&mut w, &header, solution.as_ref()),
+                    args::ImageFormat::Png => png::write_board(&mut w, &header, solution.as_ref()),
+                }?;
+                w.flush()
+            });
 
-            // Open the standard output.
-            let stdout = termcolor::StandardStream::stdout(color_choice);
-            let mut stdout = stdout.lock();
+            match result {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to write `{}`: {err}", out.display()),
+                    );
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        args::Command::Sheet {
+            count,
+            size,
+            seed,
+            unique,
+            out,
+            format,
+        } => {
+            if size == 0 {
+                return ExitCode::from(3);
+            }
 
-            // If no output has been specified, use the `OutputFormat::Both` format.
-            if output.is_empty() {
-                let _ = format::print_solution(
-                    &mut stdout,
-                    &solution,
-                    &header,
-                    size,
-                    &args::OutputFormat::Both,
-                );
-            } else {
-                let mut iter = output.iter();
+            let extension = out.extension().and_then(|e| e.to_str());
+            let format = match (format, extension) {
+                (Some(format), _) => format,
+                (None, Some(e)) if e.eq_ignore_ascii_case("pdf") => args::SheetFormat::Pdf,
+                (None, Some(e)) if e.eq_ignore_ascii_case("html") => args::SheetFormat::Html,
+                (None, _) => {
+                    print_error(
+                        color_choice,
+                        "can't guess the format of the worksheet from its extension, use `--format`",
+                    );
+                    return ExitCode::from(2);
+                }
+            };
+
+            let seed = match seed {
+                Some(seed) => seed,
+                None => Xoroshiro128StarStar::from_entropy().next_u64(),
+            };
+
+            let mut puzzles = Vec::with_capacity(count);
+            for i in 0..count {
+                let seed = seed.wrapping_add(i as u64);
+                match generate_board(seed, size, unique, false, false, color_choice) {
+                    Generated::Board(solution, header) => puzzles.push((header, solution)),
+                    // The operation has been interrupted by a CTRL+C.
+                    Generated::Interrupted => return ExitCode::SUCCESS,
+                    Generated::Invalid => return ExitCode::from(EXIT_INTERNAL_ERROR),
+                }
+            }
 
-                if let Some(first) = iter.next() {
-                    let _ = format::print_solution(&mut stdout, &solution, &header, size, first);
+            let result = std::fs::File::create(&out).and_then(|file| {
+                let mut w = io::BufWriter::new(file);
+                match format {
+                    args::SheetFormat::Pdf => pdf::write_sheet(&mut w, &puzzles),
+                    args::SheetFormat::Html => html::write_sheet(&mut w, &puzzles),
+                }?;
+                w.flush()
+            });
+
+            match result {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to write `{}`: {err}", out.display()),
+                    );
+                    ExitCode::FAILURE
                 }
+            }
+        }
+        args::Command::Import { url } => {
+            let (header, givens) = match puzzlink::decode(&url) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    print_error(color_choice, err);
+                    return ExitCode::from(2);
+                }
+            };
 
-                for output in iter {
-                    let _ = stdout.write_all(b"\n");
-                    let _ = format::print_solution(&mut stdout, &solution, &header, size, output);
+            let mut stdout = io::stdout().lock();
+            let clues: Vec<String> = header.iter().map(u8::to_string).collect();
+            let mut res = writeln!(stdout, "{}", clues.join(" "));
+            if let Some(givens) = givens {
+                for row in givens.rows() {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|&v| match v {
+                            0 => ".".to_string(),
+                            _ => v.to_string(),
+                        })
+                        .collect();
+                    res = res.and_then(|()| writeln!(stdout, "{}", cells.join(" ")));
                 }
             }
 
-            ExitCode::SUCCESS
+            match res {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(_) => ExitCode::FAILURE,
+            }
         }
-        args::Command::Solve {
-            header,
-            output,
-            animate,
+        args::Command::Pack {
+            sizes,
+            per_size,
+            seed,
+            unique,
+            out,
         } => {
-            let size = header.0.len() / 4;
+            if sizes.contains(&0) {
+                return ExitCode::from(3);
+            }
+
+            let seed = match seed {
+                Some(seed) => seed,
+                None => Xoroshiro128StarStar::from_entropy().next_u64(),
+            };
+
+            let failed = |err: io::Error| {
+                print_error(
+                    color_choice,
+                    format_args!("failed to write the pack: {err}"),
+                );
+                ExitCode::FAILURE
+            };
+
+            let w: Box<dyn Write> = match &out {
+                Some(path) => match std::fs::File::create(path) {
+                    Ok(file) => Box::new(io::BufWriter::new(file)),
+                    Err(err) => return failed(err),
+                },
+                None => Box::new(io::stdout().lock()),
+            };
+            let mut writer = match pack::Writer::new(w) {
+                Ok(writer) => writer,
+                Err(err) => return failed(err),
+            };
+
+            // Every puzzle is written as soon as it has been rated. When interrupted, the
+            // document is still closed after the puzzles that have already been written.
+            'pack: for size in sizes {
+                for i in 0..per_size {
+                    let seed = seed.wrapping_add(i as u64);
+                    let entry = match generate_board(seed, size, unique, false, false, color_choice)
+                    {
+                        Generated::Board(solution, header) => {
+                            pack::Entry::new(i, seed, header, solution)
+                        }
+                        Generated::Interrupted => None,
+                        Generated::Invalid => return ExitCode::from(EXIT_INTERNAL_ERROR),
+                    };
+                    match entry {
+                        Some(entry) => {
+                            if let Err(err) = writer.push(&entry) {
+                                return failed(err);
+                            }
+                        }
+                        // The operation has been interrupted by a CTRL+C.
+                        None => break 'pack,
+                    }
+                }
+            }
 
+            match writer.finish() {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => failed(err),
+            }
+        }
+        args::Command::Judge {
+            program,
+            args,
+            size,
+            tests,
+            seed,
+            unique,
+            input,
+            timeout,
+        } => {
             if size == 0 {
                 return ExitCode::from(3);
             }
 
+            let timeout = match Duration::try_from_secs_f64(timeout) {
+                Ok(ok) => ok,
+                Err(_) => {
+                    print_error(
+                        color_choice,
+                        "the timeout must be a positive number of seconds",
+                    );
+                    return ExitCode::from(2);
+                }
+            };
+
+            let seed = match seed {
+                Some(seed) => seed,
+                None => Xoroshiro128StarStar::from_entropy().next_u64(),
+            };
+
             let stdout = termcolor::StandardStream::stdout(color_choice);
             let mut stdout = stdout.lock();
 
-            let res = if animate {
-                solve::solve_animated(&header.0, size, &mut stdout, Duration::from_millis(20))
+            let mut results = Vec::with_capacity(tests);
+            for i in 0..tests {
+                let seed = seed.wrapping_add(i as u64);
+                let header = match generate_board(seed, size, unique, false, false, color_choice) {
+                    Generated::Board(_, header) => header,
+                    // The operation has been interrupted by a CTRL+C.
+                    Generated::Interrupted => break,
+                    Generated::Invalid => return ExitCode::from(EXIT_INTERNAL_ERROR),
+                };
+
+                let test = match judge::run(&program, &args, input, &header, timeout) {
+                    Ok(ok) => ok,
+                    Err(err) => {
+                        print_error(
+                            color_choice,
+                            format_args!("failed to run `{}`: {err}", program.display()),
+                        );
+                        return ExitCode::FAILURE;
+                    }
+                };
+
+                let _ = judge::write_test(&mut stdout, i, seed, &test);
+                results.push(test);
+
+                if sigint::occured() {
+                    break;
+                }
+            }
+
+            let _ = judge::write_summary(&mut stdout, &results);
+
+            if results.iter().all(|t| t.outcome.is_pass()) {
+                ExitCode::SUCCESS
             } else {
-                solve::solve(&header.0, size)
+                ExitCode::FAILURE
+            }
+        }
+        args::Command::Serve {
+            port,
+            host,
+            node_limit,
+            timeout,
+            max_size,
+            workers,
+        } => {
+            let listener = match std::net::TcpListener::bind((host.as_str(), port)) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to listen on {host}:{port}: {err}"),
+                    );
+                    return ExitCode::FAILURE;
+                }
+            };
+            print_info(
+                color_choice,
+                format_args!("listening on http://{host}:{port}"),
+            );
+
+            let bounds = serve::Bounds {
+                max_size,
+                max_nodes: node_limit,
+                timeout,
+            };
+            let workers = workers
+                .or_else(|| std::thread::available_parallelism().ok())
+                .unwrap_or(std::num::NonZeroUsize::MIN);
+            match serve::run(listener, bounds, workers) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => {
+                    print_error(color_choice, format_args!("{err}"));
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        args::Command::Batch { command } => batch(command, color_choice, accessible),
+        args::Command::Estimate {
+            header,
+            probes,
+            seed,
+        } => {
+            let size = header.size();
+
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let mut rng = match seed {
+                Some(seed) => Xoroshiro128StarStar::seed_from_u64(seed),
+                None => Xoroshiro128StarStar::from_entropy(),
             };
 
-            let solution = match res {
+            let estimate = match solve::estimate(&header, &mut rng, probes) {
                 Ok(ok) => ok,
-                Err(solve::SolutionError::Interrupted) => return ExitCode::SUCCESS,
-                Err(solve::SolutionError::NoSolution) => {
-                    use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+                Err(_) => return ExitCode::SUCCESS,
+            };
 
-                    let stderr = StandardStream::stderr(color_choice);
-                    let mut stderr = stderr.lock();
+            let low = (estimate.nodes - estimate.nodes_margin).max(0.0);
+            let high = estimate.nodes + estimate.nodes_margin;
+            let ms_per_node = estimate.time_per_node.as_secs_f64() * 1000.0;
 
-                    let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)));
-                    let _ = write!(stderr, "error");
-                    let _ = stderr.reset();
-                    let _ = writeln!(stderr, ": no solution found");
+            let _ = writeln!(
+                io::stdout(),
+                "nodes: {:.0} (95% confidence interval: {low:.0} to {high:.0})\n\
+                 time:  {:.3} ms (95% confidence interval: {:.3} ms to {:.3} ms)",
+                estimate.nodes,
+                estimate.nodes * ms_per_node,
+                low * ms_per_node,
+                high * ms_per_node,
+            );
 
-                    return ExitCode::FAILURE;
+            ExitCode::SUCCESS
+        }
+        args::Command::Count { header, limit } => {
+            if header.size() == 0 {
+                return ExitCode::from(3);
+            }
+
+            let limit = limit.unwrap_or(usize::MAX);
+            let mut solutions = solve::solutions(&header, Default::default());
+            let count = solutions.by_ref().take(limit).count();
+            // The limit is only exceeded when one more solution can be found.
+            let exceeded = count == limit && solutions.next().is_some();
+
+            // The count is incomplete when the search has been stopped early.
+            let incomplete = match solutions.error() {
+                Some(_) => {
+                    print_error(
+                        color_choice,
+                        "the search has been interrupted before it could complete",
+                    );
+                    true
                 }
+                None => exceeded,
             };
 
-            let _ = format::print_solution(&mut stdout, &solution, &header.0, size as u8, &output);
-
+            if incomplete {
+                println!("{count}+");
+            } else {
+                println!("{count}");
+            }
             ExitCode::SUCCESS
         }
-        args::Command::Check { header } => {
-            use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+        args::Command::Rate { header } => {
+            if header.size() == 0 {
+                return ExitCode::from(3);
+            }
 
-            let mut board = Vec::new();
-            match std::io::stdin().read_to_end(&mut board) {
-                Ok(_) => (),
-                Err(_) => {
-                    let stderr = StandardStream::stderr(color_choice);
-                    let mut stderr = stderr.lock();
+            match analyze::rate(&header) {
+                Ok(rating) => {
+                    let _ = analyze::write_rating(&mut io::stdout().lock(), &rating);
+                    ExitCode::SUCCESS
+                }
+                Err(solve::SolutionError::NoSolution) => {
+                    print_error(color_choice, "no solution found");
+                    ExitCode::FAILURE
+                }
+                // The operation has been interrupted by a CTRL+C.
+                Err(_) => ExitCode::SUCCESS,
+            }
+        }
+        args::Command::Bench {
+            corpus: Some(path),
+            compare,
+            ab,
+            timeout,
+            ..
+        } => {
+            let ilp = compare.contains(&args::Algorithm::Ilp)
+                || ab
+                    .iter()
+                    .any(|config| config.algorithm == args::Algorithm::Ilp);
+            if ilp && timeout.is_some() {
+                print_error(
+                    color_choice,
+                    format_args!("the algorithm `ilp` can't be combined with `--timeout`"),
+                );
+                return ExitCode::from(2);
+            }
 
-                    let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)));
-                    let _ = write!(stderr, "error");
-                    let _ = stderr.reset();
-                    let _ = writeln!(stderr, ": failed to read the standard input");
+            let text = match read_input(&path, None, color_choice) {
+                Some(text) => text,
+                None => return ExitCode::FAILURE,
+            };
+            let corpus = match bench::read_corpus(&String::from_utf8_lossy(&text)) {
+                Ok(corpus) => corpus,
+                Err((line, err)) => {
+                    print_error(
+                        color_choice,
+                        format_args!("invalid corpus `{}`, line {line}: {err}", path.display()),
+                    );
+                    return ExitCode::from(2);
+                }
+            };
 
-                    return ExitCode::FAILURE;
+            // When interrupted, the report is still written for the puzzles that have already
+            // been solved.
+            let mut stdout = io::stdout().lock();
+            let _ = match ab.as_slice() {
+                [a, b] => {
+                    let (runs, _) = bench::ab(&corpus, a, b, timeout);
+                    bench::write_ab(&mut stdout, &corpus, a, b, &runs)
                 }
+                _ => {
+                    let (measures, _) = bench::compare(&corpus, &compare, timeout);
+                    bench::write_comparison(&mut stdout, &measures)
+                }
+            };
+            ExitCode::SUCCESS
+        }
+        args::Command::Bench {
+            sizes,
+            iterations,
+            seed,
+            ..
+        } => {
+            if sizes.0.contains(&0) {
+                return ExitCode::from(3);
             }
-            match check::check(&header.0, header.0.len() / 4, &board) {
-                Ok(()) => ExitCode::SUCCESS,
-                Err(err) => {
-                    let stderr = StandardStream::stderr(color_choice);
-                    let mut stderr = stderr.lock();
 
-                    let mut last = 0;
-                    for &check::Span { start, end } in &err.spans {
-                        let _ = stderr.write_all(&board[last..start]);
-                        let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)));
-                        let _ = stderr.write_all(&board[start..end]);
-                        let _ = stderr.reset();
-                        last = end;
-                    }
-                    let _ = stderr.write_all(&board[last..]);
-
-                    let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)));
-                    let _ = write!(stderr, "error");
-                    let _ = stderr.reset();
-
-                    match err.kind {
-                        check::BoardErrorKind::InvalidNumber => {
-                            let _ = write!(stderr, ": `");
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let &check::Span { start, end } = err.spans.first().unwrap();
-                            let _ = stderr.write_all(&board[start..end]);
-                            let _ = stderr.reset();
-                            let _ = writeln!(stderr, "` is not a valid number");
-                        }
-                        check::BoardErrorKind::ColumnCount { expected, given } => {
-                            let _ = write!(stderr, ": expected {} columns, found ", expected);
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = writeln!(stderr, "{given}");
-                            let _ = stderr.reset();
-                        }
-                        check::BoardErrorKind::RowCount { expected, given } => {
-                            let _ = write!(stderr, ": expected {} rows, found ", expected);
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = writeln!(stderr, "{given}");
-                            let _ = stderr.reset();
-                        }
-                        check::BoardErrorKind::UnexpectedCharacter(c) => {
-                            let _ = write!(stderr, ": character `");
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = write!(stderr, "{}", c.escape_ascii());
-                            let _ = stderr.reset();
-                            let _ = writeln!(stderr, "` was not expected");
-                        }
-                        check::BoardErrorKind::TopToBottom { expected, given } => {
-                            let _ = write!(
-                                stderr,
-                                ": from top to bottom, expected view count of {expected}, got "
-                            );
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = writeln!(stderr, "{}", given);
-                            let _ = stderr.reset();
-                        }
-                        check::BoardErrorKind::BottomToTop { expected, given } => {
-                            let _ = write!(
-                                stderr,
-                                ": from bottom to top, expected view count of {expected}, got "
-                            );
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = writeln!(stderr, "{}", given);
-                            let _ = stderr.reset();
-                        }
-                        check::BoardErrorKind::LeftToRight { expected, given } => {
-                            let _ = write!(
-                                stderr,
-                                ": from left to right, expected view count of {expected}, got "
-                            );
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = writeln!(stderr, "{}", given);
-                            let _ = stderr.reset();
-                        }
-                        check::BoardErrorKind::RightToLeft { expected, given } => {
-                            let _ = write!(
-                                stderr,
-                                ": from right to left, expected view count of {expected}, got "
+            // When interrupted, the table is still written for the boards that have already been
+            // solved.
+            let mut rng = Xoroshiro128StarStar::seed_from_u64(seed);
+            let mut measures = Vec::new();
+            for size in sizes.0 {
+                let (m, complete) = bench::run(&mut rng, size, iterations);
+                measures.push(m);
+                if !complete {
+                    break;
+                }
+            }
+
+            let _ = bench::write_table(&mut io::stdout().lock(), &measures);
+            ExitCode::SUCCESS
+        }
+        args::Command::Selftest {
+            sizes,
+            iterations,
+            seed,
+            unique,
+        } => {
+            if sizes.0.contains(&0) {
+                return ExitCode::from(3);
+            }
+
+            let seed = match seed {
+                Some(seed) => seed,
+                None => Xoroshiro128StarStar::from_entropy().next_u64(),
+            };
+            print_info(color_choice, format_args!("starting at seed {seed}"));
+
+            for size in sizes.0 {
+                for i in 0..iterations {
+                    let board_seed = seed.wrapping_add(i as u64);
+                    match generate_board(board_seed, size, unique, false, true, color_choice) {
+                        Generated::Board(..) => (),
+                        // The operation has been interrupted by a CTRL+C.
+                        Generated::Interrupted => return ExitCode::SUCCESS,
+                        Generated::Invalid => {
+                            print_error(
+                                color_choice,
+                                format_args!(
+                                    "the board of size {size} generated with the seed {board_seed} failed the self-test"
+                                ),
                             );
-                            let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)));
-                            let _ = writeln!(stderr, "{}", given);
-                            let _ = stderr.reset();
+                            return ExitCode::from(EXIT_INTERNAL_ERROR);
                         }
-                        check::BoardErrorKind::Doubles => {
-                            let _ = writeln!(
-                                stderr,
-                                ": found twice the same number on the same row/column"
+                    }
+                }
+
+                println!("size {size}: {iterations} boards ok");
+            }
+
+            ExitCode::SUCCESS
+        }
+        args::Command::Verify {
+            seed,
+            size,
+            unique,
+            limit,
+        } => {
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let (solution, header) =
+                match generate_board(seed, size, unique, false, true, color_choice) {
+                    Generated::Board(solution, header) => (solution, header),
+                    // The operation has been interrupted by a CTRL+C.
+                    Generated::Interrupted => return ExitCode::SUCCESS,
+                    Generated::Invalid => {
+                        print_error(
+                            color_choice,
+                            format_args!(
+                                "the board generated with the seed {seed} failed verification"
+                            ),
+                        );
+                        return ExitCode::from(EXIT_INTERNAL_ERROR);
+                    }
+                };
+
+            // One solution past the limit is searched for, such that a count equal to the limit is
+            // not mistaken for a lower bound.
+            let limit = limit.max(1);
+            let solutions = match solve::count_solutions(
+                &header,
+                limit.saturating_add(1),
+                Default::default(),
+            ) {
+                Ok(ok) => ok,
+                // The operation has been interrupted by a CTRL+C.
+                Err(solve::SolutionError::Interrupted) => return ExitCode::SUCCESS,
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to count the solutions: {err}"),
+                    );
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            // The hashes are computed on the output of `-o header-line` and `-o flat`, such that
+            // they can be reproduced with any hashing tool.
+            let join = |values: &[u8]| {
+                let words: Vec<String> = values.iter().map(u8::to_string).collect();
+                format!("{}\n", words.join(" "))
+            };
+            let header_line = join(&header);
+            let flat = join(&solution);
+
+            let (solutions, more) = match solutions > limit {
+                true => (limit, "+"),
+                false => (solutions, ""),
+            };
+            println!("seed:            {seed}");
+            println!("size:            {size}");
+            println!("header:          {}", header_line.trim_end());
+            println!("solutions:       {solutions}{more}");
+            println!("header sha256:   {}", sign::digest(header_line.as_bytes()));
+            println!("solution sha256: {}", sign::digest(flat.as_bytes()));
+            print_valid(color_choice);
+
+            ExitCode::SUCCESS
+        }
+        args::Command::Analyze {
+            size,
+            seeds,
+            hardest,
+            format,
+        } => {
+            if size.contains(&0) {
+                return ExitCode::from(3);
+            }
+
+            // When interrupted, the report is still written for the seeds that have already been
+            // analyzed.
+            let mut by_size = Vec::new();
+            for size in size {
+                let mut samples = Vec::new();
+                for seed in seeds.0.clone() {
+                    match analyze::analyze_seed(seed, size) {
+                        Ok(sample) => samples.push(sample),
+                        Err(solve::SolutionError::Interrupted) => break,
+                        Err(err) => {
+                            print_error(
+                                color_choice,
+                                format_args!("failed to analyze the seed {seed}: {err}"),
                             );
+                            return ExitCode::FAILURE;
                         }
                     }
+                }
+                by_size.push((size, samples));
 
-                    ExitCode::FAILURE
+                if sigint::occured() {
+                    break;
                 }
             }
+
+            let mut stdout = io::stdout().lock();
+            let _ = match format {
+                args::AnalyzeFormat::Report => {
+                    by_size
+                        .iter()
+                        .enumerate()
+                        .try_for_each(|(i, (size, samples))| {
+                            if i != 0 {
+                                writeln!(stdout)?;
+                            }
+                            analyze::write_report(&mut stdout, *size, samples, hardest)
+                        })
+                }
+                args::AnalyzeFormat::Table => {
+                    analyze::write_distribution(&mut stdout, &by_size, false)
+                }
+                args::AnalyzeFormat::Csv => {
+                    analyze::write_distribution(&mut stdout, &by_size, true)
+                }
+            };
+
+            ExitCode::SUCCESS
         }
-    }
+        args::Command::Stats {
+            size,
+            samples,
+            seed,
+        } => {
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            // When interrupted, the report is still written for the boards that have already
+            // been generated.
+            let mut stats = analyze::Statistics::new(size);
+            for i in 0..samples {
+                let seed = seed.wrapping_add(i as u64);
+                match stats.add_seed(seed) {
+                    Ok(()) => (),
+                    Err(solve::SolutionError::Interrupted) => break,
+                    Err(err) => {
+                        print_error(
+                            color_choice,
+                            format_args!("failed to analyze the seed {seed}: {err}"),
+                        );
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+
+            let _ = analyze::write_statistics(&mut io::stdout().lock(), &stats);
+            ExitCode::SUCCESS
+        }
+        args::Command::Check(args) => check(args, color_choice, accessible),
+        args::Command::Hint { header, alphabet } => {
+            hint(header, alphabet, color_choice, accessible)
+        }
+        args::Command::Explain { header, max_steps } => {
+            let size = header.size();
+
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let explanation = match explain::explain(&header, Some(max_steps)) {
+                Ok(ok) => ok,
+                Err(solve::SolutionError::NoSolution) => {
+                    print_error(color_choice, "no solution found");
+                    return ExitCode::FAILURE;
+                }
+                Err(solve::SolutionError::NodeLimit) => {
+                    print_error(
+                        color_choice,
+                        format_args!("the explanation needs more than {max_steps} steps"),
+                    );
+                    return ExitCode::FAILURE;
+                }
+                // The operation has been interrupted by a CTRL+C.
+                Err(_) => return ExitCode::SUCCESS,
+            };
+
+            let stdout = termcolor::StandardStream::stdout(color_choice);
+            let mut stdout = stdout.lock();
+            let width = explanation.steps.len().to_string().len();
+            for (i, entry) in explanation.steps.iter().enumerate() {
+                let _ = writeln!(
+                    stdout,
+                    "{:>width$}. {:indent$}{}",
+                    i + 1,
+                    "",
+                    entry.step,
+                    indent = entry.depth * 2,
+                );
+            }
+            let _ = writeln!(stdout);
+            let _ = format::print_solution(
+                &mut stdout,
+                &explanation.solution,
+                &header,
+                &args::OutputFormat::Both,
+                &alphabet::Alphabet::Digits,
+            );
+
+            ExitCode::SUCCESS
+        }
+        args::Command::Debug { header } => {
+            let size = header.size();
+
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let stdout = termcolor::StandardStream::stdout(color_choice);
+            let _ = debug::run(
+                &header,
+                &mut io::stdin().lock(),
+                &mut stdout.lock(),
+                atty::is(atty::Stream::Stdin),
+            );
+
+            ExitCode::SUCCESS
+        }
+        args::Command::Play(args) => play(args, color_choice),
+        args::Command::AnalyzeClues { header, limit } => {
+            let size = header.size();
+
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let limit = limit.max(1);
+
+            let solutions = match solve::count_solutions(&header, limit, Default::default()) {
+                Ok(ok) => ok,
+                Err(_) => return ExitCode::SUCCESS,
+            };
+
+            let impacts = match analyze::analyze_clues(&header, limit) {
+                Ok(ok) => ok,
+                Err(_) => return ExitCode::SUCCESS,
+            };
+
+            let _ = analyze::write_clue_report(
+                &mut io::stdout().lock(),
+                &header,
+                size,
+                solutions,
+                &impacts,
+                limit,
+            );
+
+            ExitCode::SUCCESS
+        }
+        args::Command::Hash { header, board } => {
+            let size = header.size();
+
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let board = if board {
+                let board = match read_stdin(color_choice) {
+                    Some(board) => board,
+                    None => return ExitCode::FAILURE,
+                };
+
+                let result = check::check(
+                    &header,
+                    &board,
+                    check::Mode::Lenient,
+                    &alphabet::Alphabet::Digits,
+                )
+                .and_then(|()| {
+                    check::parse_values(
+                        &board,
+                        size,
+                        check::Mode::Lenient,
+                        &alphabet::Alphabet::Digits,
+                    )
+                });
+                match result {
+                    Ok(values) => Some(values),
+                    Err(err) => {
+                        let stderr = termcolor::StandardStream::stderr(color_choice);
+                        let mut stderr = stderr.lock();
+                        let _ = print_board_error(&mut stderr, &board, &err, accessible);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            } else {
+                None
+            };
+
+            let hash = symmetry::canonical_hash(&header, board.as_deref(), size);
+            let _ = writeln!(io::stdout(), "{hash:016x}");
+
+            ExitCode::SUCCESS
+        }
+        args::Command::Decrypt { file, passphrase } => {
+            let data = match std::fs::read(&file) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to read `{}`: {err}", file.display()),
+                    );
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            match encrypt::decrypt(&data, &passphrase) {
+                Ok(plaintext) => {
+                    let _ = io::stdout().write_all(&plaintext);
+                    ExitCode::SUCCESS
+                }
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to decrypt `{}`: {err}", file.display()),
+                    );
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        args::Command::VerifySignature {
+            header,
+            sign_key,
+            signature,
+        } => {
+            let size = header.size();
+
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let board = match read_stdin(color_choice) {
+                Some(board) => board,
+                None => return ExitCode::FAILURE,
+            };
+
+            let solution = match check::parse_values(
+                &board,
+                size,
+                check::Mode::Lenient,
+                &alphabet::Alphabet::Digits,
+            ) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    let stderr = termcolor::StandardStream::stderr(color_choice);
+                    let mut stderr = stderr.lock();
+                    let _ = print_board_error(&mut stderr, &board, &err, accessible);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            if sign::verify(sign_key.as_bytes(), &header, &solution, &signature) {
+                ExitCode::SUCCESS
+            } else {
+                print_error(
+                    color_choice,
+                    "the signature does not match the header and the solution",
+                );
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+/// Reads the file at `path`, `-` being the standard input, decrypting it with `passphrase` when
+/// provided.
+///
+/// If the file can't be read or decrypted, the error is printed and `None` is returned.
+fn read_input(
+    path: &std::path::Path,
+    passphrase: Option<&str>,
+    color_choice: termcolor::ColorChoice,
+) -> Option<Vec<u8>> {
+    let input = if path.as_os_str() == "-" {
+        read_stdin(color_choice)?
+    } else {
+        match std::fs::read(path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("failed to read `{}`: {err}", path.display()),
+                );
+                return None;
+            }
+        }
+    };
+
+    match passphrase {
+        Some(passphrase) => match encrypt::decrypt(&input, passphrase) {
+            Ok(ok) => Some(ok),
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("failed to decrypt `{}`: {err}", path.display()),
+                );
+                None
+            }
+        },
+        None => Some(input),
+    }
+}
+
+/// Reads the partial board of `solve --givens` from `path`, `-` being the standard input.
+///
+/// If the board can't be read or parsed, the error is printed and `None` is returned.
+fn read_givens(
+    path: &std::path::Path,
+    passphrase: Option<&str>,
+    header: &Header,
+    alphabet: &alphabet::Alphabet,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> Option<Board> {
+    let input = read_input(path, passphrase, color_choice)?;
+
+    match check::parse_partial(&input, header.size(), check::Mode::Lenient, alphabet) {
+        Ok(givens) => Some(givens),
+        Err(err) => {
+            let stderr = termcolor::StandardStream::stderr(color_choice);
+            let _ = print_board_error(&mut stderr.lock(), &input, &err, accessible);
+            None
+        }
+    }
+}
+
+/// Reads the whole standard input.
+///
+/// If an error occurs, it is printed and `None` is returned.
+fn read_stdin(color_choice: termcolor::ColorChoice) -> Option<Vec<u8>> {
+    let mut input = Vec::new();
+    match std::io::stdin().read_to_end(&mut input) {
+        Ok(_) => Some(input),
+        Err(_) => {
+            print_error(color_choice, "failed to read the standard input");
+            None
+        }
+    }
+}
+
+/// Prints an error message to the standard error.
+fn print_error(color_choice: termcolor::ColorChoice, message: impl Display) {
+    use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+
+    let stderr = StandardStream::stderr(color_choice);
+    let mut stderr = stderr.lock();
+
+    let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)));
+    let _ = write!(stderr, "error");
+    let _ = stderr.reset();
+    let _ = writeln!(stderr, ": {message}");
+}
+
+/// Prints an informational message to the standard error, in the style of [`print_error`].
+fn print_info(color_choice: termcolor::ColorChoice, message: impl Display) {
+    use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+
+    let stderr = StandardStream::stderr(color_choice);
+    let mut stderr = stderr.lock();
+
+    let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)));
+    let _ = write!(stderr, "info");
+    let _ = stderr.reset();
+    let _ = writeln!(stderr, ": {message}");
+}
+
+/// Prints that the checked board is valid, in the style of [`print_error`].
+fn print_valid(color_choice: termcolor::ColorChoice) {
+    use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+
+    let stdout = StandardStream::stdout(color_choice);
+    let mut stdout = stdout.lock();
+
+    let _ = stdout.set_color(ColorSpec::new().set_fg(Some(Color::Green)));
+    let _ = write!(stdout, "ok");
+    let _ = stdout.reset();
+    let _ = writeln!(stdout, ": the board is valid");
+}
+
+/// The outcome of the generation of a single board.
+enum Generated {
+    /// The generated solution and its header.
+    Board(Board, Header),
+    /// The generation has been interrupted.
+    Interrupted,
+    /// The generated board failed verification. The error has already been reported.
+    Invalid,
+}
+
+/// Generates a single board using the provided seed.
+fn generate_board(
+    seed: u64,
+    size: u8,
+    unique: bool,
+    extreme: bool,
+    verify: bool,
+    color_choice: termcolor::ColorChoice,
+) -> Generated {
+    let mut rng = Xoroshiro128StarStar::seed_from_u64(seed);
+
+    let solution = if extreme {
+        generate::generate_extreme_solution(&mut rng, size, unique)
+    } else if unique {
+        generate::generate_unique_solution(&mut rng, size)
+    } else {
+        generate::generate_solution(&mut rng, size)
+    };
+    let solution = match solution {
+        Ok(s) => s,
+        // The operation has been interrupted by a CTRL+C.
+        Err(_) => return Generated::Interrupted,
+    };
+
+    let header = generate::solution_to_header(&solution);
+
+    if (verify || cfg!(debug_assertions)) && !verify_solution(color_choice, &header, &solution) {
+        return Generated::Invalid;
+    }
+
+    if verify && !verify_round_trip(color_choice, &header, &solution, unique) {
+        return Generated::Invalid;
+    }
+
+    Generated::Board(solution, header)
+}
+
+/// Applies `symmetry` to a solution and its header, returning them in the same order.
+fn reorient(solution: &Board, header: &Header, symmetry: symmetry::Symmetry) -> (Board, Header) {
+    let size = solution.size();
+
+    (
+        Board::new(size, symmetry::transform_board(solution, size, symmetry)),
+        Header::new(symmetry::transform_header(header, size, symmetry)),
+    )
+}
+
+/// Executes the `generate` subcommand.
+fn generate(
+    args: args::GenerateArgs,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let size = args.size;
+    if size == 0 {
+        return ExitCode::from(3);
+    }
+
+    if !check_alphabet(color_choice, &args.alphabet, size as usize) {
+        return ExitCode::from(2);
+    }
+
+    if args.output.iter().any(args::OutputFormat::is_document)
+        && (args.output.len() > 1 || args.count > 1 || args.sign_key.is_some())
+    {
+        print_error(
+            color_choice,
+            format_args!(
+                "SVG and PDF documents can only hold a single board, without other formats or signatures"
+            ),
+        );
+        return ExitCode::from(2);
+    }
+
+    // If the user did not provide a seed, generate a random one.
+    // The `i`-th board is generated with the seed `seed + i`, meaning that the generated boards do
+    // not depend on which thread generates them.
+    let seed = match args.seed {
+        Some(seed) => seed,
+        None => Xoroshiro128StarStar::from_entropy().next_u64(),
+    };
+
+    // Open the output.
+    // When the user asks for explicit flushes, the standard output is fully buffered. Otherwise,
+    // it is line-buffered and every puzzle is visible as soon as it has been generated.
+    let mut stdout = match output::Output::open(
+        args.out.as_deref(),
+        args.compress,
+        args.encrypt.as_deref(),
+        color_choice,
+        args.flush_every.is_some(),
+    ) {
+        Ok(ok) => ok,
+        Err(err) => {
+            print_error(
+                color_choice,
+                format_args!("failed to open the output: {err}"),
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut excluded = match args
+        .exclude_seeds
+        .as_deref()
+        .map(exclude::ExclusionList::open)
+    {
+        Some(Ok(ok)) => Some(ok),
+        Some(Err(err)) => {
+            print_error(
+                color_choice,
+                format_args!("failed to read the excluded seeds: {err}"),
+            );
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+    let mut reused = 0;
+
+    // Puzzles that span multiple lines are separated by an empty line.
+    let separate = !matches!(
+        args.output.as_slice(),
+        [args::OutputFormat::HeaderLine]
+            | [args::OutputFormat::Flat]
+            | [args::OutputFormat::Puzzlink]
+    );
+
+    let jobs = args.jobs.get();
+    let count = args.count;
+
+    // The canonical hashes of the boards written so far, when removing duplicates.
+    let mut seen = std::collections::HashSet::new();
+    let mut discarded = 0;
+
+    // Discarded boards are replaced by the next ones. The number of tried seeds is bounded, as
+    // the remaining boards may all be duplicates or already used.
+    let attempts = match args.dedup || excluded.is_some() {
+        true => count.saturating_mul(MAX_ATTEMPTS),
+        false => count,
+    };
+
+    let result = std::thread::scope(|scope| {
+        // The `k`-th worker generates boards `k`, `k + jobs`, `k + 2 * jobs`, ... and sends them
+        // through its own channel. Reading those channels in turn yields the boards in order, and
+        // the bound of the channels prevents workers from running too far ahead.
+        let receivers: Vec<_> = (0..jobs.min(attempts))
+            .map(|k| {
+                let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+                let args = &args;
+                scope.spawn(move || {
+                    for i in (k..attempts).step_by(jobs) {
+                        let generated = generate_board(
+                            seed.wrapping_add(i as u64),
+                            size,
+                            args.unique,
+                            args.extreme,
+                            args.verify,
+                            color_choice,
+                        );
+                        let stop = !matches!(generated, Generated::Board(..));
+
+                        // The receiver is dropped when the output is closed.
+                        if sender.send(generated).is_err() || stop {
+                            break;
+                        }
+                    }
+                });
+                receiver
+            })
+            .collect();
+
+        let mut written = 0;
+        for i in 0..attempts {
+            if written == count {
+                break;
+            }
+
+            let (solution, header) = match receivers[i % jobs].recv() {
+                Ok(Generated::Board(solution, header)) => (solution, header),
+                Ok(Generated::Interrupted) | Err(_) => return Ok(()),
+                Ok(Generated::Invalid) => return Err(ExitCode::from(EXIT_INTERNAL_ERROR)),
+            };
+
+            let hash = symmetry::canonical_hash(&header, None, size as usize);
+            let board_seed = seed.wrapping_add(i as u64);
+
+            if args.dedup && !seen.insert(hash) {
+                discarded += 1;
+                continue;
+            }
+
+            if let Some(excluded) = &excluded {
+                if excluded.contains(board_seed, hash) {
+                    reused += 1;
+                    continue;
+                }
+            }
+
+            let (solution, header) = match args.orient {
+                Some(symmetry) => reorient(&solution, &header, symmetry),
+                None => (solution, header),
+            };
+
+            let mut res = Ok(());
+            if written != 0 && separate {
+                res = stdout.write_all(b"\n");
+            }
+            res = res.and_then(|()| {
+                format::print_outputs(
+                    &mut stdout,
+                    &solution,
+                    &header,
+                    &args.output,
+                    &args.alphabet,
+                    accessible,
+                )
+            });
+            if let Some(key) = &args.sign_key {
+                res = res.and_then(|()| {
+                    let signature = sign::sign(key.as_bytes(), &header, &solution);
+                    writeln!(stdout, "signature: {signature}")
+                });
+            }
+            written += 1;
+            if res.is_ok() && args.flush_every.is_some_and(|n| written % n.get() == 0) {
+                res = stdout.flush();
+            }
+
+            if let Err(err) = res {
+                // The standard output has been closed (e.g. by `head`), no one will read the
+                // next puzzles.
+                if args.out.is_none() {
+                    return Err(ExitCode::SUCCESS);
+                }
+
+                print_error(
+                    color_choice,
+                    format_args!("failed to write the output: {err}"),
+                );
+                return Err(ExitCode::FAILURE);
+            }
+
+            if let Some(excluded) = &mut excluded {
+                if let Err(err) = excluded.insert(board_seed, hash) {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to write the excluded seeds: {err}"),
+                    );
+                    return Err(ExitCode::FAILURE);
+                }
+            }
+        }
+
+        if written < count {
+            print_error(
+                color_choice,
+                format_args!("only {written} of {count} boards could be written after trying {attempts} seeds"),
+            );
+            return Err(ExitCode::FAILURE);
+        }
+
+        Ok(())
+    });
+
+    if args.dedup {
+        print_info(
+            color_choice,
+            format_args!("discarded {discarded} duplicate boards"),
+        );
+    }
+    if let Some(excluded) = excluded {
+        print_info(
+            color_choice,
+            format_args!("discarded {reused} previously used boards"),
+        );
+
+        if let Err(err) = excluded.finish() {
+            print_error(
+                color_choice,
+                format_args!("failed to write the excluded seeds: {err}"),
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if let Err(code) = result {
+        let _ = stdout.finish();
+        return code;
+    }
+
+    if let Err(err) = stdout.finish() {
+        print_error(
+            color_choice,
+            format_args!("failed to write the output: {err}"),
+        );
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Checks that `alphabet` has enough symbols to write a board of size `size`, printing an error
+/// otherwise.
+fn check_alphabet(
+    color_choice: termcolor::ColorChoice,
+    alphabet: &alphabet::Alphabet,
+    size: usize,
+) -> bool {
+    if size <= alphabet.max_size() {
+        return true;
+    }
+
+    print_error(
+        color_choice,
+        format_args!(
+            "the alphabet only has {} symbols, which is not enough for a board of size {size}",
+            alphabet.max_size(),
+        ),
+    );
+    false
+}
+
+/// Reads the header of a `.sky` file, as accepted by `play --file`.
+///
+/// If the file can't be read or parsed, the error is printed and `None` is returned.
+fn read_sky_file(path: &std::path::Path, color_choice: termcolor::ColorChoice) -> Option<Header> {
+    let text = read_input(path, None, color_choice)?;
+    let text: String = String::from_utf8_lossy(&text)
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| [line, "\n"])
+        .collect();
+
+    // A single line holds the header alone, more of them hold the clues around the board.
+    let res = match text.lines().filter(|line| !line.trim().is_empty()).count() {
+        0 | 1 => text.parse().map_err(convert::ReadError::Header),
+        _ => {
+            convert::read(text.as_bytes(), args::ConvertFormat::Header).map(|puzzle| puzzle.header)
+        }
+    };
+
+    match res {
+        Ok(header) => Some(header),
+        Err(err) => {
+            print_error(
+                color_choice,
+                format_args!("invalid puzzle in `{}`: {err}", path.display()),
+            );
+            None
+        }
+    }
+}
+
+/// Executes the `play` subcommand.
+fn play(args: args::PlayArgs, color_choice: termcolor::ColorChoice) -> ExitCode {
+    // The puzzle is only generated once the terminal is known to be usable.
+    let given = if let Some(source) = args.header {
+        match source.read() {
+            Ok(header) => Some(header),
+            Err(err) => {
+                print_error(color_choice, err);
+                return ExitCode::from(2);
+            }
+        }
+    } else if let Some(path) = &args.file {
+        match read_sky_file(path, color_choice) {
+            Some(header) => Some(header),
+            None => return ExitCode::from(2),
+        }
+    } else if let Some(code) = &args.code {
+        match puzzlink::decode(code) {
+            Ok((header, None)) => Some(header),
+            Ok((_, Some(_))) => {
+                print_error(
+                    color_choice,
+                    format_args!("`play` does not support puzzles with given cells"),
+                );
+                return ExitCode::from(2);
+            }
+            Err(err) => {
+                print_error(color_choice, format_args!("invalid puzzle code: {err}"));
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        None
+    };
+
+    // The size is required by the arguments when no puzzle is given.
+    let size = match &given {
+        Some(header) => header.size(),
+        None => args.size.unwrap_or_default() as usize,
+    };
+    if size == 0 {
+        return ExitCode::from(3);
+    }
+
+    // Values are typed using a single key.
+    if size > 9 {
+        print_error(
+            color_choice,
+            format_args!("`play` only supports boards of size 1 to 9"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stdout) {
+        print_error(
+            color_choice,
+            format_args!("`play` must be run in a terminal"),
+        );
+        return ExitCode::from(2);
+    }
+
+    let header = match given {
+        Some(header) => header,
+        None => {
+            let mut rng = match args.seed {
+                Some(seed) => Xoroshiro128StarStar::seed_from_u64(seed),
+                None => Xoroshiro128StarStar::from_entropy(),
+            };
+
+            match args.difficulty {
+                // Puzzles of another difficulty are discarded, until the attempts run out.
+                Some(difficulty) => match analyze::generate_with_difficulty(
+                    &mut rng,
+                    size as u8,
+                    difficulty,
+                    MAX_ATTEMPTS,
+                ) {
+                    Ok(Some(header)) => header,
+                    Ok(None) => {
+                        print_error(
+                            color_choice,
+                            format_args!(
+                                "no {} puzzle of size {size} was found after trying {MAX_ATTEMPTS} boards",
+                                difficulty.name(),
+                            ),
+                        );
+                        return ExitCode::FAILURE;
+                    }
+                    // The operation has been interrupted by a CTRL+C.
+                    Err(_) => return ExitCode::SUCCESS,
+                },
+                None => match generate::generate_unique_solution(&mut rng, size as u8) {
+                    Ok(solution) => generate::solution_to_header(&solution),
+                    // The operation has been interrupted by a CTRL+C.
+                    Err(_) => return ExitCode::SUCCESS,
+                },
+            }
+        }
+    };
+
+    match play::run(&header) {
+        Ok(Some(board)) => {
+            // The game is drawn on the alternate screen, which is gone by now.
+            let mut stdout = termcolor::StandardStream::stdout(color_choice);
+            let _ = format::print_solution(
+                &mut stdout,
+                &board,
+                &header,
+                &args::OutputFormat::Both,
+                &alphabet::Alphabet::Digits,
+            );
+            ExitCode::SUCCESS
+        }
+        Ok(None) => ExitCode::SUCCESS,
+        Err(err) => {
+            print_error(color_choice, format_args!("{err}"));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Executes the `check` subcommand.
+fn check(
+    args: args::CheckArgs,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    // A header of `-` is only read from the standard input now that the arguments are known to
+    // be valid. The board is read from the lines that follow it.
+    let header = match args
+        .header
+        .clone()
+        .map(args::HeaderSource::read)
+        .transpose()
+    {
+        Ok(header) => header,
+        Err(err) => {
+            print_error(color_choice, err);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(path) = &args.watch {
+        return watch_board(&args, header.as_ref(), path, color_choice, accessible);
+    }
+
+    let mut board = match read_stdin(color_choice) {
+        Some(board) => board,
+        None => return ExitCode::FAILURE,
+    };
+    if let Some(passphrase) = &args.decrypt {
+        board = match encrypt::decrypt(&board, passphrase) {
+            Ok(ok) => ok,
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("failed to decrypt the standard input: {err}"),
+                );
+                return ExitCode::FAILURE;
+            }
+        };
+    }
+
+    check_board(&args, header.as_ref(), &board, color_choice, accessible)
+}
+
+/// Checks the provided board against `header`, as requested by the arguments of the `check`
+/// subcommand.
+fn check_board(
+    args: &args::CheckArgs,
+    header: Option<&Header>,
+    board: &[u8],
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let mode = if args.strict {
+        check::Mode::Strict
+    } else {
+        check::Mode::Lenient
+    };
+
+    let size = match header {
+        Some(header) => header.size(),
+        None => match check::infer_size(board, &args.alphabet) {
+            Some(size) => size,
+            None => {
+                print_error(color_choice, "the board is empty");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    if !check_alphabet(color_choice, &args.alphabet, size) {
+        return ExitCode::from(2);
+    }
+
+    // `--complete` conflicts with `--rules-only`, so the header is always provided here.
+    if let (true, Some(header)) = (args.complete, header) {
+        return complete_board(
+            header,
+            board,
+            mode,
+            &args.alphabet,
+            color_choice,
+            accessible,
+        );
+    }
+
+    if let Some(path) = &args.against_solution {
+        let reference = match std::fs::read(path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("failed to read `{}`: {err}", path.display()),
+                );
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let stderr = termcolor::StandardStream::stderr(color_choice);
+        let mut stderr = stderr.lock();
+
+        let reference =
+            match check::parse_values(&reference, size, check::Mode::Lenient, &args.alphabet) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    let _ = writeln!(stderr, "in the reference solution `{}`:", path.display());
+                    let _ = print_board_error(&mut stderr, &reference, &err, accessible);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+        if let Err(err) = check::compare(board, &reference, mode, &args.alphabet) {
+            let _ = print_board_error(&mut stderr, board, &err, accessible);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let result = match header {
+        Some(header) if !args.rules_only => check::check(header, board, mode, &args.alphabet),
+        _ => check::check_rules(board, size, mode, &args.alphabet),
+    };
+    if let Err(err) = result {
+        let stderr = termcolor::StandardStream::stderr(color_choice);
+        let mut stderr = stderr.lock();
+        let _ = print_board_error(&mut stderr, board, &err, accessible);
+        return ExitCode::FAILURE;
+    }
+
+    // `--unique` conflicts with `--rules-only`, so the header is always provided here.
+    if let (true, Some(header)) = (args.unique, header) {
+        // The board is a solution, any other one makes the puzzle ambiguous.
+        match solve::count_solutions(header, 2, Default::default()) {
+            Ok(1) | Err(solve::SolutionError::Interrupted) => (),
+            Ok(0) | Err(_) => {
+                print_error(
+                    color_choice,
+                    "the solver failed to find the solution of the puzzle",
+                );
+                return ExitCode::from(EXIT_INTERNAL_ERROR);
+            }
+            Ok(_) => {
+                print_error(
+                    color_choice,
+                    "the board is valid, but the puzzle has other solutions",
+                );
+                return ExitCode::from(EXIT_AMBIGUOUS);
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Executes `check --watch`, checking the board held by `path` every time the file is saved.
+///
+/// The file is polled until the program is interrupted.
+fn watch_board(
+    args: &args::CheckArgs,
+    header: Option<&Header>,
+    path: &std::path::Path,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let clear = atty::is(atty::Stream::Stdout);
+    let mut last = None;
+
+    while !sigint::occured() {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        if modified.is_some() && modified != last {
+            last = modified;
+
+            if clear {
+                let _ = terminal::clear_screen(&mut io::stdout());
+            }
+            println!("checking `{}`", path.display());
+
+            match std::fs::read(path) {
+                Ok(board) => {
+                    if check_board(args, header, &board, color_choice, accessible)
+                        == ExitCode::SUCCESS
+                    {
+                        print_valid(color_choice);
+                    }
+                }
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to read `{}`: {err}", path.display()),
+                    );
+                }
+            }
+            let _ = io::stdout().flush();
+        }
+
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Executes `check --complete`, printing the provided partial board completed by the solver.
+fn complete_board(
+    header: &Header,
+    input: &[u8],
+    mode: check::Mode,
+    alphabet: &alphabet::Alphabet,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let givens = match check::parse_partial(input, header.size(), mode, alphabet) {
+        Ok(ok) => ok,
+        Err(err) => {
+            let stderr = termcolor::StandardStream::stderr(color_choice);
+            let _ = print_board_error(&mut stderr.lock(), input, &err, accessible);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut completed = None;
+    let res = solve::for_each_solution_with(header, &givens, Default::default(), &mut |board| {
+        completed = Some(board.clone());
+        false
+    });
+
+    let completed = match (res, completed) {
+        (_, Some(board)) => board,
+        // The operation has been interrupted by a CTRL+C.
+        (Err(solve::SolutionError::Interrupted), None) => return ExitCode::SUCCESS,
+        (_, None) => {
+            print_error(
+                color_choice,
+                "the board can't be completed, the filled cells contradict the clues or the rules",
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cfg!(debug_assertions) && !verify_solution(color_choice, header, &completed) {
+        return ExitCode::from(EXIT_INTERNAL_ERROR);
+    }
+
+    let stdout = termcolor::StandardStream::stdout(color_choice);
+    let print = if accessible {
+        format::print_description
+    } else {
+        format::print_solution
+    };
+    let _ = print(
+        &mut stdout.lock(),
+        &completed,
+        header,
+        &args::OutputFormat::Solution,
+        alphabet,
+    );
+    ExitCode::SUCCESS
+}
+
+/// Executes the `hint` subcommand.
+fn hint(
+    header: Header,
+    alphabet: alphabet::Alphabet,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let size = header.size();
+
+    if size == 0 {
+        return ExitCode::from(3);
+    }
+
+    if !check_alphabet(color_choice, &alphabet, size) {
+        return ExitCode::from(2);
+    }
+
+    let input = match read_stdin(color_choice) {
+        Some(input) => input,
+        None => return ExitCode::FAILURE,
+    };
+
+    let board = match check::parse_partial(&input, size, check::Mode::Lenient, &alphabet) {
+        Ok(ok) => ok,
+        Err(err) => {
+            let stderr = termcolor::StandardStream::stderr(color_choice);
+            let _ = print_board_error(&mut stderr.lock(), &input, &err, accessible);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if !board.contains(&0) {
+        print_error(color_choice, "the board is already complete");
+        return ExitCode::FAILURE;
+    }
+
+    let hint = match solve::hint(&header, &board) {
+        Ok(Some(hint)) => hint,
+        Ok(None) => {
+            print_error(color_choice, "no cell can be deduced without guessing");
+            return ExitCode::FAILURE;
+        }
+        Err(_) => {
+            print_error(
+                color_choice,
+                "the board contradicts the clues, some cells must be wrong",
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let reason = match &hint.reason {
+        solve::Reason::Lines => "it is the only value missing from its row and column".into(),
+        solve::Reason::Clues(clues) => {
+            let clues: Vec<String> = clues
+                .iter()
+                .map(|&i| explain::Clue::new(&header, i).to_string())
+                .collect();
+            format!("forced by {}", clues.join(" and "))
+        }
+        solve::Reason::Propagation => {
+            "every other value is ruled out once the values that are already known are removed \
+             from their rows and columns, and the clues are applied again"
+                .into()
+        }
+    };
+
+    println!(
+        "r{}c{} = {} ({reason})",
+        hint.y + 1,
+        hint.x + 1,
+        alphabet.symbol(hint.value),
+    );
+    ExitCode::SUCCESS
+}
+
+/// Executes the `solve` subcommand.
+fn solve(
+    mut args: args::SolveArgs,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    // A header of `-` is only read from the standard input now that the arguments are known to
+    // be valid.
+    let mut headers = Vec::with_capacity(args.header.len());
+    for source in std::mem::take(&mut args.header) {
+        match source.read() {
+            Ok(header) => headers.push(header),
+            Err(err) => {
+                print_error(color_choice, err);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    // Headers read from a file are used like the ones given on the command line, which they
+    // conflict with.
+    if let Some(path) = &args.header_file {
+        let text = match read_input(path, args.decrypt.as_deref(), color_choice) {
+            Some(text) => text,
+            None => return ExitCode::FAILURE,
+        };
+        match String::from_utf8_lossy(&text).parse() {
+            Ok(header) => headers.push(header),
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("invalid header in `{}`: {err}", path.display()),
+                );
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    // A resumed search goes on with the header of its checkpoint.
+    let resumed = match &args.resume {
+        Some(path) => match checkpoint::read(path) {
+            Ok(state) if state.version == checkpoint::VERSION => Some(state),
+            Ok(_) => {
+                print_error(
+                    color_choice,
+                    format_args!(
+                        "`{}` has been written by another version of the program",
+                        path.display(),
+                    ),
+                );
+                return ExitCode::FAILURE;
+            }
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("failed to read `{}`: {err}", path.display()),
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    if let Some(state) = &resumed {
+        headers = vec![state.header.clone()];
+    }
+
+    if headers.iter().any(|header| header.is_empty()) {
+        return ExitCode::from(3);
+    }
+
+    // `--stats-format` implies `--stats`.
+    let stats_format = args
+        .stats_format
+        .or(args.stats.then_some(args::StatsFormat::Text));
+
+    if args.algorithm != args::Algorithm::Backtracking
+        && (args.animate
+            || args.animate_to.is_some()
+            || args.gif.is_some()
+            || args.trace_json.is_some()
+            || args.trace.is_some()
+            || stats_format.is_some()
+            || args.progress
+            || args.random_order
+            || args.node_limit.is_some()
+            || args.all
+            || args.givens.is_some()
+            || args.checkpoint.is_some()
+            || args.resume.is_some())
+    {
+        print_error(
+            color_choice,
+            format_args!(
+                "only `--algorithm backtracking` can be combined with `--animate`, `--trace-json`, `--trace`, `--stats`, `--progress`, `--random-order`, `--node-limit`, `--all`, `--givens`, `--checkpoint` or `--resume`"
+            ),
+        );
+        return ExitCode::from(2);
+    }
+
+    if args.algorithm == args::Algorithm::Ilp && args.timeout.is_some() {
+        print_error(
+            color_choice,
+            format_args!("`--algorithm ilp` can't be combined with `--timeout`"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if args.givens.is_some() && headers.len() > 1 {
+        print_error(
+            color_choice,
+            format_args!("`--givens` can only be used with a single header"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if args.gif.is_some() && headers.len() > 1 {
+        print_error(
+            color_choice,
+            format_args!("`--gif` can only be used with a single header"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if args.checkpoint.is_some() && headers.len() > 1 {
+        print_error(
+            color_choice,
+            format_args!("`--checkpoint` can only be used with a single header"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if args.checkpoint_every.is_some() && args.checkpoint.is_none() && args.resume.is_none() {
+        print_error(
+            color_choice,
+            format_args!("`--checkpoint-every` requires `--checkpoint` or `--resume`"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if args.ndjson && !args.all && !args.stdin {
+        print_error(
+            color_choice,
+            format_args!("`--ndjson` requires `--all` or `--stdin`"),
+        );
+        return ExitCode::from(2);
+    }
+
+    if headers
+        .iter()
+        .any(|header| !check_alphabet(color_choice, &args.alphabet, header.size()))
+    {
+        return ExitCode::from(2);
+    }
+
+    if args.output.is_document() && (headers.len() > 1 || args.stdin) {
+        print_error(
+            color_choice,
+            format_args!("SVG and PDF documents can only hold a single board"),
+        );
+        return ExitCode::from(2);
+    }
+
+    let givens = match (&args.givens, headers.first()) {
+        _ if resumed.is_some() => resumed.as_ref().and_then(|state| state.givens.clone()),
+        (Some(path), Some(header)) => {
+            let passphrase = args.decrypt.as_deref();
+            match read_givens(
+                path,
+                passphrase,
+                header,
+                &args.alphabet,
+                color_choice,
+                accessible,
+            ) {
+                Some(givens) => Some(givens),
+                None => return ExitCode::FAILURE,
+            }
+        }
+        _ => None,
+    };
+    let givens = givens.as_deref().unwrap_or(&[]);
+
+    let from = match (resumed, &args.resume) {
+        (Some(state), Some(path)) => {
+            let header = &headers[0];
+            let fits = (givens.is_empty() || givens.len() == header.size() * header.size())
+                && state.checkpoint.fits(header, givens);
+            if !fits {
+                print_error(
+                    color_choice,
+                    format_args!(
+                        "`{}` does not match the search of its header",
+                        path.display(),
+                    ),
+                );
+                return ExitCode::FAILURE;
+            }
+            state.checkpoint
+        }
+        _ => solve::Checkpoint::default(),
+    };
+
+    let limits = solve::Limits {
+        max_memory: args.max_memory.map(|mb| mb.saturating_mul(1024 * 1024)),
+        max_nodes: args.node_limit,
+        deadline: None,
+        ..Default::default()
+    };
+
+    // Only animations drawn to the terminal leave it in a state to restore when suspended, other
+    // commands don't need a thread waiting for the signals.
+    if args.gif.is_none() && (args.animate || args.animate_to.is_some()) {
+        terminal::initialize();
+    }
+
+    let stdout = termcolor::StandardStream::stdout(color_choice);
+    let mut stdout = stdout.lock();
+
+    if args.all {
+        return solve_all(
+            &args,
+            &headers,
+            givens,
+            limits,
+            &mut stdout,
+            color_choice,
+            accessible,
+        );
+    }
+
+    let mut trace = match &args.trace_json {
+        Some(path) => match output::FileWriter::create(path, args.compress, None) {
+            Ok(ok) => Some(trace::TraceWriter::new(ok)),
+            Err(err) => {
+                print_error(
+                    color_choice,
+                    format_args!("failed to open the trace: {err}"),
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+
+    // The solutions of the headers that have already been solved. This only removes duplicates:
+    // a header given more than once is solved once, and its solution is printed again.
+    let mut deduplicated: std::collections::HashMap<Header, Board> = Default::default();
+    let mut code = ExitCode::SUCCESS;
+    let mut printed = false;
+
+    let mut rng = args.random_order.then(|| match args.seed {
+        Some(seed) => Xoroshiro128StarStar::seed_from_u64(seed),
+        None => Xoroshiro128StarStar::from_entropy(),
+    });
+
+    // Headers read from the standard input are only read once the previous ones are solved.
+    let queue: Box<dyn Iterator<Item = Result<Header, String>>> = match args.stdin {
+        true => Box::new(HeaderLines::new(io::stdin().lock())),
+        false => Box::new(headers.iter().cloned().map(Ok)),
+    };
+
+    for header in queue {
+        let header = match header {
+            Ok(header) => header,
+            Err(err) => {
+                print_failure(&mut stdout, &args, color_choice, None, err, "");
+                code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        let header = &header;
+
+        // The alphabet of the headers of the command line has already been checked.
+        if args.stdin
+            && !args.ndjson
+            && !check_alphabet(color_choice, &args.alphabet, header.size())
+        {
+            code = ExitCode::FAILURE;
+            continue;
+        }
+
+        let limits = solve::Limits {
+            deadline: args
+                .timeout
+                .map(|timeout| std::time::Instant::now() + timeout),
+            ..limits
+        };
+        let mut stats = solve::Stats::default();
+        let start = std::time::Instant::now();
+        let fresh = !deduplicated.contains_key(header);
+        let res = match deduplicated.get(header) {
+            Some(solution) => Ok(solution.clone()),
+            None if args.checkpoint.is_some() || args.resume.is_some() => run_checkpointed(
+                &args,
+                header,
+                givens,
+                limits,
+                &from,
+                &mut stats,
+                color_choice,
+            ),
+            None => match run_solver(
+                &args,
+                header,
+                givens,
+                limits,
+                &mut stats,
+                &mut stdout,
+                trace.as_mut(),
+                rng.as_mut(),
+            ) {
+                Ok(res) => res,
+                Err(err) => {
+                    let what = match args.gif {
+                        Some(_) => "the GIF image",
+                        None => "the trace",
+                    };
+                    print_error(color_choice, format_args!("failed to write {what}: {err}"));
+                    return ExitCode::FAILURE;
+                }
+            },
+        };
+
+        // When multiple headers are solved, errors must say which one failed.
+        let name = if headers.len() > 1 || args.stdin {
+            let views: Vec<String> = header.iter().map(u8::to_string).collect();
+            format!(" for `{}`", views.join(" "))
+        } else {
+            String::new()
+        };
+        let fail = |stdout: &mut termcolor::StandardStreamLock, message: &dyn Display| {
+            print_failure(stdout, &args, color_choice, Some(header), message, &name);
+        };
+
+        let elapsed = start.elapsed();
+
+        let solution = match res {
+            Ok(ok) => ok,
+            Err(solve::SolutionError::Interrupted) => break,
+            Err(solve::SolutionError::NoSolution) => {
+                fail(&mut stdout, &"no solution found");
+                if let Some(format) = stats_format.filter(|_| fresh) {
+                    print_stats(header, &stats, elapsed, format);
+                }
+                code = ExitCode::FAILURE;
+                continue;
+            }
+            Err(solve::SolutionError::MemoryLimit) => {
+                fail(
+                    &mut stdout,
+                    &format_args!(
+                        "the solver exceeded its memory budget of {} MB",
+                        args.max_memory.unwrap_or_default(),
+                    ),
+                );
+                code = ExitCode::FAILURE;
+                continue;
+            }
+            Err(solve::SolutionError::NodeLimit) => {
+                fail(
+                    &mut stdout,
+                    &format_args!(
+                        "the solver exceeded its budget of {} nodes",
+                        args.node_limit.unwrap_or_default(),
+                    ),
+                );
+                print_budget_stats(color_choice, &stats);
+                code = ExitCode::FAILURE;
+                continue;
+            }
+            Err(solve::SolutionError::Timeout) => {
+                fail(
+                    &mut stdout,
+                    &format_args!(
+                        "the solver exceeded its time budget of {:?}",
+                        args.timeout.unwrap_or_default(),
+                    ),
+                );
+                // Only the backtracking search explores nodes.
+                if args.algorithm == args::Algorithm::Backtracking {
+                    print_budget_stats(color_choice, &stats);
+                }
+                code = ExitCode::from(EXIT_TIMEOUT);
+
+                if args.partial {
+                    if let Ok(partial) = solve::deduce(header, givens) {
+                        if printed {
+                            let _ = stdout.write_all(b"\n");
+                        }
+                        let (shown, shown_header) = match args.orient {
+                            Some(symmetry) => reorient(&partial, header, symmetry),
+                            None => (partial, header.clone()),
+                        };
+                        let _ = format::print_solution(
+                            &mut stdout,
+                            &shown,
+                            &shown_header,
+                            &args.output,
+                            &args.alphabet,
+                        );
+                        printed = true;
+                    }
+                }
+                continue;
+            }
+        };
+
+        if (args.verify || cfg!(debug_assertions))
+            && !verify_solution(color_choice, header, &solution)
+        {
+            return ExitCode::from(EXIT_INTERNAL_ERROR);
+        }
+
+        if printed && !args.ndjson {
+            let _ = stdout.write_all(b"\n");
+        }
+        let reoriented = args
+            .orient
+            .map(|symmetry| reorient(&solution, header, symmetry));
+        let (shown, shown_header) = match &reoriented {
+            Some((solution, header)) => (solution, header),
+            None => (&solution, header),
+        };
+        let print = if accessible {
+            format::print_description
+        } else {
+            format::print_solution
+        };
+        let res = if args.ndjson {
+            let puzzle = convert::Puzzle {
+                header: shown_header.clone(),
+                board: Some(shown.clone()),
+            };
+            serde_json::to_writer(&mut stdout, &puzzle)
+                .map_err(io::Error::from)
+                .and_then(|()| writeln!(stdout))
+                .and_then(|()| stdout.flush())
+        } else {
+            print(
+                &mut stdout,
+                shown,
+                shown_header,
+                &args.output,
+                &args.alphabet,
+            )
+            .and_then(|()| stdout.flush())
+        };
+        if res.is_err() && args.stdin {
+            // The standard output has been closed, no one will read the next solutions.
+            break;
+        }
+        printed = true;
+
+        if let Some(format) = stats_format.filter(|_| fresh) {
+            let _ = stdout.flush();
+            print_stats(header, &stats, elapsed, format);
+        }
+
+        if args.unique {
+            match solve::count_solutions_with(header, givens, 2, limits) {
+                Ok(1) => (),
+                Ok(0) => {
+                    print_error(
+                        color_choice,
+                        format_args!("the solver failed to find the solution again{name}"),
+                    );
+                    return ExitCode::from(EXIT_INTERNAL_ERROR);
+                }
+                Ok(_) => {
+                    print_error(
+                        color_choice,
+                        format_args!("the puzzle has more than one solution{name}"),
+                    );
+                    code = ExitCode::from(EXIT_AMBIGUOUS);
+                }
+                Err(solve::SolutionError::Interrupted) => break,
+                Err(err) => {
+                    print_error(
+                        color_choice,
+                        format_args!("failed to check the uniqueness of the solution{name}: {err}"),
+                    );
+                    code = match err {
+                        solve::SolutionError::Timeout => ExitCode::from(EXIT_TIMEOUT),
+                        _ => ExitCode::FAILURE,
+                    };
+                }
+            }
+        }
+
+        // Headers streamed through the standard input are not kept, such that a long stream
+        // does not use more and more memory.
+        if !args.stdin {
+            deduplicated.insert(header.clone(), solution);
+        }
+    }
+
+    if let Some(trace) = trace {
+        if let Err(err) = trace.into_inner().finish() {
+            print_error(
+                color_choice,
+                format_args!("failed to write the trace: {err}"),
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+
+    code
+}
+
+/// The headers read by `solve --stdin`, one per line.
+///
+/// Empty lines and lines starting with `#` are skipped. Lines that are not headers produce an
+/// error, and so does a failure to read the input, which ends the headers.
+struct HeaderLines<R> {
+    /// The lines of the input.
+    lines: io::Lines<R>,
+    /// The number of the last line read, starting at 1.
+    line: usize,
+    /// Whether the input could not be read.
+    failed: bool,
+}
+
+impl<R: io::BufRead> HeaderLines<R> {
+    /// Creates a new [`HeaderLines`] reading `input`.
+    fn new(input: R) -> Self {
+        Self {
+            lines: input.lines(),
+            line: 0,
+            failed: false,
+        }
+    }
+}
+
+impl<R: io::BufRead> Iterator for HeaderLines<R> {
+    type Item = Result<Header, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            self.line += 1;
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(format!("failed to read the standard input: {err}")));
+                }
+            };
+
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            return Some(match text.parse::<Header>() {
+                Ok(header) => Ok(header),
+                Err(err) => Err(format!("line {}: {err}", self.line)),
+            });
+        }
+
+        None
+    }
+}
+
+/// Reports that no solution of `header` could be found, `name` naming the header in the error
+/// message.
+///
+/// With `solve --stdin --ndjson`, a line holding the error is also written to `stdout`, such that
+/// every header produces a line.
+fn print_failure(
+    stdout: &mut dyn Write,
+    args: &args::SolveArgs,
+    color_choice: termcolor::ColorChoice,
+    header: Option<&Header>,
+    message: impl Display,
+    name: &str,
+) {
+    print_error(color_choice, format_args!("{message}{name}"));
+
+    if args.stdin && args.ndjson {
+        let failure = Failure {
+            header,
+            error: message.to_string(),
+        };
+        let _ = serde_json::to_writer(&mut *stdout, &failure)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(stdout))
+            .and_then(|()| stdout.flush());
+    }
+}
+
+/// A header that could not be solved, as written by `solve --stdin --ndjson`.
+#[derive(serde::Serialize)]
+struct Failure<'a> {
+    /// The header, unless the line could not be parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header: Option<&'a Header>,
+    /// The error that prevented finding a solution.
+    error: String,
+}
+
+/// The statistics of the search of a header, as written by `solve --stats-format json`.
+#[derive(serde::Serialize)]
+struct StatsLine<'a> {
+    header: &'a Header,
+    cells: usize,
+    deduced: usize,
+    guesses: usize,
+    backtracks: usize,
+    max_depth: usize,
+    time_ms: f64,
+}
+
+/// Prints how far the search went before exceeding its budget of nodes or of time.
+fn print_budget_stats(color_choice: termcolor::ColorChoice, stats: &solve::Stats) {
+    print_info(
+        color_choice,
+        format_args!(
+            "explored {} nodes, backtracked {} times, reached a depth of {}",
+            stats.steps, stats.backtracks, stats.max_depth,
+        ),
+    );
+}
+
+/// Writes the statistics of the search of `header`, as requested by `solve --stats`.
+fn print_stats(
+    header: &Header,
+    stats: &solve::Stats,
+    elapsed: Duration,
+    format: args::StatsFormat,
+) {
+    let cells = header.size() * header.size();
+    if format == args::StatsFormat::Json {
+        let line = StatsLine {
+            header,
+            cells,
+            deduced: stats.deduced,
+            guesses: stats.steps,
+            backtracks: stats.backtracks,
+            max_depth: stats.max_depth,
+            // Rounded to the microsecond, like the text output.
+            time_ms: elapsed.as_micros() as f64 / 1000.0,
+        };
+        let mut stderr = io::stderr().lock();
+        let _ = serde_json::to_writer(&mut stderr, &line);
+        let _ = stderr.write_all(b"\n");
+        return;
+    }
+
+    eprintln!("deduced:    {} of {cells} cells", stats.deduced);
+    eprintln!("guesses:    {}", stats.steps);
+    eprintln!("backtracks: {}", stats.backtracks);
+    eprintln!("max depth:  {}", stats.max_depth);
+    eprintln!("time:       {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+}
+
+/// Executes `solve --all`, printing every solution of every header followed by their count.
+///
+/// Only the solutions that agree with `givens` are printed.
+fn solve_all(
+    args: &args::SolveArgs,
+    headers: &[Header],
+    givens: &[u8],
+    limits: solve::Limits,
+    stdout: &mut dyn termcolor::WriteColor,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let print = if accessible {
+        format::print_description
+    } else {
+        format::print_solution
+    };
+    let mut code = ExitCode::SUCCESS;
+
+    for (i, header) in headers.iter().enumerate() {
+        // When multiple headers are solved, errors must say which one failed.
+        let name = if headers.len() > 1 {
+            let views: Vec<String> = header.iter().map(u8::to_string).collect();
+            format!(" for `{}`", views.join(" "))
+        } else {
+            String::new()
+        };
+
+        if i != 0 && !args.ndjson {
+            let _ = stdout.write_all(b"\n");
+        }
+
+        let limits = solve::Limits {
+            deadline: args
+                .timeout
+                .map(|timeout| std::time::Instant::now() + timeout),
+            ..limits
+        };
+        let mut count = 0;
+        let mut solutions = solve::solutions_with(header, givens, limits);
+        for solution in solutions.by_ref() {
+            if (args.verify || cfg!(debug_assertions))
+                && !verify_solution(color_choice, header, &solution)
+            {
+                return ExitCode::from(EXIT_INTERNAL_ERROR);
+            }
+
+            let (shown, shown_header) = match args.orient {
+                Some(symmetry) => reorient(&solution, header, symmetry),
+                None => (solution, header.clone()),
+            };
+
+            let res = if args.ndjson {
+                let puzzle = convert::Puzzle {
+                    header: shown_header,
+                    board: Some(shown),
+                };
+                serde_json::to_writer(&mut *stdout, &puzzle)
+                    .map_err(io::Error::from)
+                    .and_then(|()| writeln!(stdout))
+            } else {
+                let separator = match count {
+                    0 => Ok(()),
+                    _ => stdout.write_all(b"\n"),
+                };
+                separator.and_then(|()| {
+                    print(stdout, &shown, &shown_header, &args.output, &args.alphabet)
+                })
+            };
+            if res.is_err() {
+                // The standard output has been closed, no one will read the next solutions.
+                return code;
+            }
+
+            count += 1;
+        }
+
+        match solutions.error() {
+            None => (),
+            // The operation has been interrupted by a CTRL+C.
+            Some(solve::SolutionError::Interrupted) => {
+                print_error(
+                    color_choice,
+                    "the search has been interrupted before it could complete",
+                );
+                return code;
+            }
+            Some(solve::SolutionError::MemoryLimit) => {
+                print_error(
+                    color_choice,
+                    format_args!(
+                        "the solver exceeded its memory budget of {} MB{name}",
+                        args.max_memory.unwrap_or_default(),
+                    ),
+                );
+                code = ExitCode::FAILURE;
+            }
+            Some(solve::SolutionError::NodeLimit) => {
+                print_error(
+                    color_choice,
+                    format_args!(
+                        "the solver exceeded its budget of {} nodes{name}",
+                        args.node_limit.unwrap_or_default(),
+                    ),
+                );
+                code = ExitCode::FAILURE;
+            }
+            Some(solve::SolutionError::Timeout) => {
+                print_error(
+                    color_choice,
+                    format_args!(
+                        "the solver exceeded its time budget of {:?}{name}",
+                        args.timeout.unwrap_or_default(),
+                    ),
+                );
+                code = ExitCode::from(EXIT_TIMEOUT);
+            }
+            Some(solve::SolutionError::NoSolution) => (),
+        }
+
+        if count == 0 && solutions.error().is_none() {
+            print_error(color_choice, format_args!("no solution found{name}"));
+            code = ExitCode::FAILURE;
+            continue;
+        }
+
+        // The count is incomplete when the search has been stopped early.
+        let plus = if solutions.error().is_some() { "+" } else { "" };
+        let noun = if count == 1 { "solution" } else { "solutions" };
+        if args.ndjson {
+            eprintln!("{count}{plus} {noun}{name}");
+        } else {
+            let _ = writeln!(stdout, "\n{count}{plus} {noun}");
+        }
+    }
+
+    code
+}
+
+/// Executes the `batch` subcommand.
+fn batch(
+    command: args::BatchCommand,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    match command {
+        args::BatchCommand::Solve {
+            file,
+            output,
+            alphabet,
+        } => batch_solve(&file, output, &alphabet, color_choice, accessible),
+        args::BatchCommand::Check {
+            file,
+            report,
+            strict,
+            alphabet,
+        } => batch_check(&file, report, strict, &alphabet, color_choice, accessible),
+    }
+}
+
+/// Reads the file of a `batch` subcommand, `-` being the standard input.
+///
+/// When the file can't be read, an error is printed and `None` is returned.
+fn read_batch_file(file: &std::path::Path, color_choice: termcolor::ColorChoice) -> Option<String> {
+    let input = if file.as_os_str() == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).map(|_| input)
+    } else {
+        std::fs::read_to_string(file)
+    };
+
+    match input {
+        Ok(ok) => Some(ok),
+        Err(err) => {
+            print_error(
+                color_choice,
+                format_args!("failed to read `{}`: {err}", file.display()),
+            );
+            None
+        }
+    }
+}
+
+/// Executes the `batch solve` subcommand.
+fn batch_solve(
+    file: &std::path::Path,
+    output: args::OutputFormat,
+    alphabet: &alphabet::Alphabet,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    if output.is_document() {
+        print_error(
+            color_choice,
+            format_args!("SVG and PDF documents can only hold a single board"),
+        );
+        return ExitCode::from(2);
+    }
+
+    let input = match read_batch_file(file, color_choice) {
+        Some(input) => input,
+        None => return ExitCode::FAILURE,
+    };
+
+    let stdout = termcolor::StandardStream::stdout(color_choice);
+    let mut stdout = stdout.lock();
+    let one_line = matches!(
+        output,
+        args::OutputFormat::Flat | args::OutputFormat::HeaderLine | args::OutputFormat::Puzzlink
+    );
+    let print = if accessible {
+        format::print_description
+    } else {
+        format::print_solution
+    };
+
+    let mut code = ExitCode::SUCCESS;
+    let mut printed = false;
+
+    'lines: for (line, text) in input.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let line = line + 1;
+        let solved = 'solve: {
+            let header = match text.parse::<Header>() {
+                Ok(header) if header.is_empty() => continue 'lines,
+                Ok(header) => header,
+                Err(err) => {
+                    print_error(color_choice, format_args!("line {line}: {err}"));
+                    break 'solve None;
+                }
+            };
+
+            if !check_alphabet(color_choice, alphabet, header.size()) {
+                break 'solve None;
+            }
+
+            match solve::solve(&header, Default::default()) {
+                Ok(solution) => Some((header, solution)),
+                Err(solve::SolutionError::NoSolution) => {
+                    print_error(color_choice, format_args!("line {line}: no solution found"));
+                    None
+                }
+                // The operation has been interrupted by a CTRL+C.
+                Err(_) => break 'lines,
+            }
+        };
+
+        if printed && !one_line {
+            let _ = stdout.write_all(b"\n");
+        }
+        printed = true;
+
+        let Some((header, solution)) = solved else {
+            // Headers without a solution still produce an entry, such that the entries of the
+            // output match the headers of the file.
+            let _ = stdout.write_all(b"-\n");
+            code = ExitCode::FAILURE;
+            continue;
+        };
+
+        if cfg!(debug_assertions) && !verify_solution(color_choice, &header, &solution) {
+            return ExitCode::from(EXIT_INTERNAL_ERROR);
+        }
+
+        let _ = print(&mut stdout, &solution, &header, &output, alphabet);
+    }
+
+    code
+}
+
+/// Executes the `batch check` subcommand.
+fn batch_check(
+    file: &std::path::Path,
+    report: Option<args::ReportFormat>,
+    strict: bool,
+    alphabet: &alphabet::Alphabet,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let mode = if strict {
+        check::Mode::Strict
+    } else {
+        check::Mode::Lenient
+    };
+
+    let input = match read_batch_file(file, color_choice) {
+        Some(input) => input,
+        None => return ExitCode::FAILURE,
+    };
+    // Boards are relative to the file, or to the current directory for the standard input.
+    let base = match file.as_os_str() == "-" {
+        true => std::path::Path::new(""),
+        false => file.parent().unwrap_or(std::path::Path::new("")),
+    };
+
+    let stdout = termcolor::StandardStream::stdout(color_choice);
+    let mut stdout = stdout.lock();
+    let stderr = termcolor::StandardStream::stderr(color_choice);
+    let mut stderr = stderr.lock();
+
+    let mut code = ExitCode::SUCCESS;
+    let mut entries = Vec::new();
+
+    for (line, text) in input.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let line = line + 1;
+        let (header, path) = match text.split_once(':') {
+            Some((header, path)) => (header, path.trim()),
+            None => {
+                print_error(
+                    color_choice,
+                    format_args!("line {line}: expected a header, `:` and the path of a board"),
+                );
+                code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        let header = match header.parse::<Header>() {
+            Ok(header) if !header.is_empty() => header,
+            Ok(_) => {
+                print_error(
+                    color_choice,
+                    format_args!("line {line}: the header is empty"),
+                );
+                code = ExitCode::FAILURE;
+                continue;
+            }
+            Err(err) => {
+                print_error(color_choice, format_args!("line {line}: {err}"));
+                code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        if !check_alphabet(color_choice, alphabet, header.size()) {
+            code = ExitCode::FAILURE;
+            continue;
+        }
+
+        let board = match std::fs::read(base.join(path)) {
+            Ok(ok) => ok,
+            Err(err) => {
+                code = ExitCode::FAILURE;
+                if report.is_none() {
+                    print_error(color_choice, format_args!("failed to read `{path}`: {err}"));
+                }
+                entries.push(report::Entry::unreadable(line, path.to_string(), err));
+                continue;
+            }
+        };
+
+        match check::check(&header, &board, mode, alphabet) {
+            Ok(()) => {
+                if report.is_none() {
+                    let _ = writeln!(stdout, "{path}: ok");
+                }
+                entries.push(report::Entry::passed(line, path.to_string()));
+            }
+            Err(err) => {
+                code = ExitCode::FAILURE;
+                if report.is_none() {
+                    let _ = writeln!(stderr, "in `{path}`:");
+                    let _ = print_board_error(&mut stderr, &board, &err, accessible);
+                }
+                entries.push(report::Entry::failed(line, path.to_string(), &board, &err));
+            }
+        }
+    }
+
+    let result = match report {
+        Some(args::ReportFormat::Csv) => report::write_csv(&mut stdout, &entries),
+        Some(args::ReportFormat::Json) => report::write_json(&mut stdout, &entries),
+        None => {
+            let passed = entries.iter().filter(|e| e.passed).count();
+            writeln!(stderr, "{passed}/{} boards passed", entries.len())
+        }
+    };
+    if let Err(err) = result {
+        print_error(
+            color_choice,
+            format_args!("failed to write the report: {err}"),
+        );
+        return ExitCode::FAILURE;
+    }
+
+    code
+}
+
+/// Checks the overlap requested for samurai puzzles of size `size`, and builds their layout.
+///
+/// When the overlap is invalid, an error is printed and `None` is returned.
+fn samurai_layout(
+    size: usize,
+    grids: usize,
+    overlap: Option<usize>,
+    color_choice: termcolor::ColorChoice,
+) -> Option<samurai::Layout> {
+    let overlap = overlap.unwrap_or((size / 3).max(1));
+
+    if overlap == 0 || overlap > size / 2 {
+        print_error(
+            color_choice,
+            format_args!(
+                "the overlap must be between 1 and half the size of the grids ({})",
+                size / 2,
+            ),
+        );
+        return None;
+    }
+
+    Some(samurai::Layout {
+        size,
+        grids,
+        overlap,
+    })
+}
+
+/// Executes the `samurai` subcommand.
+fn samurai(
+    command: args::SamuraiCommand,
+    color_choice: termcolor::ColorChoice,
+    accessible: bool,
+) -> ExitCode {
+    let (args::SamuraiCommand::Generate { output, .. }
+    | args::SamuraiCommand::Solve { output, .. }) = &command;
+    if output.is_document() {
+        print_error(
+            color_choice,
+            format_args!("samurai puzzles can't be written as SVG or PDF documents"),
+        );
+        return ExitCode::from(2);
+    }
+
+    let (layout, solutions, headers, output) = match command {
+        args::SamuraiCommand::Generate {
+            size,
+            grids,
+            overlap,
+            seed,
+            unique,
+            output,
+        } => {
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            let layout = match samurai_layout(size as usize, grids.max(1), overlap, color_choice) {
+                Some(layout) => layout,
+                None => return ExitCode::from(2),
+            };
+
+            let mut rng = match seed {
+                Some(seed) => Xoroshiro128StarStar::seed_from_u64(seed),
+                None => Xoroshiro128StarStar::from_entropy(),
+            };
+
+            let solutions = if unique {
+                samurai::generate_unique_solutions(&mut rng, layout)
+            } else {
+                samurai::generate_solutions(&mut rng, layout)
+            };
+            let solutions = match solutions {
+                Ok(solutions) => solutions,
+                // The operation has been interrupted by a CTRL+C.
+                Err(_) => return ExitCode::SUCCESS,
+            };
+
+            let headers = samurai::headers(&solutions);
+            (layout, solutions, headers, output)
+        }
+        args::SamuraiCommand::Solve {
+            headers,
+            overlap,
+            output,
+        } => {
+            let size = headers[0].size();
+            if size == 0 {
+                return ExitCode::from(3);
+            }
+
+            if headers.iter().any(|header| header.size() != size) {
+                print_error(
+                    color_choice,
+                    format_args!("every grid must have the same size"),
+                );
+                return ExitCode::from(2);
+            }
+
+            let layout = match samurai_layout(size, headers.len(), overlap, color_choice) {
+                Some(layout) => layout,
+                None => return ExitCode::from(2),
+            };
+
+            let mut found = None;
+            let res = samurai::for_each_solution(layout, &headers, &mut |solutions| {
+                found = Some(solutions.to_vec());
+                false
+            });
+
+            let solutions = match (res, found) {
+                (Ok(()), Some(solutions)) => solutions,
+                (Ok(()), None) => {
+                    print_error(color_choice, format_args!("no solution found"));
+                    return ExitCode::FAILURE;
+                }
+                (Err(_), _) => return ExitCode::SUCCESS,
+            };
+
+            (layout, solutions, headers, output)
+        }
+    };
+
+    let stdout = termcolor::StandardStream::stdout(color_choice);
+    let _ = samurai::print(
+        &mut stdout.lock(),
+        layout,
+        &solutions,
+        &headers,
+        &output,
+        accessible,
+    );
+
+    ExitCode::SUCCESS
+}
+
+/// Runs the solver on a single header, as requested by the arguments of the `solve` subcommand.
+///
+/// An error is returned if the trace or the GIF image could not be written.
+#[allow(clippy::too_many_arguments)]
+fn run_solver(
+    args: &args::SolveArgs,
+    header: &Header,
+    givens: &[u8],
+    limits: solve::Limits,
+    stats: &mut solve::Stats,
+    stdout: &mut dyn termcolor::WriteColor,
+    trace: Option<&mut trace::TraceWriter<output::FileWriter>>,
+    rng: Option<&mut Xoroshiro128StarStar>,
+) -> io::Result<Result<Board, solve::SolutionError>> {
+    if let Some(path) = &args.trace {
+        let format = match args.trace_format {
+            args::TraceFormat::Text => trace::DeductionFormat::Text,
+            args::TraceFormat::Json => trace::DeductionFormat::Json,
+        };
+        let file = output::FileWriter::create(path, None, None)?;
+        let mut log = trace::DeductionWriter::new(file, format, header);
+        log.start()?;
+        let res = solve::solve_traced(header, givens, limits, stats, &mut log);
+        log.end(&res)?;
+        log.into_inner().finish()?;
+
+        Ok(res)
+    } else if let Some(trace) = trace {
+        let mut trace_res = trace.start(header);
+        let res = solve::solve_observed(header, givens, limits, stats, &mut |frame| {
+            if trace_res.is_ok() {
+                trace_res = trace.frame(frame, header.size());
+            }
+        });
+        trace_res.and_then(|()| trace.end(&res))?;
+
+        Ok(res)
+    } else if let Some(path) = &args.gif {
+        let file = io::BufWriter::new(std::fs::File::create(path)?);
+        let mut gif = gif::Animation::new(file, header)?;
+        let mut gif_res = Ok(());
+        let res = solve::solve_observed(header, givens, limits, stats, &mut |frame| {
+            if gif_res.is_ok() {
+                gif_res = gif.frame(&frame.board());
+            }
+        });
+        // The last deductions of the search are not part of any frame.
+        if let Ok(solution) = &res {
+            gif_res = gif_res.and_then(|()| gif.frame(solution));
+        }
+        gif_res.and_then(|()| gif.finish()?.flush())?;
+
+        Ok(res)
+    } else if args.animate_to == Some(args::AnimationTarget::Stderr) {
+        let color_choice = if atty::is(atty::Stream::Stderr) {
+            termcolor::ColorChoice::Auto
+        } else {
+            termcolor::ColorChoice::Never
+        };
+        let stderr = termcolor::StandardStream::stderr(color_choice);
+        let mut stderr = stderr.lock();
+        Ok(solve::solve_animated(
+            header,
+            givens,
+            limits,
+            stats,
+            &mut stderr,
+            Duration::from_millis(20),
+        ))
+    } else if args.animate || args.animate_to.is_some() {
+        Ok(solve::solve_animated(
+            header,
+            givens,
+            limits,
+            stats,
+            stdout,
+            Duration::from_millis(20),
+        ))
+    } else if args.progress {
+        let mut progress = progress::Progress::new();
+        let res = solve::solve_with_observer(header, givens, limits, stats, &mut progress);
+        progress.finish();
+        Ok(res)
+    } else if let Some(rng) = rng {
+        Ok(solve::solve_shuffled(header, givens, limits, rng, stats))
+    } else {
+        Ok(match args.algorithm {
+            args::Algorithm::Backtracking => {
+                let threads = match args.threads {
+                    Some(threads) => threads.get(),
+                    None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+                };
+                solve::solve_parallel(header, givens, limits, threads, stats)
+            }
+            args::Algorithm::Ilp => ilp::solve(header),
+            #[cfg(feature = "sat")]
+            args::Algorithm::Sat => skyscrapper::sat::solve(header, limits),
+        })
+    }
+}
+
+/// Runs the solver on a single header, writing its state to the file given by `--checkpoint` (or
+/// `--resume`) as requested by the arguments of the `solve` subcommand.
+///
+/// The search goes on from `from`. Failing to write a checkpoint does not stop the search.
+fn run_checkpointed(
+    args: &args::SolveArgs,
+    header: &Header,
+    givens: &[u8],
+    limits: solve::Limits,
+    from: &solve::Checkpoint,
+    stats: &mut solve::Stats,
+    color_choice: termcolor::ColorChoice,
+) -> Result<Board, solve::SolutionError> {
+    let path = args
+        .checkpoint
+        .as_deref()
+        .or(args.resume.as_deref())
+        .expect("the search is checkpointed");
+    let givens_board = (!givens.is_empty()).then(|| Board::new(header.size(), givens.into()));
+
+    let res = solve::solve_checkpointed(
+        header,
+        givens,
+        limits,
+        from,
+        args.checkpoint_every,
+        stats,
+        &mut |checkpoint| {
+            let state = checkpoint::State {
+                version: checkpoint::VERSION,
+                header: header.clone(),
+                givens: givens_board.clone(),
+                checkpoint: checkpoint.clone(),
+            };
+            if let Err(err) = checkpoint::write(path, &state) {
+                print_error(
+                    color_choice,
+                    format_args!("failed to write `{}`: {err}", path.display()),
+                );
+            }
+        },
+    );
+
+    if res == Err(solve::SolutionError::Interrupted) {
+        eprintln!(
+            "the search has been interrupted, continue it with `--resume {}`",
+            path.display(),
+        );
+    }
+
+    res
+}
+
+/// Runs the `check` validator on a solution produced by the program.
+///
+/// If the solution turns out to be invalid, the error is printed and `false` is returned.
+fn verify_solution(
+    color_choice: termcolor::ColorChoice,
+    header: &Header,
+    solution: &Board,
+) -> bool {
+    match check::check_solution(header, solution) {
+        Ok(()) => true,
+        Err((board, err)) => {
+            print_error(
+                color_choice,
+                "internal error: the produced solution failed verification",
+            );
+
+            let stderr = termcolor::StandardStream::stderr(color_choice);
+            let mut stderr = stderr.lock();
+            let _ = print_board_error(&mut stderr, &board, &err, false);
+
+            false
+        }
+    }
+}
+
+/// Solves the header of a generated solution again, making sure the solver agrees with the
+/// generator.
+///
+/// When `unique` is set, the solver must find exactly the generated solution. Otherwise, any valid
+/// solution is accepted.
+///
+/// If the solver disagrees, the error is printed and `false` is returned.
+fn verify_round_trip(
+    color_choice: termcolor::ColorChoice,
+    header: &Header,
+    solution: &Board,
+    unique: bool,
+) -> bool {
+    let solved = match solve::solve(header, solve::Limits::default()) {
+        Ok(ok) => ok,
+        // The user asked for the program to stop, there is no inconsistency to report.
+        Err(solve::SolutionError::Interrupted) => return true,
+        Err(
+            solve::SolutionError::NoSolution
+            | solve::SolutionError::MemoryLimit
+            | solve::SolutionError::NodeLimit
+            | solve::SolutionError::Timeout,
+        ) => {
+            print_error(
+                color_choice,
+                "internal error: the generated header could not be solved",
+            );
+            return false;
+        }
+    };
+
+    if !verify_solution(color_choice, header, &solved) {
+        return false;
+    }
+
+    if unique && solved != *solution {
+        print_error(
+            color_choice,
+            "internal error: the generated header was solved differently",
+        );
+        return false;
+    }
+
+    true
+}
+
+/// Prints the provided [`check::BoardError`], highlighting its spans in `board`.
+///
+/// When `accessible` is set, the board is not printed. The spans are listed after the error
+/// instead, each one with its line and column.
+fn print_board_error(
+    stderr: &mut dyn termcolor::WriteColor,
+    board: &[u8],
+    err: &check::BoardError,
+    accessible: bool,
+) -> io::Result<()> {
+    use termcolor::{Color, ColorSpec};
+
+    if accessible {
+        print_board_error_kind(stderr, board, err)?;
+
+        // Those errors already say where they are.
+        if matches!(
+            err.kind,
+            check::BoardErrorKind::RowCount { .. } | check::BoardErrorKind::Mismatch(_)
+        ) {
+            return Ok(());
+        }
+
+        for &check::Span { start, end } in &err.spans {
+            let line_start = board[..start]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            let line = board[..start].iter().filter(|&&b| b == b'\n').count() + 1;
+            let column = String::from_utf8_lossy(&board[line_start..start])
+                .chars()
+                .count()
+                + 1;
+            write!(stderr, "  at line {line}, column {column}")?;
+            if start == end {
+                writeln!(stderr)?;
+            } else {
+                writeln!(
+                    stderr,
+                    ": `{}`",
+                    String::from_utf8_lossy(&board[start..end])
+                )?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mut last = 0;
+    for &check::Span { start, end } in &err.spans {
+        stderr.write_all(&board[last..start])?;
+        stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+        stderr.write_all(&board[start..end])?;
+        stderr.reset()?;
+        last = end;
+    }
+    stderr.write_all(&board[last..])?;
+    if !board.is_empty() && !board.ends_with(b"\n") {
+        stderr.write_all(b"\n")?;
+    }
+
+    print_board_error_kind(stderr, board, err)
+}
+
+/// Prints the message of the provided [`check::BoardError`].
+fn print_board_error_kind(
+    stderr: &mut dyn termcolor::WriteColor,
+    board: &[u8],
+    err: &check::BoardError,
+) -> io::Result<()> {
+    use termcolor::{Color, ColorSpec};
+
+    stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+    write!(stderr, "error")?;
+    stderr.reset()?;
+
+    match err.kind {
+        check::BoardErrorKind::InvalidNumber => {
+            write!(stderr, ": `")?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            let &check::Span { start, end } = err.spans.first().unwrap();
+            stderr.write_all(&board[start..end])?;
+            stderr.reset()?;
+            writeln!(stderr, "` is not a valid number")?;
+        }
+        check::BoardErrorKind::ColumnCount { expected, given } => {
+            write!(stderr, ": expected {} columns, found ", expected)?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(stderr, "{given}")?;
+            stderr.reset()?;
+        }
+        check::BoardErrorKind::RowCount { expected, given } => {
+            write!(stderr, ": expected {} rows, found ", expected)?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(stderr, "{given}")?;
+            stderr.reset()?;
+        }
+        check::BoardErrorKind::UnexpectedCharacter(c) => {
+            write!(stderr, ": character `")?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            write!(stderr, "{}", c.escape_ascii())?;
+            stderr.reset()?;
+            writeln!(stderr, "` was not expected")?;
+        }
+        check::BoardErrorKind::TopToBottom {
+            expected, given, ..
+        } => {
+            write!(
+                stderr,
+                ": from top to bottom, expected view count of {expected}, got "
+            )?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(stderr, "{}", given)?;
+            stderr.reset()?;
+        }
+        check::BoardErrorKind::BottomToTop {
+            expected, given, ..
+        } => {
+            write!(
+                stderr,
+                ": from bottom to top, expected view count of {expected}, got "
+            )?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(stderr, "{}", given)?;
+            stderr.reset()?;
+        }
+        check::BoardErrorKind::LeftToRight {
+            expected, given, ..
+        } => {
+            write!(
+                stderr,
+                ": from left to right, expected view count of {expected}, got "
+            )?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(stderr, "{}", given)?;
+            stderr.reset()?;
+        }
+        check::BoardErrorKind::RightToLeft {
+            expected, given, ..
+        } => {
+            write!(
+                stderr,
+                ": from right to left, expected view count of {expected}, got "
+            )?;
+            stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(stderr, "{}", given)?;
+            stderr.reset()?;
+        }
+        check::BoardErrorKind::Doubles | check::BoardErrorKind::Strict(_) => {
+            writeln!(stderr, ": {}", err.kind)?;
+        }
+        check::BoardErrorKind::Mismatch(ref cells) => {
+            writeln!(
+                stderr,
+                ": {} cells differ from the reference solution",
+                cells.len()
+            )?;
+            for &(x, y, expected) in cells {
+                write!(stderr, "  row {}, column {}: expected ", y + 1, x + 1)?;
+                stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+                writeln!(stderr, "{expected}")?;
+                stderr.reset()?;
+            }
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,101 @@
+//! Writes manual pages, used by the `man` subcommand.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap_mangen::Man;
+
+/// The sections appended to the page of the program, which `clap` knows nothing about.
+const EXTRA_SECTIONS: &str = r#".SH "INPUT FORMATS"
+.TP
+\fBheader\fR
+The clues of a puzzle, as written by \fB\-o header\-line\fR: the clues above the grid from left to
+right, the clues below it, the clues on its left from top to bottom and the clues on its right,
+separated by spaces. A puzzle of size \fIN\fR has \fI4N\fR clues, each between 1 and \fIN\fR.
+.TP
+\fBboard\fR
+The cells of a grid, as written by \fB\-o solution\fR: one row per line, cells being separated by
+spaces. \fBcheck\fR also accepts commas and tabs, decorative borders and the single line written by
+\fB\-o flat\fR, unless \fB\-\-strict\fR is given. Partial boards write empty cells as \fB.\fR.
+.TP
+\fBbatch files\fR
+One entry per line. Empty lines and lines starting with \fB#\fR are ignored.
+.SH "EXIT STATUS"
+.TP
+\fB0\fR
+The command succeeded.
+.TP
+\fB1\fR
+The command failed: the board is invalid, the puzzle has no solution, a file can't be read...
+.TP
+\fB2\fR
+The arguments are invalid.
+.TP
+\fB3\fR
+The size of the puzzle is 0.
+.TP
+\fB4\fR
+The program detected an internal inconsistency, such as a solution that does not match its
+header.
+.TP
+\fB5\fR
+\fBcheck \-\-unique\fR found a valid board, but the puzzle has other solutions, or
+\fBsolve \-\-unique\fR found more than one solution.
+.TP
+\fB6\fR
+\fBsolve \-\-timeout\fR aborted the search because it did not finish in time.
+"#;
+
+/// Returns the subcommands documented by their own page, recursively.
+fn subcommands(command: &clap::Command) -> Vec<&clap::Command> {
+    let mut all = Vec::new();
+    for sub in command.get_subcommands().filter(|s| !s.is_hide_set()) {
+        all.push(sub);
+        all.extend(subcommands(sub));
+    }
+    all
+}
+
+/// Returns the name of the page of `command`, such as `skyscrapper-cli-solve`.
+fn page_name(command: &clap::Command) -> &str {
+    command
+        .get_display_name()
+        .unwrap_or_else(|| command.get_name())
+}
+
+/// Builds the command of the program, as documented by the pages.
+fn build(mut command: clap::Command) -> clap::Command {
+    command = command.disable_help_subcommand(true);
+    // Building the command gives subcommands the names of their pages.
+    command.build();
+    command
+}
+
+/// Writes the page of the program.
+///
+/// The page lists the subcommands, whose options are documented by the pages written by
+/// [`write_pages`].
+pub fn write_page(w: &mut dyn Write, command: clap::Command) -> io::Result<()> {
+    Man::new(build(command)).render(w)?;
+    w.write_all(EXTRA_SECTIONS.as_bytes())
+}
+
+/// Writes the page of the program and of every subcommand to `dir`.
+///
+/// The paths of the written pages are returned.
+pub fn write_pages(dir: &Path, command: clap::Command) -> io::Result<Vec<PathBuf>> {
+    let command = build(command);
+    let mut paths = Vec::new();
+
+    let path = dir.join(format!("{}.1", page_name(&command)));
+    let mut file = io::BufWriter::new(std::fs::File::create(&path)?);
+    write_page(&mut file, command.clone())?;
+    file.flush()?;
+    paths.push(path);
+
+    for sub in subcommands(&command) {
+        paths.push(Man::new(sub.clone()).generate_to(dir)?);
+    }
+
+    Ok(paths)
+}
@@ -0,0 +1,209 @@
+//! Destinations to which the program writes its results.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use termcolor::{BufferedStandardStream, ColorSpec, NoColor, StandardStream, WriteColor};
+
+use skyscrapper::args::Compression;
+
+/// A file opened for writing, optionally encrypted.
+pub enum Sink {
+    /// The content is written as-is.
+    Plain(BufWriter<File>),
+    /// The content is encrypted using a passphrase.
+    Encrypted(age::stream::StreamWriter<BufWriter<File>>),
+}
+
+impl Sink {
+    /// Writes the end of the encrypted stream, if any, and flushes the file.
+    fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            Self::Plain(file) => file,
+            Self::Encrypted(writer) => writer.finish()?,
+        };
+
+        file.flush()
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Encrypted(w) => w.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.write_all(buf),
+            Self::Encrypted(w) => w.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Encrypted(w) => w.flush(),
+        }
+    }
+}
+
+/// A file opened for writing, optionally compressed.
+pub enum FileWriter {
+    /// The content is written as-is.
+    Plain(Sink),
+    /// The content is compressed using gzip.
+    Gzip(flate2::write::GzEncoder<Sink>),
+    /// The content is compressed using zstd.
+    Zstd(zstd::Encoder<'static, Sink>),
+}
+
+impl FileWriter {
+    /// Creates the file at `path`, compressing its content with `compression`.
+    ///
+    /// When a passphrase is provided, the content is encrypted after having been compressed.
+    pub fn create(
+        path: &Path,
+        compression: Option<Compression>,
+        passphrase: Option<&str>,
+    ) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        let sink = match passphrase {
+            None => Sink::Plain(file),
+            Some(passphrase) => Sink::Encrypted(crate::encrypt::encrypt(file, passphrase)?),
+        };
+
+        Ok(match compression {
+            None => Self::Plain(sink),
+            Some(Compression::Gzip) => Self::Gzip(flate2::write::GzEncoder::new(
+                sink,
+                flate2::Compression::default(),
+            )),
+            Some(Compression::Zstd) => Self::Zstd(zstd::Encoder::new(sink, 0)?),
+        })
+    }
+
+    /// Writes the end of the compressed and encrypted streams, if any, and flushes the file.
+    pub fn finish(self) -> io::Result<()> {
+        let sink = match self {
+            Self::Plain(sink) => sink,
+            Self::Gzip(encoder) => encoder.finish()?,
+            Self::Zstd(encoder) => encoder.finish()?,
+        };
+
+        sink.finish()
+    }
+}
+
+impl Write for FileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Gzip(w) => w.write(buf),
+            Self::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.write_all(buf),
+            Self::Gzip(w) => w.write_all(buf),
+            Self::Zstd(w) => w.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gzip(w) => w.flush(),
+            Self::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// The destination of the results of a command.
+pub enum Output {
+    /// The standard output, flushed after every line.
+    Stdout(StandardStream),
+    /// The standard output, only flushed when explicitly requested.
+    BufferedStdout(BufferedStandardStream),
+    /// A file. Colors are never written to files.
+    File(NoColor<FileWriter>),
+}
+
+impl Output {
+    /// Opens the destination requested by the user.
+    ///
+    /// When `path` is `None`, the standard output is used. It is line-buffered unless `buffered`
+    /// is set.
+    pub fn open(
+        path: Option<&Path>,
+        compression: Option<Compression>,
+        passphrase: Option<&str>,
+        color_choice: termcolor::ColorChoice,
+        buffered: bool,
+    ) -> io::Result<Self> {
+        Ok(match path {
+            Some(path) => Self::File(NoColor::new(FileWriter::create(
+                path,
+                compression,
+                passphrase,
+            )?)),
+            None if buffered => Self::BufferedStdout(BufferedStandardStream::stdout(color_choice)),
+            None => Self::Stdout(StandardStream::stdout(color_choice)),
+        })
+    }
+
+    /// Flushes the output, finishing the compressed and encrypted streams when writing to a file.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Stdout(mut w) => w.flush(),
+            Self::BufferedStdout(mut w) => w.flush(),
+            Self::File(w) => w.into_inner().finish(),
+        }
+    }
+
+    /// Returns the inner [`WriteColor`] implementation.
+    fn inner(&mut self) -> &mut dyn WriteColor {
+        match self {
+            Self::Stdout(w) => w,
+            Self::BufferedStdout(w) => w,
+            Self::File(w) => w,
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner().flush()
+    }
+}
+
+impl WriteColor for Output {
+    fn supports_color(&self) -> bool {
+        match self {
+            Self::Stdout(w) => w.supports_color(),
+            Self::BufferedStdout(w) => w.supports_color(),
+            Self::File(w) => w.supports_color(),
+        }
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.inner().set_color(spec)
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.inner().reset()
+    }
+}
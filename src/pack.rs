@@ -0,0 +1,89 @@
+//! Writes curated sets of puzzles, used by the `pack` subcommand.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use skyscrapper::board::{Board, Header};
+use skyscrapper::{analyze, solve};
+
+/// A puzzle of a pack, along with the metadata an application or a book needs to present it.
+#[derive(Serialize)]
+pub struct Entry {
+    /// An identifier made of the size of the puzzle and its position among the puzzles of that
+    /// size, such as `5-007`.
+    pub id: String,
+    /// The size of the puzzle.
+    pub size: u8,
+    /// The seed that generates the puzzle.
+    pub seed: u64,
+    /// The difficulty of the puzzle, as rated by the `rate` subcommand.
+    pub difficulty: &'static str,
+    /// The most advanced technique needed to solve the puzzle.
+    pub technique: &'static str,
+    /// Whether the header has exactly one solution.
+    pub unique: bool,
+    /// The clues of the puzzle.
+    pub header: Header,
+    /// The solution of the puzzle.
+    pub solution: Board,
+}
+
+impl Entry {
+    /// Rates a generated puzzle and creates its entry.
+    ///
+    /// `index` is the position of the puzzle among the puzzles of the same size, starting at 0.
+    /// `None` is returned when the operation has been interrupted.
+    pub fn new(index: usize, seed: u64, header: Header, solution: Board) -> Option<Self> {
+        let size = header.size() as u8;
+        let rating = analyze::rate(&header).ok()?;
+        let solutions = solve::count_solutions(&header, 2, Default::default()).ok()?;
+
+        Some(Self {
+            id: format!("{size}-{:03}", index + 1),
+            size,
+            seed,
+            difficulty: rating.difficulty.name(),
+            technique: rating.technique.name(),
+            unique: solutions == 1,
+            header,
+            solution,
+        })
+    }
+}
+
+/// Writes a pack of puzzles as a JSON document, `{"puzzles":[...]}`, one puzzle at a time.
+///
+/// Every puzzle is written on its own line, which keeps large packs readable and diffable. The
+/// document is only complete once [`Writer::finish`] has been called.
+pub struct Writer<W: Write> {
+    /// The output of the pack.
+    w: W,
+    /// The number of puzzles written so far.
+    puzzles: usize,
+}
+
+impl<W: Write> Writer<W> {
+    /// Creates a new [`Writer`], writing the start of the document to `w`.
+    pub fn new(mut w: W) -> io::Result<Self> {
+        write!(w, "{{\"puzzles\":[")?;
+        Ok(Self { w, puzzles: 0 })
+    }
+
+    /// Writes a puzzle to the pack, and flushes it.
+    pub fn push(&mut self, entry: &Entry) -> io::Result<()> {
+        if self.puzzles != 0 {
+            self.w.write_all(b",")?;
+        }
+        self.w.write_all(b"\n  ")?;
+        serde_json::to_writer(&mut self.w, entry)?;
+        self.puzzles += 1;
+        self.w.flush()
+    }
+
+    /// Writes the end of the document, and flushes it.
+    pub fn finish(mut self) -> io::Result<()> {
+        writeln!(self.w, "\n]}}")?;
+        self.w.flush()
+    }
+}
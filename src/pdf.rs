@@ -0,0 +1,231 @@
+//! Draws puzzles as PDF documents.
+//!
+//! Only the small subset of the format needed to draw lines and numbers is written: every page
+//! uses the standard Helvetica font, which PDF readers provide themselves.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::board::{Board, Header};
+
+/// The width of an A4 page, in points.
+const PAGE_WIDTH: f32 = 595.0;
+/// The height of an A4 page, in points.
+const PAGE_HEIGHT: f32 = 842.0;
+/// The space left blank around the content of a page, in points.
+const MARGIN: f32 = 72.0;
+/// The largest side of a cell, in points.
+const MAX_CELL: f32 = 40.0;
+
+/// The width of a digit in Helvetica, relative to the size of the font.
+const DIGIT_WIDTH: f32 = 0.556;
+/// The height of a digit in Helvetica, relative to the size of the font.
+const DIGIT_HEIGHT: f32 = 0.716;
+
+/// A PDF document, made of pages of the A4 format.
+#[derive(Default)]
+struct Document {
+    /// The content stream of every page.
+    pages: Vec<String>,
+}
+
+impl Document {
+    /// Writes the document.
+    fn write(&self, w: &mut dyn Write) -> io::Result<()> {
+        // The catalog, the page tree and the font come first, followed by a page object and a
+        // content stream for every page.
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..self.pages.len())
+                    .map(|i| format!("{} 0 R", 4 + 2 * i))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                self.pages.len(),
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                5 + 2 * i,
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len(),
+            ));
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"%PDF-1.4\n");
+
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            write!(out, "{} 0 obj\n{object}\nendobj\n", i + 1)?;
+        }
+
+        let xref = out.len();
+        write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1)?;
+        for offset in offsets {
+            write!(out, "{offset:010} 00000 n \n")?;
+        }
+        write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1,
+        )?;
+
+        w.write_all(&out)
+    }
+}
+
+/// Draws a number centered on `(cx, cy)`, to the content stream of a page.
+///
+/// This must be called between `BT` and `ET`, with the font of the provided size selected.
+fn draw_number(content: &mut String, cx: f32, cy: f32, font: f32, number: u8) {
+    // Text is positioned from its baseline, which is centered manually.
+    let text = number.to_string();
+    let width = text.len() as f32 * DIGIT_WIDTH * font;
+    let _ = writeln!(
+        content,
+        "1 0 0 1 {} {} Tm ({text}) Tj",
+        cx - width / 2.0,
+        cy - DIGIT_HEIGHT * font / 2.0,
+    );
+}
+
+/// Draws the header around a grid, to the content stream of a page. The grid is filled with
+/// `solution` when provided.
+///
+/// `(left, top)` is the top-left corner of the clues surrounding the grid, and `cell` is the side
+/// of a cell. Clues of `0` are left out.
+fn draw_puzzle(
+    content: &mut String,
+    left: f32,
+    top: f32,
+    cell: f32,
+    header: &Header,
+    solution: Option<&Board>,
+) {
+    let size = header.size();
+    let grid = size as f32 * cell;
+    let x0 = left + cell;
+    let y0 = top - cell - grid;
+
+    // Writing to a `String` cannot fail.
+    let _ = writeln!(content, "0.5 w");
+    for i in 1..size {
+        let at = i as f32 * cell;
+        let _ = writeln!(content, "{} {y0} m {} {} l S", x0 + at, x0 + at, y0 + grid);
+        let _ = writeln!(content, "{x0} {} m {} {} l S", y0 + at, x0 + grid, y0 + at);
+    }
+    let _ = writeln!(content, "2 w {x0} {y0} {grid} {grid} re S");
+
+    let font = cell / 2.0;
+    let _ = writeln!(content, "BT /F1 {font} Tf");
+    for (index, &clue) in header.iter().enumerate() {
+        if clue == 0 {
+            continue;
+        }
+
+        let i = (index % size + 1) as f32;
+        let (x, y) = match index / size {
+            0 => (i, 0.0),
+            1 => (i, size as f32 + 1.0),
+            2 => (0.0, i),
+            _ => (size as f32 + 1.0, i),
+        };
+
+        let cx = left + x * cell + cell / 2.0;
+        let cy = top - y * cell - cell / 2.0;
+        draw_number(content, cx, cy, font, clue);
+    }
+    if let Some(solution) = solution {
+        let _ = writeln!(content, "0.4 g");
+        for (y, row) in solution.rows().enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                let cx = x0 + x as f32 * cell + cell / 2.0;
+                let cy = top - (y + 1) as f32 * cell - cell / 2.0;
+                draw_number(content, cx, cy, font, value);
+            }
+        }
+        let _ = writeln!(content, "0 g");
+    }
+    let _ = writeln!(content, "ET");
+}
+
+/// Writes a one-page PDF document with the header surrounding an empty grid.
+pub fn write_puzzle(w: &mut dyn Write, header: &Header) -> io::Result<()> {
+    let size = header.size();
+    let cell = MAX_CELL.min((PAGE_WIDTH - 2.0 * MARGIN) / (size + 2) as f32);
+    let left = (PAGE_WIDTH - (size + 2) as f32 * cell) / 2.0;
+
+    let mut content = String::new();
+    draw_puzzle(&mut content, left, PAGE_HEIGHT - MARGIN, cell, header, None);
+
+    Document {
+        pages: vec![content],
+    }
+    .write(w)
+}
+
+/// The number of puzzles on a row of a worksheet.
+const SHEET_COLUMNS: usize = 2;
+/// The number of rows of puzzles on a page of a worksheet.
+const SHEET_ROWS: usize = 3;
+/// The size of the font of the titles of a worksheet.
+const TITLE_FONT: f32 = 12.0;
+
+/// Draws puzzles on pages of a worksheet, each one below a title made of `title` and its number.
+fn draw_sheet_pages(
+    pages: &mut Vec<String>,
+    puzzles: &[(Header, Board)],
+    title: &str,
+    solved: bool,
+) {
+    let width = (PAGE_WIDTH - 2.0 * MARGIN) / SHEET_COLUMNS as f32;
+    let height = (PAGE_HEIGHT - 2.0 * MARGIN) / SHEET_ROWS as f32;
+
+    for (page, chunk) in puzzles.chunks(SHEET_COLUMNS * SHEET_ROWS).enumerate() {
+        let mut content = String::new();
+
+        for (i, (header, solution)) in chunk.iter().enumerate() {
+            let number = page * SHEET_COLUMNS * SHEET_ROWS + i + 1;
+            let left = MARGIN + (i % SHEET_COLUMNS) as f32 * width;
+            let top = PAGE_HEIGHT - MARGIN - (i / SHEET_COLUMNS) as f32 * height;
+
+            let _ = writeln!(
+                content,
+                "BT /F1 {TITLE_FONT} Tf 1 0 0 1 {left} {} Tm ({title} {number}) Tj ET",
+                top - TITLE_FONT,
+            );
+
+            // The space below the title is shared by the puzzle and a small gap.
+            let side = (width - TITLE_FONT).min(height - 3.0 * TITLE_FONT);
+            let cell = MAX_CELL.min(side / (header.size() + 2) as f32);
+            let solution = if solved { Some(solution) } else { None };
+            draw_puzzle(
+                &mut content,
+                left,
+                top - 2.0 * TITLE_FONT,
+                cell,
+                header,
+                solution,
+            );
+        }
+
+        pages.push(content);
+    }
+}
+
+/// Writes a worksheet: the headers of `puzzles` surrounding empty grids, several of them per
+/// page, followed by an answer key starting on a new page.
+pub fn write_sheet(w: &mut dyn Write, puzzles: &[(Header, Board)]) -> io::Result<()> {
+    let mut document = Document::default();
+    draw_sheet_pages(&mut document.pages, puzzles, "Puzzle", false);
+    draw_sheet_pages(&mut document.pages, puzzles, "Solution", true);
+    document.write(w)
+}
@@ -0,0 +1,324 @@
+//! An interactive game, played in the terminal.
+//!
+//! The board is drawn on the alternate screen of the terminal, and redrawn after every key press.
+//! Clues turn green once their line is complete and correct, and red as soon as they can't be
+//! satisfied anymore. Cells holding the same value as another cell of their row or column are
+//! drawn in red.
+
+use std::io::{self, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+
+use skyscrapper::board::{Board, Header};
+use skyscrapper::check::{ClueState, IncrementalChecker};
+
+/// The keys of the game, as listed below the board.
+const KEYS: &[&str] = &[
+    "arrows  move",
+    "1-N     fill the cell",
+    "p       switch between pen and pencil marks",
+    "0       clear the cell",
+    "u       undo",
+    "q       quit",
+];
+
+/// The state of a cell before an edit, recorded so that the edit can be undone.
+struct Edit {
+    /// The column of the cell.
+    x: usize,
+    /// The row of the cell.
+    y: usize,
+    /// The value of the cell, `0` when empty.
+    value: u8,
+    /// The pencil marks of the cell.
+    marks: u16,
+}
+
+/// A game in progress.
+struct Game<'a> {
+    /// The header of the puzzle.
+    header: &'a Header,
+    /// Validates the board as it is filled.
+    checker: IncrementalChecker,
+    /// The pencil marks of every cell. The bit `v` is set when `v` is marked.
+    marks: Vec<u16>,
+    /// The column of the selected cell.
+    x: usize,
+    /// The row of the selected cell.
+    y: usize,
+    /// Whether typing a value toggles a pencil mark instead of filling the cell.
+    pencil: bool,
+    /// The edits that can be undone, the last one at the end.
+    history: Vec<Edit>,
+}
+
+impl<'a> Game<'a> {
+    /// Creates a new [`Game`] with an empty board.
+    fn new(header: &'a Header) -> Self {
+        let size = header.size();
+
+        Self {
+            header,
+            checker: IncrementalChecker::new(header),
+            marks: vec![0; size * size],
+            x: 0,
+            y: 0,
+            pencil: false,
+            history: Vec::new(),
+        }
+    }
+
+    /// Returns the size of the board.
+    fn size(&self) -> usize {
+        self.header.size()
+    }
+
+    /// Sets the value and the pencil marks of the selected cell, recording the edit.
+    fn edit(&mut self, value: u8, marks: u16) {
+        let (x, y) = (self.x, self.y);
+        let index = x + y * self.size();
+        let previous = Edit {
+            x,
+            y,
+            value: self.checker.board().get(x, y),
+            marks: self.marks[index],
+        };
+
+        if previous.value == value && previous.marks == marks {
+            return;
+        }
+
+        self.history.push(previous);
+        self.checker.set(x, y, value);
+        self.marks[index] = marks;
+    }
+
+    /// Handles a value typed by the user.
+    fn type_value(&mut self, value: u8) {
+        let index = self.x + self.y * self.size();
+        if self.pencil {
+            self.edit(
+                self.checker.board().get(self.x, self.y),
+                self.marks[index] ^ (1 << value),
+            );
+        } else {
+            self.edit(value, self.marks[index]);
+        }
+    }
+
+    /// Clears the value of the selected cell, or its pencil marks when it is already empty.
+    fn clear(&mut self) {
+        let index = self.x + self.y * self.size();
+        if self.checker.board().get(self.x, self.y) != 0 {
+            self.edit(0, self.marks[index]);
+        } else {
+            self.edit(0, 0);
+        }
+    }
+
+    /// Reverts the last edit, and selects the cell it was made on.
+    fn undo(&mut self) {
+        if let Some(edit) = self.history.pop() {
+            let index = edit.x + edit.y * self.size();
+            self.checker.set(edit.x, edit.y, edit.value);
+            self.marks[index] = edit.marks;
+            self.x = edit.x;
+            self.y = edit.y;
+        }
+    }
+
+    /// Returns whether the cell at `(x, y)` holds the same value as another cell of its row or
+    /// column.
+    fn is_conflicting(&self, x: usize, y: usize) -> bool {
+        let board = self.checker.board();
+        let value = board.get(x, y);
+
+        value != 0
+            && (0..self.size()).any(|i| {
+                (i != x && board.get(i, y) == value) || (i != y && board.get(x, i) == value)
+            })
+    }
+
+    /// Draws the clue at `index` in the header, colored according to its state.
+    fn draw_clue<W: Write>(&self, w: &mut W, index: usize) -> io::Result<()> {
+        let clue = self.header[index];
+        if clue == 0 {
+            return queue!(w, Print("  "));
+        }
+
+        let color = match self.checker.clue(index) {
+            ClueState::Pending => Color::Yellow,
+            ClueState::Satisfied => Color::Green,
+            ClueState::Violated => Color::Red,
+        };
+        queue!(
+            w,
+            Print(" "),
+            SetForegroundColor(color),
+            Print(clue),
+            ResetColor
+        )
+    }
+
+    /// Draws the whole screen.
+    ///
+    /// When `solved` is set, the selected cell is not highlighted anymore, and a message
+    /// congratulates the user.
+    fn draw<W: Write>(&self, w: &mut W, solved: bool) -> io::Result<()> {
+        let size = self.size();
+        let board = self.checker.board();
+        let mut line = 0;
+        let mut next_line = |w: &mut W| -> io::Result<()> {
+            line += 1;
+            queue!(w, cursor::MoveTo(0, line))
+        };
+
+        queue!(
+            w,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0),
+            Print("  "),
+        )?;
+        for x in 0..size {
+            self.draw_clue(w, x)?;
+        }
+
+        for y in 0..size {
+            next_line(w)?;
+            self.draw_clue(w, 2 * size + y)?;
+            for x in 0..size {
+                let value = board.get(x, y);
+                let marked = self.marks[x + y * size] != 0;
+
+                queue!(w, Print(" "))?;
+                if !solved && (x, y) == (self.x, self.y) {
+                    queue!(w, SetAttribute(Attribute::Reverse))?;
+                }
+                if self.is_conflicting(x, y) {
+                    queue!(w, SetForegroundColor(Color::Red))?;
+                }
+                match value {
+                    0 if marked => queue!(w, Print("+"))?,
+                    0 => queue!(w, Print("."))?,
+                    _ => queue!(w, Print(value))?,
+                }
+                queue!(w, ResetColor, SetAttribute(Attribute::Reset))?;
+            }
+            self.draw_clue(w, 3 * size + y)?;
+        }
+
+        next_line(w)?;
+        queue!(w, Print("  "))?;
+        for x in 0..size {
+            self.draw_clue(w, size + x)?;
+        }
+
+        next_line(w)?;
+        next_line(w)?;
+        if solved {
+            queue!(
+                w,
+                SetForegroundColor(Color::Green),
+                Print("Solved, well done! Press any key to exit."),
+                ResetColor
+            )?;
+        } else {
+            let marks = self.marks[self.x + self.y * size];
+            let marks: Vec<String> = (1..=size)
+                .filter(|&v| marks & (1 << v) != 0)
+                .map(|v| v.to_string())
+                .collect();
+
+            queue!(
+                w,
+                Print(format_args!(
+                    "r{}c{}  {}  marks: {}",
+                    self.y + 1,
+                    self.x + 1,
+                    if self.pencil { "pencil" } else { "pen" },
+                    if marks.is_empty() {
+                        "none".into()
+                    } else {
+                        marks.join(" ")
+                    },
+                )),
+            )?;
+
+            next_line(w)?;
+            for keys in KEYS {
+                next_line(w)?;
+                queue!(w, Print(keys))?;
+            }
+        }
+
+        w.flush()
+    }
+}
+
+/// Switches the terminal to the alternate screen and to raw mode, until it is dropped.
+struct Screen;
+
+impl Screen {
+    /// Prepares the terminal.
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = Self;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Lets the user solve the puzzle surrounded by `header`, until they succeed or quit.
+///
+/// The solved board is returned, or `None` if the user quit before solving it.
+pub fn run(header: &Header) -> io::Result<Option<Board>> {
+    let size = header.size();
+    let mut game = Game::new(header);
+    let _screen = Screen::enter()?;
+    let mut stdout = io::stdout().lock();
+
+    loop {
+        game.draw(&mut stdout, false)?;
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Char('z') if ctrl => game.undo(),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            KeyCode::Up | KeyCode::Char('k') => game.y = game.y.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => game.y = (game.y + 1).min(size - 1),
+            KeyCode::Left | KeyCode::Char('h') => game.x = game.x.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => game.x = (game.x + 1).min(size - 1),
+            KeyCode::Char('p') => game.pencil = !game.pencil,
+            KeyCode::Char('u') => game.undo(),
+            KeyCode::Char('0' | '.') | KeyCode::Backspace | KeyCode::Delete => game.clear(),
+            KeyCode::Char(c @ '1'..='9') => {
+                let value = c as u8 - b'0';
+                if value as usize <= size {
+                    game.type_value(value);
+                }
+            }
+            _ => (),
+        }
+
+        if game.checker.is_solved() {
+            game.draw(&mut stdout, true)?;
+            while !matches!(event::read()?, Event::Key(key) if key.kind == KeyEventKind::Press) {}
+            return Ok(Some(game.checker.board().clone()));
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Draws puzzles as PNG images.
+//!
+//! Images are grayscale, and numbers are drawn using a small built-in bitmap font, such that no
+//! font has to be available on the machine.
+
+use std::io::{self, Write};
+
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+
+use skyscrapper::board::{Board, Header};
+
+/// The side of a cell, in pixels.
+const CELL: usize = 40;
+
+/// The glyphs of the digits, 5 pixels wide and 7 pixels high. Each row is a bit mask, the most
+/// significant of the 5 bits being the leftmost pixel.
+const DIGITS: [[u8; 7]; 10] = [
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+];
+
+/// A grayscale image.
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a white image.
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0xFF; width * height],
+        }
+    }
+
+    /// Fills a rectangle, clipped to the image.
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, value: u8) {
+        for row in y..(y + height).min(self.height) {
+            let start = row * self.width;
+            let line = &mut self.pixels[start..start + self.width];
+            for pixel in line.iter_mut().take(x + width).skip(x) {
+                *pixel = value;
+            }
+        }
+    }
+
+    /// Draws a number centered on `(cx, cy)`, as large as possible within a cell.
+    fn number(&mut self, cx: usize, cy: usize, number: u8, value: u8) {
+        let text = number.to_string();
+        // Every glyph is followed by one column of spacing, except the last one.
+        let columns = text.len() * 6 - 1;
+        let scale = (1..=3).rev().find(|s| columns * s <= CELL - 8).unwrap_or(1);
+
+        let mut x = cx.saturating_sub(columns * scale / 2);
+        let y = cy.saturating_sub(7 * scale / 2);
+        for digit in text.bytes() {
+            let glyph = &DIGITS[(digit - b'0') as usize];
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0x10 >> column) != 0 {
+                        self.fill(x + column * scale, y + row * scale, scale, scale, value);
+                    }
+                }
+            }
+            x += 6 * scale;
+        }
+    }
+}
+
+/// Writes a chunk of a PNG file.
+fn write_chunk(w: &mut dyn Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let mut crc = Crc::new();
+    crc.update(kind);
+    crc.update(data);
+
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+    w.write_all(&crc.sum().to_be_bytes())
+}
+
+/// Draws the header surrounding a grid, filled with `solution` when provided.
+///
+/// Clues of `0` are left out, and so are the cells of `solution` whose value is `0`.
+pub fn draw_board(header: &Header, solution: Option<&Board>) -> Image {
+    let size = header.size();
+    // One cell of margin is left around the grid for the clues.
+    let side = (size + 2) * CELL;
+    let grid = size * CELL;
+
+    let mut image = Image::new(side, side);
+    for i in 1..size {
+        let at = (i + 1) * CELL;
+        image.fill(at, CELL, 1, grid, 0x00);
+        image.fill(CELL, at, grid, 1, 0x00);
+    }
+    image.fill(CELL - 1, CELL - 1, grid + 3, 3, 0x00);
+    image.fill(CELL - 1, CELL + grid - 1, grid + 3, 3, 0x00);
+    image.fill(CELL - 1, CELL - 1, 3, grid + 3, 0x00);
+    image.fill(CELL + grid - 1, CELL - 1, 3, grid + 3, 0x00);
+
+    for (index, &clue) in header.iter().enumerate() {
+        if clue == 0 {
+            continue;
+        }
+
+        let i = index % size + 1;
+        let (x, y) = match index / size {
+            0 => (i, 0),
+            1 => (i, size + 1),
+            2 => (0, i),
+            _ => (size + 1, i),
+        };
+        image.number(x * CELL + CELL / 2, y * CELL + CELL / 2, clue, 0x00);
+    }
+
+    if let Some(solution) = solution {
+        for (y, row) in solution.rows().enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                if value == 0 {
+                    continue;
+                }
+                image.number(
+                    (x + 1) * CELL + CELL / 2,
+                    (y + 1) * CELL + CELL / 2,
+                    value,
+                    0x60,
+                );
+            }
+        }
+    }
+
+    image
+}
+
+/// Writes a PNG image of the header surrounding a grid, filled with `solution` when provided.
+///
+/// Clues of `0` are left out.
+pub fn write_board(w: &mut dyn Write, header: &Header, solution: Option<&Board>) -> io::Result<()> {
+    let image = draw_board(header, solution);
+    let side = image.width;
+
+    // Every row starts with its filter type, which is always `None`.
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    for row in image.pixels.chunks_exact(side) {
+        encoder.write_all(&[0])?;
+        encoder.write_all(row)?;
+    }
+    let data = encoder.finish()?;
+
+    // Width, height, 8 bits per sample, grayscale, deflate, no filter, no interlacing.
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(side as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(side as u32).to_be_bytes());
+    ihdr.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    w.write_all(b"\x89PNG\r\n\x1a\n")?;
+    write_chunk(w, b"IHDR", &ihdr)?;
+    write_chunk(w, b"IDAT", &data)?;
+    write_chunk(w, b"IEND", &[])
+}
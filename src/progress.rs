@@ -0,0 +1,91 @@
+//! Reports the progress of long searches, used by `solve --progress`.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use skyscrapper::solve::{Frame, SolveObserver};
+use skyscrapper::terminal;
+
+/// The time between two reports.
+const INTERVAL: Duration = Duration::from_millis(500);
+
+/// The number of steps between two reads of the clock.
+const CLOCK_STEPS: usize = 1024;
+
+/// Writes the state of a search to the standard error at regular intervals.
+///
+/// On a terminal, the report is a single line that is redrawn in place and cleared once the
+/// search ends. Otherwise, every report is written on its own line.
+pub struct Progress {
+    start: Instant,
+    next: Instant,
+    in_place: bool,
+    printed: bool,
+    steps: usize,
+    backtracks: usize,
+    depth: usize,
+}
+
+impl Progress {
+    /// Creates a new [`Progress`], starting its clock.
+    pub fn new() -> Self {
+        let start = Instant::now();
+        Self {
+            start,
+            next: start + INTERVAL,
+            in_place: atty::is(atty::Stream::Stderr),
+            printed: false,
+            steps: 0,
+            backtracks: 0,
+            depth: 0,
+        }
+    }
+
+    /// Writes a report.
+    fn report(&mut self) -> io::Result<()> {
+        let mut stderr = io::stderr().lock();
+        if self.in_place {
+            terminal::clear_line(&mut stderr)?;
+        }
+        write!(
+            stderr,
+            "{:.1}s: explored {} nodes, backtracked {} times, at a depth of {}",
+            self.start.elapsed().as_secs_f64(),
+            self.steps,
+            self.backtracks,
+            self.depth,
+        )?;
+        if !self.in_place {
+            stderr.write_all(b"\n")?;
+        }
+        stderr.flush()
+    }
+
+    /// Clears the line written on the terminal, such that the result of the search is printed
+    /// where it was.
+    pub fn finish(self) {
+        if self.in_place && self.printed {
+            let mut stderr = io::stderr();
+            let _ = terminal::clear_line(&mut stderr);
+            let _ = stderr.flush();
+        }
+    }
+}
+
+impl SolveObserver for Progress {
+    fn on_frame(&mut self, frame: &Frame) {
+        self.steps = frame.step;
+        self.depth = frame.depth;
+
+        if frame.step.is_multiple_of(CLOCK_STEPS) && Instant::now() >= self.next {
+            self.next = Instant::now() + INTERVAL;
+            // The search is not worth aborting because the standard error is closed.
+            let _ = self.report();
+            self.printed = true;
+        }
+    }
+
+    fn on_backtrack(&mut self, _depth: usize) {
+        self.backtracks += 1;
+    }
+}
@@ -0,0 +1,175 @@
+//! Reads and writes puzzles as URLs of puzz.link and pzv.jp.
+//!
+//! Those websites encode a skyscrapers puzzle as `p?skyscrapers/COLS/ROWS/BODY`. The body first
+//! lists the clues around the grid (top, bottom, left then right, like a [`Header`]), followed by
+//! the numbers given in the grid, row by row. Each number is written as:
+//!
+//! * a single hexadecimal digit, for numbers up to 15;
+//! * `-` followed by two hexadecimal digits, for numbers up to 255;
+//! * `.`, for an unknown number, which is treated as a missing one;
+//! * a letter from `g` to `z`, which skips 1 to 20 positions.
+
+use std::fmt;
+use std::fmt::Write as _;
+
+use crate::board::{Board, Header, MAX_SIZE};
+
+/// An error which might occur whilst reading a puzz.link URL.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The URL does not describe a skyscrapers puzzle.
+    NotSkyscrapers,
+    /// The URL is missing the size of the grid, or its body.
+    Truncated,
+    /// The grid is not square.
+    NotSquare(usize, usize),
+    /// The size of the grid is not supported.
+    InvalidSize,
+    /// The body contains an invalid character.
+    InvalidCharacter(char),
+    /// A number is larger than the size of the grid.
+    OutOfRange(usize),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotSkyscrapers => f.write_str("the URL does not describe a skyscrapers puzzle"),
+            Self::Truncated => f.write_str("the URL is truncated"),
+            Self::NotSquare(cols, rows) => {
+                write!(f, "the grid must be square, but it is {cols}x{rows}")
+            }
+            Self::InvalidSize => write!(f, "the size of the grid must be between 1 and {MAX_SIZE}"),
+            Self::InvalidCharacter(c) => write!(f, "unexpected character `{c}` in the URL"),
+            Self::OutOfRange(n) => write!(f, "the number {n} is larger than the size of the grid"),
+        }
+    }
+}
+
+/// Decodes numbers from `body` into `out`, returning the part of `body` that follows them.
+///
+/// Numbers that are not written are left to `0`.
+fn decode_numbers<'a>(mut body: &'a str, out: &mut [u8]) -> Result<&'a str, ImportError> {
+    let mut i = 0;
+
+    while i < out.len() {
+        let c = match body.chars().next() {
+            Some(c) => c,
+            None => break,
+        };
+        body = &body[c.len_utf8()..];
+
+        let number = match c {
+            '0'..='9' | 'a'..='f' => c.to_digit(16).unwrap() as usize,
+            '-' | '+' => {
+                let len = if c == '-' { 2 } else { 3 };
+                let digits = body.get(..len).ok_or(ImportError::Truncated)?;
+                body = &body[len..];
+                usize::from_str_radix(digits, 16).map_err(|_| ImportError::Truncated)?
+            }
+            '.' => 0,
+            'g'..='z' => {
+                i += c.to_digit(36).unwrap() as usize - 15;
+                continue;
+            }
+            _ => return Err(ImportError::InvalidCharacter(c)),
+        };
+
+        out[i] = u8::try_from(number).map_err(|_| ImportError::OutOfRange(number))?;
+        i += 1;
+    }
+
+    Ok(body)
+}
+
+/// Reads a puzzle from a puzz.link or pzv.jp URL.
+///
+/// The returned board holds the numbers given in the grid, `0` being an empty cell. It is `None`
+/// when the grid is empty.
+pub fn decode(url: &str) -> Result<(Header, Option<Board>), ImportError> {
+    let query = match url.trim().split_once('?') {
+        Some((_, query)) => query,
+        None => url.trim(),
+    };
+
+    let mut parts = query.splitn(4, '/');
+    if parts.next() != Some("skyscrapers") {
+        return Err(ImportError::NotSkyscrapers);
+    }
+    let mut next = || parts.next().ok_or(ImportError::Truncated);
+    let cols: usize = next()?.parse().map_err(|_| ImportError::InvalidSize)?;
+    let rows: usize = next()?.parse().map_err(|_| ImportError::InvalidSize)?;
+    let body = next().unwrap_or("");
+
+    if cols != rows {
+        return Err(ImportError::NotSquare(cols, rows));
+    }
+    if cols == 0 || cols > MAX_SIZE {
+        return Err(ImportError::InvalidSize);
+    }
+    let size = cols;
+
+    let mut clues = vec![0; 4 * size];
+    let body = decode_numbers(body, &mut clues)?;
+    let mut cells = vec![0; size * size];
+    decode_numbers(body, &mut cells)?;
+
+    if let Some(&n) = clues.iter().chain(&cells).find(|&&n| n as usize > size) {
+        return Err(ImportError::OutOfRange(n as usize));
+    }
+
+    let board = match cells.iter().any(|&c| c != 0) {
+        true => Some(Board::new(size, cells.into_boxed_slice())),
+        false => None,
+    };
+
+    Ok((Header::new(clues.into_boxed_slice()), board))
+}
+
+/// Encodes `numbers` into `out`, in the format read by [`decode_numbers`].
+fn encode_numbers(out: &mut String, numbers: &[u8]) {
+    // The number of consecutive missing numbers that have not been written yet.
+    let mut skipped = 0;
+
+    for &n in numbers {
+        if n == 0 {
+            skipped += 1;
+            if skipped == 20 {
+                out.push('z');
+                skipped = 0;
+            }
+            continue;
+        }
+
+        if skipped != 0 {
+            out.push(char::from_digit(skipped + 15, 36).unwrap());
+            skipped = 0;
+        }
+
+        // Writing to a `String` cannot fail.
+        let _ = match n {
+            0..=15 => write!(out, "{n:x}"),
+            _ => write!(out, "-{n:02x}"),
+        };
+    }
+
+    if skipped != 0 {
+        out.push(char::from_digit(skipped + 15, 36).unwrap());
+    }
+}
+
+/// Writes the URL of the puzzle on puzz.link, with the provided numbers given in the grid.
+///
+/// Clues of `0` are left out, and so are the cells of `givens` that are `0`.
+pub fn encode(header: &Header, givens: Option<&Board>) -> String {
+    let size = header.size();
+    let mut url = format!("https://puzz.link/p?skyscrapers/{size}/{size}/");
+
+    encode_numbers(&mut url, header);
+    match givens {
+        Some(givens) => encode_numbers(&mut url, givens),
+        None => encode_numbers(&mut url, &vec![0; size * size]),
+    }
+
+    url
+}
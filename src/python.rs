@@ -0,0 +1,87 @@
+//! Python bindings to the engine, enabled by the `python` feature.
+//!
+//! The bindings are built as an extension module named `skyscrapper`. Like in the `ffi` module,
+//! boards and headers are passed as flat lists of integers: a board of size `size` has
+//! `size * size` cells in row-major order, and its header has `size * 4` clues.
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rand::SeedableRng;
+use rand_xoshiro::Xoroshiro128StarStar;
+
+use crate::board::{Board, Header};
+use crate::solve::SolutionError;
+
+/// Creates a [`Header`] from the clues provided by Python.
+fn header(clues: Vec<u8>) -> PyResult<Header> {
+    match Header::try_from(clues) {
+        Ok(header) if header.size() != 0 => Ok(header),
+        Ok(_) => Err(PyValueError::new_err("the header can't be empty")),
+        Err(err) => Err(PyValueError::new_err(err.to_string())),
+    }
+}
+
+/// Generates a random solved board of size `size`.
+///
+/// Returns the cells of the board and its clues. The same `seed` always produces the same board,
+/// the one `skyscrapper-cli generate --seed` produces.
+#[pyfunction]
+fn generate(py: Python, size: u8, seed: u64) -> PyResult<(Bound<PyList>, Bound<PyList>)> {
+    if size == 0 {
+        return Err(PyValueError::new_err("the size can't be 0"));
+    }
+
+    let solution = py
+        .allow_threads(|| {
+            let mut rng = Xoroshiro128StarStar::seed_from_u64(seed);
+            crate::generate::generate_solution(&mut rng, size)
+        })
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+    let header = crate::generate::solution_to_header(&solution);
+
+    Ok((
+        PyList::new(py, solution.cells())?,
+        PyList::new(py, header.clues())?,
+    ))
+}
+
+/// Solves the header made of the clues `header`.
+///
+/// A clue of `0` is missing. Returns the cells of the solution, or `None` if there is none.
+#[pyfunction]
+fn solve(py: Python, header: Vec<u8>) -> PyResult<Option<Bound<PyList>>> {
+    let header = self::header(header)?;
+
+    match py.allow_threads(|| crate::solve::solve(&header, Default::default())) {
+        Ok(solution) => Ok(Some(PyList::new(py, solution.cells())?)),
+        Err(SolutionError::NoSolution) => Ok(None),
+        Err(err) => Err(PyRuntimeError::new_err(err.to_string())),
+    }
+}
+
+/// Returns whether the cells `board` solve the header made of the clues `header`.
+#[pyfunction]
+fn check(header: Vec<u8>, board: Vec<u8>) -> PyResult<bool> {
+    let header = self::header(header)?;
+    let size = header.size();
+
+    if board.len() != size * size {
+        return Err(PyValueError::new_err(format!(
+            "a board of size {size} must have {} cells",
+            size * size,
+        )));
+    }
+
+    let board = Board::new(size, board.into_boxed_slice());
+    Ok(crate::check::check_solution(&header, &board).is_ok())
+}
+
+/// Generates, solves and checks Skyscrapper puzzles.
+#[pymodule]
+fn skyscrapper(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(generate, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(check, m)?)?;
+    Ok(())
+}
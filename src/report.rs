@@ -0,0 +1,129 @@
+//! Writes grading reports, used by `batch check --report`.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use skyscrapper::check::BoardError;
+
+/// The result of checking a single board.
+#[derive(Serialize)]
+pub struct Entry {
+    /// The line of the pair in the input file.
+    pub line: usize,
+    /// The path of the board, as written in the input file.
+    pub board: String,
+    /// Whether the board is a valid solution of its header.
+    pub passed: bool,
+    /// The kind of error found in the board, such as `top-to-bottom`.
+    ///
+    /// Boards that can't be read are reported as `unreadable`.
+    pub error: Option<&'static str>,
+    /// A description of the error.
+    pub message: Option<String>,
+    /// The line of the board where the error has been found, starting at 1.
+    pub error_line: Option<usize>,
+    /// The column of the board where the error has been found, starting at 1.
+    pub error_column: Option<usize>,
+}
+
+impl Entry {
+    /// Creates the entry of a board that passed.
+    pub fn passed(line: usize, board: String) -> Self {
+        Self {
+            line,
+            board,
+            passed: true,
+            error: None,
+            message: None,
+            error_line: None,
+            error_column: None,
+        }
+    }
+
+    /// Creates the entry of a board that can't be read.
+    pub fn unreadable(line: usize, board: String, err: io::Error) -> Self {
+        Self {
+            error: Some("unreadable"),
+            message: Some(err.to_string()),
+            passed: false,
+            ..Self::passed(line, board)
+        }
+    }
+
+    /// Creates the entry of a board in which `err` has been found.
+    ///
+    /// `text` is the content of the board, used to locate the first span of the error.
+    pub fn failed(line: usize, board: String, text: &[u8], err: &BoardError) -> Self {
+        let position = err.spans.first().map(|span| {
+            let before = &text[..span.start];
+            let line_start = before
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+            let column = String::from_utf8_lossy(&before[line_start..])
+                .chars()
+                .count()
+                + 1;
+            (line, column)
+        });
+
+        Self {
+            error: Some(err.kind.name()),
+            message: Some(err.to_string()),
+            passed: false,
+            error_line: position.map(|(line, _)| line),
+            error_column: position.map(|(_, column)| column),
+            ..Self::passed(line, board)
+        }
+    }
+}
+
+/// Writes a field of a CSV file, quoting it when needed.
+fn write_field(w: &mut dyn Write, field: &str) -> io::Result<()> {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(w, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        w.write_all(field.as_bytes())
+    }
+}
+
+/// Writes a report as a CSV file, starting with a line naming the columns.
+///
+/// Missing values are left empty.
+pub fn write_csv(w: &mut dyn Write, entries: &[Entry]) -> io::Result<()> {
+    writeln!(w, "line,board,passed,error,message,error_line,error_column")?;
+    for entry in entries {
+        write!(w, "{},", entry.line)?;
+        write_field(w, &entry.board)?;
+        write!(w, ",{},{},", entry.passed, entry.error.unwrap_or(""))?;
+        write_field(w, entry.message.as_deref().unwrap_or(""))?;
+        w.write_all(b",")?;
+        if let Some(line) = entry.error_line {
+            write!(w, "{line}")?;
+        }
+        w.write_all(b",")?;
+        if let Some(column) = entry.error_column {
+            write!(w, "{column}")?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Writes a report as a JSON document, `{"results":[...]}`.
+///
+/// Every board is written on its own line, like the puzzles of a pack.
+pub fn write_json(w: &mut dyn Write, entries: &[Entry]) -> io::Result<()> {
+    writeln!(w, "{{\"results\":[")?;
+    for (i, entry) in entries.iter().enumerate() {
+        w.write_all(b"  ")?;
+        serde_json::to_writer(&mut *w, entry)?;
+        if i + 1 != entries.len() {
+            w.write_all(b",")?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w, "]}}")
+}
@@ -0,0 +1,363 @@
+//! Experimental support for samurai puzzles.
+//!
+//! A samurai puzzle is made of several grids of the same size, arranged diagonally so that the
+//! bottom-right corner of each grid is also the top-left corner of the next one. The shared cells
+//! must satisfy both grids. Clues that would sit inside a neighbouring grid, or in the same place
+//! as one of its clues, are removed.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+#[cfg(feature = "cli")]
+use std::io;
+
+use rand::RngCore;
+
+#[cfg(feature = "cli")]
+use crate::args;
+use crate::board::{Board, Header};
+use crate::error::Interrupted;
+use crate::{generate, solve};
+
+/// The arrangement of the grids of a samurai puzzle.
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    /// The size of every grid.
+    pub size: usize,
+    /// The number of grids.
+    pub grids: usize,
+    /// The size of the square shared by two consecutive grids.
+    ///
+    /// This must be at least `1`, and at most half of `size`.
+    pub overlap: usize,
+}
+
+impl Layout {
+    /// Returns the position of the top-left cell of grid `k`.
+    ///
+    /// Because the grids are arranged diagonally, this is both its column and its row.
+    pub fn offset(&self, k: usize) -> usize {
+        k * (self.size - self.overlap)
+    }
+
+    /// Returns the size of the square that contains every grid.
+    pub fn extent(&self) -> usize {
+        self.offset(self.grids - 1) + self.size
+    }
+
+    /// Returns whether the clue at `index` of grid `k` is removed because of a neighbouring grid.
+    pub fn is_covered(&self, k: usize, index: usize) -> bool {
+        let i = index % self.size;
+
+        match index / self.size {
+            // The top and left clues run into the previous grid.
+            0 | 2 => k > 0 && i <= self.overlap,
+            // The bottom and right clues run into the next grid.
+            _ => k + 1 < self.grids && i >= self.size - self.overlap,
+        }
+    }
+
+    /// Returns the header of grid `k`, with the clues removed by [`Layout::is_covered`] set to
+    /// `0`.
+    pub fn visible_header(&self, k: usize, header: &Header) -> Header {
+        Header::new(
+            header
+                .iter()
+                .enumerate()
+                .map(|(index, &clue)| if self.is_covered(k, index) { 0 } else { clue })
+                .collect(),
+        )
+    }
+
+    /// Returns the givens that the solution of a grid imposes on the next grid: its bottom-right
+    /// corner, moved to the top-left corner.
+    fn givens_for_next(&self, solution: &Board) -> Box<[u8]> {
+        let shift = self.size - self.overlap;
+        let mut givens: Box<[u8]> = core::iter::repeat_n(0, self.size * self.size).collect();
+
+        for y in 0..self.overlap {
+            for x in 0..self.overlap {
+                givens[x + y * self.size] = solution.get(x + shift, y + shift);
+            }
+        }
+
+        givens
+    }
+}
+
+/// Generates random solutions for every grid of `layout`.
+///
+/// [`Interrupted`] is returned when the operation has been interrupted.
+pub fn generate_solutions(
+    rng: &mut dyn RngCore,
+    layout: Layout,
+) -> Result<Vec<Board>, Interrupted> {
+    let mut solutions: Vec<Board> = Vec::with_capacity(layout.grids);
+
+    for _ in 0..layout.grids {
+        let givens = match solutions.last() {
+            Some(previous) => layout.givens_for_next(previous),
+            None => Box::default(),
+        };
+        solutions.push(generate::generate_solution_with(
+            rng,
+            layout.size as u8,
+            &givens,
+        )?);
+    }
+
+    Ok(solutions)
+}
+
+/// Generates random solutions for every grid of `layout`, such that the resulting puzzle has
+/// exactly one solution.
+///
+/// [`Interrupted`] is returned when the operation has been interrupted.
+pub fn generate_unique_solutions(
+    rng: &mut dyn RngCore,
+    layout: Layout,
+) -> Result<Vec<Board>, Interrupted> {
+    loop {
+        let solutions = generate_solutions(rng, layout)?;
+        let headers = headers(&solutions);
+
+        match count_solutions(layout, &headers, 2) {
+            Ok(1) => return Ok(solutions),
+            Ok(_) => (),
+            Err(_) => return Err(Interrupted),
+        }
+    }
+}
+
+/// Returns the headers of every grid of a solved puzzle.
+///
+/// Every clue is included, even the ones removed by [`Layout::is_covered`].
+pub fn headers(solutions: &[Board]) -> Vec<Header> {
+    solutions.iter().map(generate::solution_to_header).collect()
+}
+
+/// A callback receiving the solutions of every grid of a puzzle, and returning whether more
+/// solutions should be searched.
+pub type OnSolution<'a> = dyn FnMut(&[Board]) -> bool + 'a;
+
+/// Calls `on_solution` with the solutions of every grid, for every solution of the puzzle, until
+/// it returns `false`.
+///
+/// The clues removed by [`Layout::is_covered`] are ignored.
+pub fn for_each_solution(
+    layout: Layout,
+    headers: &[Header],
+    on_solution: &mut OnSolution,
+) -> Result<(), solve::SolutionError> {
+    let headers: Vec<Header> = headers
+        .iter()
+        .enumerate()
+        .map(|(k, header)| layout.visible_header(k, header))
+        .collect();
+
+    let mut current = Vec::with_capacity(layout.grids);
+    search(layout, &headers, &[], &mut current, on_solution).map(|_| ())
+}
+
+/// Finds the solutions of the grid following the ones in `current`, and recursively of the grids
+/// after it.
+///
+/// `Ok(false)` is returned when `on_solution` asked to stop.
+fn search(
+    layout: Layout,
+    headers: &[Header],
+    givens: &[u8],
+    current: &mut Vec<Board>,
+    on_solution: &mut OnSolution,
+) -> Result<bool, solve::SolutionError> {
+    let k = current.len();
+    let mut result = Ok(true);
+
+    solve::for_each_solution_with(&headers[k], givens, Default::default(), &mut |solution| {
+        current.push(solution.clone());
+        let next = if k + 1 == layout.grids {
+            Ok(on_solution(current))
+        } else {
+            let givens = layout.givens_for_next(solution);
+            search(layout, headers, &givens, current, on_solution)
+        };
+        current.pop();
+
+        match next {
+            Ok(true) => true,
+            other => {
+                result = other;
+                false
+            }
+        }
+    })?;
+
+    result
+}
+
+/// Counts the solutions of a puzzle, stopping as soon as `limit` solutions have been found.
+pub fn count_solutions(
+    layout: Layout,
+    headers: &[Header],
+    limit: usize,
+) -> Result<usize, solve::SolutionError> {
+    let mut count = 0;
+
+    if limit != 0 {
+        for_each_solution(layout, headers, &mut |_| {
+            count += 1;
+            count < limit
+        })?;
+    }
+
+    Ok(count)
+}
+
+/// Something drawn on the combined board.
+#[cfg(feature = "cli")]
+#[derive(Clone, Copy)]
+enum Mark {
+    /// Nothing is drawn there.
+    Empty,
+    /// A clue.
+    Clue(u8),
+    /// A cell of one of the grids.
+    Cell(u8),
+}
+
+/// Prints the puzzle according to the provided output format.
+///
+/// The `header` and `both` formats draw every grid on the same board, leaving out the clues removed
+/// by [`Layout::is_covered`]. The `header-line` and `flat` formats print one line per grid.
+///
+/// When `accessible` is set, every grid is described separately using
+/// [`crate::format::print_description`], and the removed clues are described as missing.
+#[cfg(feature = "cli")]
+pub fn print(
+    w: &mut dyn termcolor::WriteColor,
+    layout: Layout,
+    solutions: &[Board],
+    headers: &[Header],
+    output: &args::OutputFormat,
+    accessible: bool,
+) -> io::Result<()> {
+    let size = layout.size;
+
+    if accessible {
+        for (k, (solution, header)) in solutions.iter().zip(headers).enumerate() {
+            writeln!(w, "Grid {}:", k + 1)?;
+            crate::format::print_description(
+                w,
+                solution,
+                &layout.visible_header(k, header),
+                output,
+                &crate::alphabet::Alphabet::Digits,
+            )?;
+        }
+        return Ok(());
+    }
+
+    let show_solution = match output {
+        args::OutputFormat::HeaderLine
+        | args::OutputFormat::Flat
+        | args::OutputFormat::Puzzlink => {
+            for (solution, header) in solutions.iter().zip(headers) {
+                crate::format::print_solution(
+                    w,
+                    solution,
+                    header,
+                    output,
+                    &crate::alphabet::Alphabet::Digits,
+                )?;
+            }
+            return Ok(());
+        }
+        // The empty cells are always drawn. Documents are rejected before getting here.
+        args::OutputFormat::Header
+        | args::OutputFormat::Blank
+        | args::OutputFormat::BlankSvg
+        | args::OutputFormat::BlankPdf => false,
+        args::OutputFormat::Solution | args::OutputFormat::Both => true,
+    };
+    let show_clues = !matches!(output, args::OutputFormat::Solution);
+
+    // The combined board, with a margin of one mark around it for the clues.
+    let width = layout.extent() + 2;
+    let mut board = vec![Mark::Empty; width * width];
+
+    for (k, (solution, header)) in solutions.iter().zip(headers).enumerate() {
+        let o = layout.offset(k);
+
+        for y in 0..size {
+            for x in 0..size {
+                let value = if show_solution { solution.get(x, y) } else { 0 };
+                board[o + 1 + x + (o + 1 + y) * width] = Mark::Cell(value);
+            }
+        }
+
+        if !show_clues {
+            continue;
+        }
+
+        for (index, &clue) in header.iter().enumerate() {
+            if layout.is_covered(k, index) {
+                continue;
+            }
+
+            let i = o + 1 + index % size;
+            let (x, y) = match index / size {
+                0 => (i, o),
+                1 => (i, o + size + 1),
+                2 => (o, i),
+                _ => (o + size + 1, i),
+            };
+            board[x + y * width] = Mark::Clue(clue);
+        }
+    }
+
+    let len = crate::alphabet::log10(size as u8);
+    let rows = board.chunks_exact(width);
+    // Without clues, the margin is left out.
+    let rows: Box<dyn Iterator<Item = &[Mark]>> = if show_clues {
+        Box::new(rows)
+    } else {
+        Box::new(rows.skip(1).take(width - 2).map(|row| &row[1..width - 1]))
+    };
+
+    for row in rows {
+        let end = row
+            .iter()
+            .rposition(|mark| !matches!(mark, Mark::Empty))
+            .map_or(0, |i| i + 1);
+
+        for (i, mark) in row[..end].iter().enumerate() {
+            if i != 0 {
+                w.write_all(b" ")?;
+            }
+
+            match *mark {
+                Mark::Empty => write!(w, "{:len$}", "")?,
+                Mark::Clue(clue) => {
+                    w.set_color(
+                        termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)),
+                    )?;
+                    write!(w, "{clue:<len$}")?;
+                    w.reset()?;
+                }
+                Mark::Cell(0) => write!(w, "{:<len$}", ".")?,
+                Mark::Cell(value) => {
+                    w.set_color(
+                        termcolor::ColorSpec::new()
+                            .set_fg(Some(termcolor::Color::Blue))
+                            .set_intense(true),
+                    )?;
+                    write!(w, "{value:<len$}")?;
+                    w.reset()?;
+                }
+            }
+        }
+
+        w.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
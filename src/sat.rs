@@ -0,0 +1,271 @@
+//! Solves skyscrapper problems by encoding them as boolean formulas.
+//!
+//! Like [`crate::ilp`], this backend only describes what a solution looks like, and lets the
+//! `splr` SAT solver find it. Its clause learning copes with large boards whose search tree is too
+//! wide for the backtracking search of [`crate::solve`].
+//!
+//! # Model
+//!
+//! Every cell `(x, y)` and height `h` gets a variable that is set when the cell holds that height.
+//! Every cell holds exactly one height, and every height appears exactly once per row and per
+//! column.
+//!
+//! For every clue, the cells it looks at are numbered from the closest one, and three more
+//! families of variables are introduced:
+//!
+//! - `taller(i, h)` is set when one of the first `i + 1` cells holds a height of `h` or more.
+//! - `visible(i)` is set when cell `i` is visible from the clue, that is, when no previous cell is
+//!   taller than it.
+//! - `count(i, k)` is set when at least `k` of the first `i + 1` cells are visible.
+//!
+//! The clue `c` then requires `count(size - 1, c)` and forbids `count(size - 1, c + 1)`.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use splr::{Certificate, Config, SolveIF, Solver, SolverError};
+
+use crate::board::{Board, Header};
+use crate::solve::{Limits, SolutionError};
+
+/// The interval at which the interrupt signal and the deadline are checked while `splr` runs.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A formula in conjunctive normal form, as understood by `splr`.
+///
+/// Variables are numbered from `1`, and a negative literal is the negation of its variable.
+struct Cnf {
+    vars: i32,
+    clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    /// Adds a new variable to the formula.
+    fn var(&mut self) -> i32 {
+        self.vars += 1;
+        self.vars
+    }
+
+    /// Requires at least one of `lits` to be set.
+    fn clause(&mut self, lits: impl IntoIterator<Item = i32>) {
+        self.clauses.push(lits.into_iter().collect());
+    }
+
+    /// Requires at most one of `lits` to be set.
+    fn at_most_one(&mut self, lits: &[i32]) {
+        for (i, &a) in lits.iter().enumerate() {
+            for &b in &lits[i + 1..] {
+                self.clause([-a, -b]);
+            }
+        }
+    }
+}
+
+/// Runs `splr` on `clauses`, which gives up by itself after `timeout` seconds.
+fn run(clauses: Vec<Vec<i32>>, timeout: f64) -> Result<Certificate, SolverError> {
+    let config = Config {
+        c_timeout: timeout,
+        quiet_mode: true,
+        ..Default::default()
+    };
+
+    match Solver::try_from((config, clauses.as_slice())) {
+        Ok(mut solver) => solver.solve(),
+        Err(Err(SolverError::EmptyClause)) => Ok(Certificate::UNSAT),
+        Err(res) => res,
+    }
+}
+
+/// Runs `splr` on `clauses` in the background, returning early when the program is interrupted or
+/// when the deadline of `limits` passes.
+///
+/// `splr` can't be stopped from the outside. When it is abandoned, its own timeout (which only
+/// counts whole seconds) is set to stop it shortly after the deadline.
+fn run_limited(clauses: Vec<Vec<i32>>, limits: Limits) -> Result<Certificate, SolutionError> {
+    let timeout = match limits.deadline {
+        Some(deadline) => deadline.saturating_duration_since(Instant::now()).as_secs() as f64 + 1.0,
+        None => Config::default().c_timeout,
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        // The receiver is gone when the search has been abandoned.
+        let _ = sender.send(run(clauses, timeout));
+    });
+
+    let res = loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(res) => break res,
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => match thread.join() {
+                Err(payload) => std::panic::resume_unwind(payload),
+                Ok(()) => unreachable!("the solver exited without a result"),
+            },
+        }
+
+        if crate::sigint::occured() {
+            return Err(SolutionError::Interrupted);
+        }
+        if limits
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(SolutionError::Timeout);
+        }
+    };
+
+    match res {
+        Ok(certificate) => Ok(certificate),
+        Err(SolverError::OutOfMemory) => Err(SolutionError::MemoryLimit),
+        Err(SolverError::TimeOut) => Err(SolutionError::Timeout),
+        // The other errors are other ways for the solver to report a contradiction.
+        Err(_) => Err(SolutionError::NoSolution),
+    }
+}
+
+/// Solves the provided header using a SAT solver.
+///
+/// The search stops when the program is interrupted or when the deadline of `limits` passes. The
+/// other limits are ignored: the SAT solver does not explore a search tree whose nodes could be
+/// counted, and it manages its own memory.
+pub fn solve(header: &Header, limits: Limits) -> Result<Board, SolutionError> {
+    let size = header.size();
+    let mut cnf = Cnf {
+        vars: 0,
+        clauses: Vec::new(),
+    };
+
+    // A variable that is always set, standing for the constants of the model.
+    let truth = cnf.var();
+    cnf.clause([truth]);
+
+    // `cells[(x + y * size) * size + h]` is set when `(x, y)` holds the height `h + 1`.
+    let cells: Vec<i32> = (0..size * size * size).map(|_| cnf.var()).collect();
+    let cell = |x: usize, y: usize, h: usize| cells[(x + y * size) * size + h];
+
+    for a in 0..size {
+        for b in 0..size {
+            // Every cell holds exactly one height.
+            let lits: Vec<i32> = (0..size).map(|h| cell(a, b, h)).collect();
+            cnf.clause(lits.iter().copied());
+            cnf.at_most_one(&lits);
+            // Every height appears exactly once per row...
+            let lits: Vec<i32> = (0..size).map(|x| cell(x, a, b)).collect();
+            cnf.clause(lits.iter().copied());
+            cnf.at_most_one(&lits);
+            // ...and per column.
+            let lits: Vec<i32> = (0..size).map(|y| cell(a, y, b)).collect();
+            cnf.clause(lits.iter().copied());
+            cnf.at_most_one(&lits);
+        }
+    }
+
+    for (index, &clue) in header.iter().enumerate() {
+        // A clue of `0` is missing.
+        if clue == 0 {
+            continue;
+        }
+        let clue = clue as usize;
+
+        let at = |i: usize, h: usize| {
+            let (x, y) = crate::ilp::line_cell(index, i, size);
+            cell(x, y, h)
+        };
+
+        // `taller[i][h]`, with an extra height that no cell reaches.
+        let mut taller: Vec<Vec<i32>> = Vec::with_capacity(size);
+        // `count[i][k]`, for `k` up to `clue + 1`.
+        let mut count: Vec<Vec<i32>> = Vec::with_capacity(size);
+
+        for i in 0..size {
+            let row: Vec<i32> = (0..=size)
+                .map(|h| match h {
+                    0 => truth,
+                    _ if h == size => -truth,
+                    _ => cnf.var(),
+                })
+                .collect();
+            for h in 1..size {
+                // `taller(i, h)` is the disjunction of `taller(i - 1, h)` and of the heights of
+                // the cell that are `h` or more.
+                let reached = (h..size).map(|g| at(i, g));
+                for g in reached.clone() {
+                    cnf.clause([-g, row[h]]);
+                }
+                match i {
+                    0 => cnf.clause([-row[h]].into_iter().chain(reached)),
+                    _ => {
+                        let previous = taller[i - 1][h];
+                        cnf.clause([-previous, row[h]]);
+                        cnf.clause([-row[h], previous].into_iter().chain(reached));
+                    }
+                }
+            }
+
+            // `visible(i)` is set when the height `h` of the cell is not reached by the previous
+            // cells, that is, when `taller(i - 1, h + 1)` is not set.
+            let visible = match i {
+                0 => truth,
+                _ => {
+                    let var = cnf.var();
+                    for h in 0..size {
+                        let hidden = taller[i - 1][h + 1];
+                        cnf.clause([-at(i, h), hidden, var]);
+                        cnf.clause([-at(i, h), -hidden, -var]);
+                    }
+                    var
+                }
+            };
+
+            // `count(i, k)` is set when `count(i - 1, k)` is, or when `count(i - 1, k - 1)` is and
+            // the cell is visible.
+            let counts: Vec<i32> = (0..=clue + 1)
+                .map(|k| match k {
+                    0 => truth,
+                    _ => cnf.var(),
+                })
+                .collect();
+            for k in 1..=clue + 1 {
+                let (previous, below) = match i {
+                    0 => (-truth, if k == 1 { truth } else { -truth }),
+                    _ => (count[i - 1][k], count[i - 1][k - 1]),
+                };
+                cnf.clause([-previous, counts[k]]);
+                cnf.clause([-below, -visible, counts[k]]);
+                cnf.clause([-counts[k], previous, below]);
+                cnf.clause([-counts[k], previous, visible]);
+            }
+
+            taller.push(row);
+            count.push(counts);
+        }
+
+        cnf.clause([count[size - 1][clue]]);
+        cnf.clause([-count[size - 1][clue + 1]]);
+
+        // At least `clue - 1` buildings must be visible after the cell `i`, so it can't be taller
+        // than `size - clue + i + 1`. This follows from the model, but saves the solver some
+        // conflicts.
+        for i in 0..size {
+            for h in (size + i + 1).saturating_sub(clue)..size {
+                cnf.clause([-at(i, h)]);
+            }
+        }
+    }
+
+    let model = match run_limited(cnf.clauses, limits)? {
+        Certificate::SAT(model) => model,
+        Certificate::UNSAT => return Err(SolutionError::NoSolution),
+    };
+
+    // The model lists every variable in order, negated when it is not set.
+    let board = (0..size * size)
+        .map(|i| {
+            (0..size)
+                .find(|&h| model[cells[i * size + h] as usize - 1] > 0)
+                .map_or(0, |h| h as u8 + 1)
+        })
+        .collect();
+
+    Ok(Board::new(size, board))
+}
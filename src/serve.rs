@@ -0,0 +1,334 @@
+//! A small HTTP server exposing the engine, used by the `serve` subcommand.
+//!
+//! Requests are handled by a fixed number of worker threads, and every connection is closed once
+//! its response has been written. Connections that arrive while every worker is busy and the
+//! queue is full are answered with an error right away. Requests and responses use JSON, headers
+//! and boards being written as flat lists of numbers, like in the `convert` subcommand:
+//!
+//! * `GET /generate?size=6&seed=42&unique=true` generates a puzzle. The seed and the uniqueness
+//!   are optional.
+//! * `POST /solve`, with `{"header":[...]}`, solves a header.
+//! * `POST /check`, with `{"header":[...],"board":[...]}`, checks a solution.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::{RngCore, SeedableRng};
+use rand_xoshiro::Xoroshiro128StarStar;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use skyscrapper::board::{Board, Header};
+use skyscrapper::{check, generate, sigint, solve};
+
+/// The largest body accepted in a request, in bytes.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// The longest line accepted in the head of a request, in bytes.
+const MAX_LINE: usize = 8 * 1024;
+
+/// The largest number of header fields accepted in a request.
+const MAX_FIELDS: usize = 100;
+
+/// Bounds the work done for a single request.
+#[derive(Debug, Clone, Copy)]
+pub struct Bounds {
+    /// The largest size of the puzzles that may be generated or solved.
+    pub max_size: u8,
+    /// The maximum number of nodes the solver may explore.
+    pub max_nodes: usize,
+    /// The time after which the solver gives up.
+    pub timeout: Duration,
+}
+
+impl Bounds {
+    /// Returns the limits of the solver for a request starting now.
+    fn limits(&self) -> solve::Limits {
+        solve::Limits {
+            max_nodes: Some(self.max_nodes),
+            deadline: Some(Instant::now() + self.timeout),
+            ..Default::default()
+        }
+    }
+
+    /// Returns an error response if puzzles of size `size` can't be generated or solved.
+    fn check_size(&self, size: usize) -> Result<(), Response> {
+        match size > self.max_size as usize {
+            true => Err(error(
+                400,
+                format_args!("the size can't be larger than {}", self.max_size),
+            )),
+            false => Ok(()),
+        }
+    }
+}
+
+/// A response to a request: its status code and its JSON body.
+type Response = (u16, Value);
+
+/// Creates an error response.
+fn error(status: u16, message: impl ToString) -> Response {
+    (status, json!({ "error": message.to_string() }))
+}
+
+/// The body of a `/solve` request.
+#[derive(Deserialize)]
+struct SolveRequest {
+    header: Header,
+}
+
+/// The body of a `/check` request.
+#[derive(Deserialize)]
+struct CheckRequest {
+    header: Header,
+    board: Board,
+}
+
+/// Returns the response reporting that a search could not complete.
+fn search_error(err: solve::SolutionError) -> Response {
+    match err {
+        solve::SolutionError::Interrupted => error(503, err),
+        _ => error(422, err),
+    }
+}
+
+/// Handles `GET /generate`.
+fn generate(query: &str, bounds: Bounds) -> Response {
+    let mut size = None;
+    let mut seed = None;
+    let mut unique = false;
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let parsed = match key {
+            "size" => value.parse().map(|v| size = Some(v)).is_ok(),
+            "seed" => value.parse().map(|v| seed = Some(v)).is_ok(),
+            "unique" => value.parse().map(|v| unique = v).is_ok(),
+            _ => return error(400, format_args!("unknown parameter `{key}`")),
+        };
+        if !parsed {
+            return error(400, format_args!("invalid value `{value}` for `{key}`"));
+        }
+    }
+
+    let size: u8 = match size {
+        Some(size @ 1..) => size,
+        Some(_) => return error(400, "the size can't be 0"),
+        None => return error(400, "the `size` parameter is required"),
+    };
+    if let Err(response) = bounds.check_size(size as usize) {
+        return response;
+    }
+    let seed = seed.unwrap_or_else(|| Xoroshiro128StarStar::from_entropy().next_u64());
+
+    let mut rng = Xoroshiro128StarStar::seed_from_u64(seed);
+    let solution = if unique {
+        generate::generate_unique_solution_with(&mut rng, size, bounds.limits())
+    } else {
+        generate::generate_solution(&mut rng, size).map_err(solve::SolutionError::from)
+    };
+    let solution = match solution {
+        Ok(ok) => ok,
+        Err(err) => return search_error(err),
+    };
+    let header = generate::solution_to_header(&solution);
+
+    (
+        200,
+        json!({ "seed": seed, "header": header, "board": solution }),
+    )
+}
+
+/// Handles `POST /solve`.
+fn solve(body: &[u8], bounds: Bounds) -> Response {
+    let request: SolveRequest = match serde_json::from_slice(body) {
+        Ok(ok) => ok,
+        Err(err) => return error(400, err),
+    };
+    if request.header.is_empty() {
+        return error(400, "the header can't be empty");
+    }
+    if let Err(response) = bounds.check_size(request.header.size()) {
+        return response;
+    }
+
+    match solve::solve(&request.header, bounds.limits()) {
+        Ok(solution) => (200, json!({ "board": solution })),
+        Err(err) => search_error(err),
+    }
+}
+
+/// Handles `POST /check`.
+fn check(body: &[u8]) -> Response {
+    let request: CheckRequest = match serde_json::from_slice(body) {
+        Ok(ok) => ok,
+        Err(err) => return error(400, err),
+    };
+    if request.board.size() != request.header.size() {
+        return error(
+            400,
+            format_args!(
+                "a board of size {} can't have a header of size {}",
+                request.board.size(),
+                request.header.size(),
+            ),
+        );
+    }
+
+    match check::check_solution(&request.header, &request.board) {
+        Ok(()) => (200, json!({ "valid": true })),
+        Err((_, err)) => (200, json!({ "valid": false, "error": err.to_string() })),
+    }
+}
+
+/// Returns the reason phrase of a status code.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    }
+}
+
+/// Reads a line of the head of a request into `line`, returning `false` if it is longer than
+/// [`MAX_LINE`].
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<bool> {
+    line.clear();
+    let len = reader.take(MAX_LINE as u64).read_line(line)?;
+    Ok(len < MAX_LINE || line.ends_with('\n'))
+}
+
+/// Reads a request from `stream`, and computes its response.
+fn respond(stream: &TcpStream, bounds: Bounds) -> io::Result<Response> {
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    if !read_line(&mut reader, &mut line)? {
+        return Ok(error(414, "the request line is too long"));
+    }
+    let mut words = line.split_ascii_whitespace();
+    let (method, target) = match (words.next(), words.next()) {
+        (Some(method), Some(target)) => (method.to_string(), target.to_string()),
+        _ => return Ok(error(400, "malformed request line")),
+    };
+
+    let mut length = 0;
+    let mut fields = 0;
+    loop {
+        if !read_line(&mut reader, &mut line)? {
+            return Ok(error(431, "a header field is too long"));
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+        fields += 1;
+        if fields > MAX_FIELDS {
+            return Ok(error(431, "too many header fields"));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = match value.trim().parse() {
+                    Ok(ok) => ok,
+                    Err(_) => return Ok(error(400, "invalid `Content-Length` header")),
+                };
+            }
+        }
+    }
+
+    if length > MAX_BODY {
+        return Ok(error(413, "the body is too large"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    Ok(match (method.as_str(), path) {
+        ("GET", "/generate") => generate(query, bounds),
+        ("POST", "/solve") => solve(&body, bounds),
+        ("POST", "/check") => check(&body),
+        (_, "/generate" | "/solve" | "/check") => error(405, "method not allowed"),
+        _ => error(404, "not found"),
+    })
+}
+
+/// Writes `response` to `stream`.
+fn write_response(stream: &mut TcpStream, (status, body): Response) -> io::Result<()> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Connection: close\r\n\r\n{body}",
+        reason(status),
+        body.len(),
+    )?;
+    stream.flush()
+}
+
+/// Handles a single connection.
+fn handle(mut stream: TcpStream, bounds: Bounds) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+
+    let response = respond(&stream, bounds)?;
+    write_response(&mut stream, response)
+}
+
+/// Serves requests on `listener` until the program is interrupted.
+///
+/// Requests are handled by `workers` threads, and every request is bounded by `bounds`, such
+/// that a single request can't keep a worker busy forever.
+pub fn run(listener: TcpListener, bounds: Bounds, workers: NonZeroUsize) -> io::Result<()> {
+    // Connections wait in a queue as long as the number of workers, and are refused past it.
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(workers.get());
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..workers.get() {
+        let receiver = Arc::clone(&receiver);
+        std::thread::spawn(move || loop {
+            let stream = receiver.lock().unwrap().recv();
+            match stream {
+                Ok(stream) => {
+                    // The client is gone, there is no one to report the error to.
+                    let _ = handle(stream, bounds);
+                }
+                // The server is shutting down.
+                Err(_) => return,
+            }
+        });
+    }
+
+    // The listener is polled so that CTRL+C can stop the server.
+    listener.set_nonblocking(true)?;
+
+    while !sigint::occured() {
+        match listener.accept() {
+            Ok((stream, _)) => match sender.try_send(stream) {
+                Ok(()) => (),
+                Err(TrySendError::Full(mut stream))
+                | Err(TrySendError::Disconnected(mut stream)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                    let _ = write_response(&mut stream, error(503, "the server is busy"));
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
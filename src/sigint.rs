@@ -1,13 +1,16 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, Ordering};
 
 static OCCURED: AtomicBool = AtomicBool::new(false);
 
 /// Initializes the CTRL+C handler.
+#[cfg(feature = "cli")]
 pub fn initialize() {
     ctrlc::set_handler(|| OCCURED.store(true, Ordering::Relaxed)).unwrap();
 }
 
 /// Returns whether the interrupt signal has been recieved.
+///
+/// Without the `cli` feature, no handler is ever installed, and this always returns `false`.
 #[inline]
 pub fn occured() -> bool {
     OCCURED.load(Ordering::Relaxed)
@@ -0,0 +1,53 @@
+//! Signs puzzles and their solutions, allowing modified answer keys to be detected.
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// Creates the HMAC instance authenticating a puzzle and its solution.
+fn mac(key: &[u8], header: &[u8], solution: &[u8]) -> Hmac<Sha256> {
+    // HMAC accepts keys of any length.
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+
+    // The size is part of the message so that the boundary between the header and the solution
+    // is unambiguous.
+    mac.update(b"skyscrapper-v1");
+    mac.update(&[(header.len() / 4) as u8]);
+    mac.update(header);
+    mac.update(solution);
+    mac
+}
+
+/// Writes bytes as an hexadecimal string.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Computes the signature of a puzzle and its solution, as an hexadecimal string.
+pub fn sign(key: &[u8], header: &[u8], solution: &[u8]) -> String {
+    hex(&mac(key, header, solution).finalize().into_bytes())
+}
+
+/// Computes the SHA-256 hash of `data`, as an hexadecimal string.
+pub fn digest(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+/// Returns whether `signature`, an hexadecimal string, is the signature of the provided puzzle and
+/// solution.
+pub fn verify(key: &[u8], header: &[u8], solution: &[u8], signature: &str) -> bool {
+    let signature = signature.trim();
+    if !signature.len().is_multiple_of(2) || !signature.is_ascii() {
+        return false;
+    }
+
+    let bytes: Option<Vec<u8>> = (0..signature.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&signature[i..i + 2], 16).ok())
+        .collect();
+
+    match bytes {
+        // The comparison is performed in constant time.
+        Some(bytes) => mac(key, header, solution).verify_slice(&bytes).is_ok(),
+        None => false,
+    }
+}
@@ -1,82 +1,156 @@
 //! Provides ways to solve skyscrapper problems.
 
-use std::time::Duration;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::time::Duration;
 
+use rand::{Rng, RngCore};
+
+#[cfg(feature = "cli")]
 use termcolor::WriteColor;
 
+use crate::board::{Board, Header, MAX_SIZE};
 use crate::sigint;
 
 /// An error which may occur whilst trying to compute a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum SolutionError {
     /// No solution was found for the provided header.
     NoSolution,
     /// The alogithm has been interrupted.
     Interrupted,
+    /// The search required more memory than allowed by its [`Limits`].
+    MemoryLimit,
+    /// The search required more steps than allowed by its [`Limits`].
+    NodeLimit,
+    /// The search went on past the deadline of its [`Limits`].
+    Timeout,
+}
+
+/// Statistics about a search.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// The number of values that have been tried on a cell.
+    pub steps: usize,
+    /// The number of times the search had to go back to a previous cell because no value was
+    /// left to try.
+    pub backtracks: usize,
+    /// The maximum number of cells on which the search was backtracking at the same time.
+    pub max_depth: usize,
+    /// The number of cells fixed by the clues and the givens alone, before any value was tried.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub deduced: usize,
+}
+
+/// The way the backtracking search picks the cell to branch on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Heuristic {
+    /// The undetermined cell with the fewest candidates, the first one in row-major order
+    /// winning ties (minimum remaining values).
+    #[default]
+    Mrv,
+    /// The first undetermined cell in row-major order.
+    Lex,
+}
+
+/// Limits that a search must respect.
+///
+/// The heuristic of the search is provided along with them, as every search takes them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    /// The maximum number of bytes that the boards of the search may occupy.
+    pub max_memory: Option<usize>,
+    /// The maximum number of steps that the search may take.
+    ///
+    /// Unlike a timeout, this limit does not depend on the speed of the machine.
+    pub max_nodes: Option<usize>,
+    /// The instant after which the search is aborted.
+    #[cfg(feature = "std")]
+    pub deadline: Option<std::time::Instant>,
+    /// The way the search picks the cell to branch on.
+    pub heuristic: Heuristic,
 }
 
 /// No solution is possible.
 struct NoSolution;
 
+impl core::fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::NoSolution => f.write_str("no solution found"),
+            Self::Interrupted => f.write_str("the search has been interrupted"),
+            Self::MemoryLimit => f.write_str("the search exceeded its memory budget"),
+            Self::NodeLimit => f.write_str("the search exceeded its node budget"),
+            Self::Timeout => f.write_str("the search exceeded its time budget"),
+        }
+    }
+}
+
+impl core::error::Error for SolutionError {}
+
+impl From<crate::error::Interrupted> for SolutionError {
+    fn from(_value: crate::error::Interrupted) -> Self {
+        Self::Interrupted
+    }
+}
+
 impl From<NoSolution> for SolutionError {
     fn from(_value: NoSolution) -> Self {
         Self::NoSolution
     }
 }
 
-/// Contains the values available for a given board cell.
-#[repr(transparent)]
-struct BoardCell([u8]);
-
-impl BoardCell {
-    /// Creates a new [`BoardCell`] instance.
-    ///
-    /// # Safety
-    ///
-    /// * This function assumes `slice` has a length of at least `2`.
-    /// * And that its first element is smaller than its length.
-    unsafe fn wrap_ref(slice: &[u8]) -> &Self {
-        debug_assert!(slice.len() >= 2);
-        debug_assert!((slice[0] as usize) < slice.len());
-
-        // SAFETY:
-        //  - `BoardCell` is a `#[repr(transparent)]` wrapper around `[u8]`.
-        unsafe { core::mem::transmute(slice) }
+/// Returns the bit of a [`BoardCell`] standing for `value`, or `0` when `value` is not a valid
+/// height.
+fn bit(value: u8) -> u64 {
+    match value {
+        1..=64 => 1 << (value - 1),
+        _ => 0,
     }
+}
 
-    /// Creates a new [`BoardCell`] instance.
-    ///
-    /// # Safety
-    ///
-    /// * This function assumes `slice` has a length of at least `2`.
-    /// * And that its first element is smaller than its length.
-    unsafe fn wrap_mut(slice: &mut [u8]) -> &mut Self {
-        debug_assert!(slice.len() >= 2);
-        debug_assert!((slice[0] as usize) < slice.len());
+/// Contains the values available for a given board cell.
+///
+/// The value `v` is available when the bit `v - 1` is set, which is why the solver does not
+/// support boards larger than [`MAX_SIZE`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct BoardCell(u64);
 
-        // SAFETY:
-        //  - `BoardCell` is a `#[repr(transparent)]` wrapper around `[u8]`.
-        unsafe { core::mem::transmute(slice) }
+impl BoardCell {
+    /// Creates a [`BoardCell`] that accepts every value from `1` to `size`.
+    fn full(size: usize) -> Self {
+        Self(u64::MAX.checked_shr(64 - size as u32).unwrap_or(0))
     }
 
     /// Returns whether this cell accepts a certain value.
-    pub fn accepts(&self, value: u8) -> bool {
-        self.0.contains(&value)
+    pub fn accepts(self, value: u8) -> bool {
+        self.0 & bit(value) != 0
     }
 
     /// Returns the number of element allowed for this cell.
-    pub fn count(&self) -> usize {
-        // SAFETY:
-        //  `BoardCell` knows that its slice has a length greater or equal to `2`.
-        unsafe { *self.0.get_unchecked(0) as usize }
+    pub fn count(self) -> usize {
+        self.0.count_ones() as usize
     }
 
-    /// Returns a slice over the values allowed by this cell.
-    pub fn slice(&self) -> &[u8] {
-        let len = self.count();
+    /// Returns the smallest value allowed by this cell, or `0` when it allows none.
+    pub fn first(self) -> u8 {
+        match self.0 {
+            0 => 0,
+            mask => mask.trailing_zeros() as u8 + 1,
+        }
+    }
 
-        // SAFETY:
-        //  The slice is known to be large enough to store `len + 1` elements.
-        unsafe { self.0.get_unchecked(1..1 + len) }
+    /// Returns the values allowed by this cell, in increasing order.
+    pub fn values(self) -> Candidates {
+        Candidates(self.0)
     }
 
     /// Sets the value of this cell to `value`.
@@ -84,13 +158,7 @@ impl BoardCell {
     /// If the cell forbids the provided value, an error is returned.
     pub fn set(&mut self, value: u8) -> Result<(), NoSolution> {
         if self.accepts(value) {
-            // SAFETY:
-            //  `BoardCell` knows that its length is greater or equal to `2`.
-            unsafe {
-                *self.0.get_unchecked_mut(0) = 1;
-                *self.0.get_unchecked_mut(1) = value;
-            }
-
+            self.0 = bit(value);
             Ok(())
         } else {
             Err(NoSolution)
@@ -99,120 +167,176 @@ impl BoardCell {
 
     /// Tries to disallow a value for this cell.
     ///
-    /// If the value was already disallowed, `false` is returned. Otherwise, `true` is returned.
+    /// Returns whether the value was allowed before.
     pub fn forbid(&mut self, value: u8) -> bool {
-        if let Some(pos) = self.slice().iter().position(|&b| b == value) {
-            unsafe {
-                // SAFETY:
-                //  The size of the inner slice is known to be larger than `2`.
-                *self.0.get_unchecked_mut(0) -= 1;
-                let len = *self.0.get_unchecked(0) as usize;
+        let allowed = self.accepts(value);
+        self.0 &= !bit(value);
+        allowed
+    }
+}
 
-                // SAFETY:
-                //  `pos` has been returned
-                *self.0.get_unchecked_mut(1 + pos) = *self.0.get_unchecked(1 + len);
-            }
+/// The values that are still allowed for a cell, in increasing order.
+#[derive(Debug, Clone)]
+pub struct Candidates(u64);
 
-            true
-        } else {
-            false
+impl Iterator for Candidates {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
         }
+
+        let value = self.0.trailing_zeros() as u8 + 1;
+        self.0 &= self.0 - 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
     }
 }
 
+impl ExactSizeIterator for Candidates {}
+
+impl core::iter::FusedIterator for Candidates {}
+
+/// A state of a [`BoardSet`], as returned by [`BoardSet::mark`].
+#[derive(Clone, Copy)]
+struct Mark {
+    /// The length of the trail.
+    len: usize,
+    /// The number of fixed cells.
+    fixed: usize,
+}
+
 /// Stores every possible value available for each cell of a board.
 #[derive(Clone)]
 struct BoardSet {
-    /// The backing array of this [`BoardSet`].
-    ///
-    /// This array has a size of `size * size * (size + 1)` bytes. Where `size` is the size of the
-    /// input skyscrapper board.
-    ///
-    /// Each cell takes `size + 1` bytes. The first byte represents how many possible values the
-    /// cell has, and the `size` other bytes are the actual possible values.
-    ///
-    /// For example:
-    ///
-    /// ```txt
-    /// |3|1|2|5|4|3|
-    /// ```
-    ///
-    /// When a size of `5`, above cell accepts the values 1, 2, and 5. 3 values in total.
-    array: Box<[u8]>,
+    /// The cells of the board, in row-major order.
+    cells: Box<[BoardCell]>,
     /// The size that was used to create the `BoardSet`.
     ///
     /// This tiny bit of redundancy makes the program much more safe and easy to use and maintain.
     size: usize,
+    /// The candidates removed from the cells so far, as `(index, removed)` pairs, `removed` being
+    /// a mask of the values taken away from the cell at `index`.
+    ///
+    /// Every change to `cells` is recorded here, such that [`BoardSet::rewind`] can undo it.
+    trail: Vec<(usize, u64)>,
+    /// The number of cells that allow a single value.
+    fixed: usize,
 }
 
 impl BoardSet {
     /// Creates a new [`BoardSet`] instance.
     ///
     /// Every cell of the created board will accept every possible value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `size` exceeds [`MAX_SIZE`].
     pub fn new(size: usize) -> Self {
-        let mut array = Vec::with_capacity(size * size * (size + 1));
-
-        for _ in 0..size * size {
-            array.push(size as u8);
-            array.extend(1..=size as u8);
-        }
-
-        debug_assert_eq!(array.len(), array.capacity());
-        debug_assert_eq!(array.capacity(), size * size * (size + 1));
+        assert!(
+            size <= MAX_SIZE,
+            "the solver does not support boards larger than {MAX_SIZE}"
+        );
 
         Self {
-            array: array.into_boxed_slice(),
+            cells: alloc::vec![BoardCell::full(size); size * size].into_boxed_slice(),
             size,
+            trail: Vec::new(),
+            // Cells of a board of size 1 start with a single value.
+            fixed: if size == 1 { 1 } else { 0 },
         }
     }
 
-    /// Gets the state of a cell of this [`Board`].
-    ///
-    /// # Safety
+    /// Sets the value of the cell at `(x, y)` to `value`.
     ///
-    /// `index` must be the start of a cell bounary.
-    /// `index` must be on a cell bounary.
-    pub unsafe fn cell_mut(&mut self, index: usize) -> &mut BoardCell {
-        debug_assert_eq!(index % (self.size + 1), 0);
-        debug_assert!(index < self.array.len());
+    /// If the cell forbids the provided value, an error is returned.
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, value: u8) -> Result<(), NoSolution> {
+        debug_assert!(x < self.size);
+        let index = x + y * self.size;
 
-        // SAFETY:
-        //  `index` is on a cell bounary, ensuring that this slice is in bounds.
-        let slice = unsafe { self.array.get_unchecked_mut(index..index + 1 + self.size) };
+        let removed = self.cells[index].0 & !bit(value);
+        self.cells[index].set(value)?;
+        if removed != 0 {
+            self.trail.push((index, removed));
+            self.fixed += 1;
+        }
 
-        // SAFETY:
-        //  We know by invariant of `BoardSet` that each cell contains `size + 1` elements, ensuring
-        //  that both preconditions are validated.
-        unsafe { BoardCell::wrap_mut(slice) }
+        Ok(())
     }
 
-    /// Gets the state of a cell of this [`Board`].
+    /// Disallows `value` for the cell at `(x, y)`.
     ///
-    /// # Safety
-    ///
-    /// `index` must be the start of a cell bounary.
-    pub unsafe fn cell(&self, index: usize) -> &BoardCell {
-        debug_assert_eq!(index % (self.size + 1), 0);
-        debug_assert!(index < self.array.len());
+    /// When the cell is left with a single value, it is added to `buf`. When it is left with
+    /// none, `NoSolution` is returned.
+    #[inline]
+    pub fn forbid(
+        &mut self,
+        x: usize,
+        y: usize,
+        value: u8,
+        buf: &mut Vec<(usize, usize)>,
+    ) -> Result<(), NoSolution> {
+        debug_assert!(x < self.size);
+        let index = x + y * self.size;
+
+        let cell = &mut self.cells[index];
+        if !cell.forbid(value) {
+            return Ok(());
+        }
+        let count = cell.count();
+        self.trail.push((index, bit(value)));
+
+        match count {
+            0 => {
+                self.fixed -= 1;
+                Err(NoSolution)
+            }
+            1 => {
+                self.fixed += 1;
+                buf.push((x, y));
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a [`Mark`] that [`BoardSet::rewind`] can restore the board to.
+    pub fn mark(&self) -> Mark {
+        Mark {
+            len: self.trail.len(),
+            fixed: self.fixed,
+        }
+    }
+
+    /// Restores the candidates removed since `mark` was taken.
+    pub fn rewind(&mut self, mark: Mark) {
+        for (index, removed) in self.trail.drain(mark.len..) {
+            self.cells[index].0 |= removed;
+        }
+        self.fixed = mark.fixed;
+    }
 
-        // SAFETY:
-        //  `index` is on a cell bounary, ensuring that this slice is in bounds.
-        let slice = unsafe { self.array.get_unchecked(index..index + 1 + self.size) };
+    /// Returns the number of bytes used by this [`BoardSet`].
+    pub fn memory(&self) -> usize {
+        core::mem::size_of_val(&*self.cells) + core::mem::size_of_val(&*self.trail)
+    }
 
-        // SAFETY:
-        //  We know by invariant of `BoardSet` that each cell contains `size + 1` elements, ensuring
-        //  that both preconditions are validated.
-        unsafe { BoardCell::wrap_ref(slice) }
+    /// Gets the state of the cell at `(x, y)`.
+    pub fn cell(&self, x: usize, y: usize) -> BoardCell {
+        debug_assert!(x < self.size);
+        self.cells[x + y * self.size]
     }
 
     /// Account for a specific header value associated with a collection of indices.
     ///
     /// Cells that are set to a single value are added to `buf`.
-    ///
-    /// # Safety
-    ///
-    /// `indices` must return valid cell coordinates.
-    unsafe fn _account_for_header(
+    fn _account_for_header(
         &mut self,
         value: u8,
         mut indices: impl Iterator<Item = (usize, usize)>,
@@ -220,48 +344,147 @@ impl BoardSet {
     ) -> Result<(), NoSolution> {
         let size = self.size as u8;
 
-        if value == 1 {
+        if value == 0 {
+            // The clue is missing.
+            return Ok(());
+        } else if value == 1 {
             // The value one only allows for the maximum value directly before itself.
             let (x, y) = indices.next().unwrap();
-            let index = x * (self.size + 1) + y * (self.size + 1) * self.size;
-            // SAFETY:
-            //  The iterator must provide valid cell indices.
-            let cell = unsafe { self.cell_mut(index) };
-            cell.set(size)?;
+            self.set(x, y, size)?;
             buf.push((x, y));
             return Ok(());
         } else if value == self.size as u8 {
             // The maximum value only allows one configuration.
             for (i, (x, y)) in indices.enumerate() {
-                let index = x * (self.size + 1) + y * (self.size + 1) * self.size;
-                // SAFETY:
-                //  The iterator must provide valid indices.
-                let cell = unsafe { self.cell_mut(index) };
-
-                cell.set((i + 1) as u8)?;
+                self.set(x, y, (i + 1) as u8)?;
                 buf.push((x, y));
             }
             return Ok(());
         }
 
         for (i, (x, y)) in indices.enumerate() {
-            let index = x * (self.size + 1) + y * self.size * (self.size + 1);
-
-            // SAFETY:
-            //  `indices` must yield valid cell indices.
-            let cell = unsafe { self.cell_mut(index) };
-
             // TODO: optimization
             //  Create a `forbid_greater` that removes all elements that are greater than a given
             //  value. That would be fore efficient than calling `forbid` in a loop.
             let first_to_remove = size - value + 2 + i as u8;
             for to_remove in first_to_remove..=size {
-                if cell.forbid(to_remove) {
-                    match cell.count() {
-                        0 => return Err(NoSolution),
-                        1 => buf.push((x, y)),
-                        _ => (),
-                    }
+                self.forbid(x, y, to_remove, buf)?;
+            }
+
+            // With a clue of 2, the second cell can't hold the second tallest building: the first
+            // one would be seen before it, and the tallest one after it.
+            if value == 2 && i == 1 {
+                self.forbid(x, y, size - 1, buf)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Accounts for the clues found at both ends of the same line.
+    ///
+    /// The tallest building is seen from both ends, after every other building seen from either
+    /// of them. The clues can't add up to more than `size + 1`, and when they add up to exactly
+    /// `size + 1`, the position of the tallest building is known.
+    ///
+    /// `indices` must return the cells of the line, starting from the end of `first`.
+    fn _account_for_clue_pair(
+        &mut self,
+        first: u8,
+        last: u8,
+        mut indices: impl Iterator<Item = (usize, usize)>,
+        buf: &mut Vec<(usize, usize)>,
+    ) -> Result<(), NoSolution> {
+        if first == 0 || last == 0 {
+            // One of the clues is missing.
+            return Ok(());
+        }
+
+        let size = self.size;
+        let sum = first as usize + last as usize;
+        if sum > size + 1 {
+            return Err(NoSolution);
+        } else if sum == size + 1 {
+            let (x, y) = indices.nth(first as usize - 1).unwrap();
+            let was_fixed = self.cell(x, y).count() == 1;
+            self.set(x, y, size as u8)?;
+            if !was_fixed {
+                buf.push((x, y));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies a clue again, accounting for the cells of its line that are already fixed.
+    ///
+    /// The fixed cells at the start of the line are seen like in a complete board. The other
+    /// cells must provide the remaining views, which only buildings taller than every fixed one
+    /// can provide.
+    ///
+    /// Cells that are set to a single value are added to `buf`. `indices` must return the cells of
+    /// the line, starting from the clue.
+    fn _account_for_fixed(
+        &mut self,
+        clue: u8,
+        indices: impl Iterator<Item = (usize, usize)> + Clone,
+        buf: &mut Vec<(usize, usize)>,
+    ) -> Result<(), NoSolution> {
+        if clue == 0 {
+            // The clue is missing.
+            return Ok(());
+        }
+
+        let size = self.size;
+
+        // The number of fixed cells at the start of the line, the number of buildings seen among
+        // them, and the tallest of them.
+        let mut fixed = 0;
+        let mut views = 0;
+        let mut tallest = 0;
+        for (x, y) in indices.clone() {
+            let cell = self.cell(x, y);
+            if cell.count() != 1 {
+                break;
+            }
+
+            let value = cell.first();
+            if value > tallest {
+                views += 1;
+                tallest = value;
+            }
+            fixed += 1;
+        }
+
+        if tallest as usize == size {
+            // Nothing can be seen behind the tallest building.
+            return match views == clue {
+                true => Ok(()),
+                false => Err(NoSolution),
+            };
+        }
+
+        // The tallest building is yet to be placed, and it will be seen.
+        let remaining = match clue.checked_sub(views) {
+            Some(remaining) if remaining != 0 => remaining as usize,
+            _ => return Err(NoSolution),
+        };
+        if remaining > size - fixed || remaining > size - tallest as usize {
+            return Err(NoSolution);
+        }
+
+        // Cells further away than `remaining` can hold any value.
+        for (distance, (x, y)) in indices.skip(fixed).take(remaining).enumerate() {
+            // Every building seen after this one is taller than it.
+            let max = size + 1 + distance - remaining;
+
+            for value in 1..=size {
+                // When a single building remains to be seen, it is the tallest one, and it must
+                // not be hidden by a building taller than the fixed ones.
+                let hides = remaining == 1 && value > tallest as usize && value != size;
+
+                if value > max || hides {
+                    self.forbid(x, y, value as u8, buf)?;
                 }
             }
         }
@@ -269,6 +492,81 @@ impl BoardSet {
         Ok(())
     }
 
+    /// Runs `f`, reporting to `observer` the candidates it removes as deductions that follow from
+    /// `cause`. When `f` leads to a contradiction, it is reported instead.
+    #[inline]
+    fn traced(
+        &mut self,
+        cause: Cause,
+        observer: Option<&mut (dyn SolveObserver + '_)>,
+        f: impl FnOnce(&mut Self) -> Result<(), NoSolution>,
+    ) -> Result<(), NoSolution> {
+        match observer {
+            None => f(self),
+            Some(observer) => self.report(cause, observer, f),
+        }
+    }
+
+    /// The slow path of [`BoardSet::traced`], kept out of the search when it is not traced.
+    #[inline(never)]
+    fn report(
+        &mut self,
+        cause: Cause,
+        observer: &mut dyn SolveObserver,
+        f: impl FnOnce(&mut Self) -> Result<(), NoSolution>,
+    ) -> Result<(), NoSolution> {
+        let before = self.cells.clone();
+        if let Err(err) = f(self) {
+            observer.on_contradiction(cause);
+            return Err(err);
+        }
+
+        let size = self.size;
+        let mut removed = Vec::new();
+        let mut left = Vec::new();
+        for (i, (&old, &new)) in before.iter().zip(self.cells.iter()).enumerate() {
+            if old == new {
+                continue;
+            }
+
+            removed.clear();
+            removed.extend(old.values().filter(|&v| !new.accepts(v)));
+            left.clear();
+            left.extend(new.values());
+
+            observer.on_deduction(&Deduction {
+                cause,
+                x: i % size,
+                y: i / size,
+                removed: &removed,
+                left: &left,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Sets the cells of this board to the values provided in `givens`.
+    ///
+    /// `givens` is either empty, or holds one value per cell. Cells whose given value is `0` are
+    /// left untouched. The cells that have been set are added to `buf`.
+    pub fn account_for_givens(
+        &mut self,
+        givens: &[u8],
+        buf: &mut Vec<(usize, usize)>,
+    ) -> Result<(), NoSolution> {
+        for (i, &value) in givens.iter().enumerate().take(self.size * self.size) {
+            if value == 0 {
+                continue;
+            }
+
+            self.set(i % self.size, i / self.size, value)?;
+            buf.push((i % self.size, i / self.size));
+        }
+
+        Ok(())
+    }
+
     // TODO: optimization
     //  This function seems to add the same coordinates multiple times (up to four times in the
     //  worst case) to the buffer. Being able to mitigate that would be great.
@@ -276,44 +574,127 @@ impl BoardSet {
     /// Modifies the allowed values for each cell of this board using the provided header-line.
     pub fn account_for_header(
         &mut self,
-        header: &[u8],
+        header: &Header,
+        buf: &mut Vec<(usize, usize)>,
+    ) -> Result<(), NoSolution> {
+        self.account_for_header_traced(header, buf, None)
+    }
+
+    /// Like [`BoardSet::account_for_header`], but reports every deduction to `observer`.
+    pub fn account_for_header_traced(
+        &mut self,
+        header: &Header,
         buf: &mut Vec<(usize, usize)>,
+        mut observer: Option<&mut (dyn SolveObserver + '_)>,
     ) -> Result<(), NoSolution> {
         let size = self.size;
 
-        assert_eq!(header.len(), size * 4);
+        assert_eq!(header.size(), size);
+
+        for (col, (&top, &bottom)) in header.top().iter().zip(header.bottom()).enumerate() {
+            let (t, b) = (col, size + col);
+            self.traced(Cause::Clue(t), observer.as_deref_mut(), |set| {
+                set._account_for_header(top, (0..size).map(|y| (col, y)), buf)
+            })?;
+            self.traced(Cause::Clue(b), observer.as_deref_mut(), |set| {
+                set._account_for_header(bottom, (0..size).rev().map(|y| (col, y)), buf)
+            })?;
+            self.traced(Cause::CluePair(t, b), observer.as_deref_mut(), |set| {
+                set._account_for_clue_pair(top, bottom, (0..size).map(|y| (col, y)), buf)
+            })?;
+        }
 
-        for col in 0..size {
-            // SAFETY:
-            //  We know that the header has a size of `size * 4`.
-            let views_from_top = unsafe { *header.get_unchecked(col) };
+        for (row, (&left, &right)) in header.left().iter().zip(header.right()).enumerate() {
+            let (l, r) = (2 * size + row, 3 * size + row);
+            self.traced(Cause::Clue(l), observer.as_deref_mut(), |set| {
+                set._account_for_header(left, (0..size).map(|x| (x, row)), buf)
+            })?;
+            self.traced(Cause::Clue(r), observer.as_deref_mut(), |set| {
+                set._account_for_header(right, (0..size).rev().map(|x| (x, row)), buf)
+            })?;
+            self.traced(Cause::CluePair(l, r), observer.as_deref_mut(), |set| {
+                set._account_for_clue_pair(left, right, (0..size).map(|x| (x, row)), buf)
+            })?;
+        }
 
-            unsafe {
-                self._account_for_header(views_from_top, (0..size).map(|y| (col, y)), buf)?;
-            }
+        Ok(())
+    }
+
+    /// Applies the clues of the provided header-line again, accounting for the cells that are
+    /// already fixed.
+    ///
+    /// The cells that get fixed are added to `buf`.
+    pub fn account_for_fixed(
+        &mut self,
+        header: &Header,
+        buf: &mut Vec<(usize, usize)>,
+        mut observer: Option<&mut (dyn SolveObserver + '_)>,
+    ) -> Result<(), NoSolution> {
+        let size = self.size;
 
-            let views_from_bottom = unsafe { *header.get_unchecked(size + col) };
+        assert_eq!(header.size(), size);
 
-            unsafe {
-                self._account_for_header(views_from_bottom, (0..size).rev().map(|y| (col, y)), buf)
-            }?;
+        for (col, (&top, &bottom)) in header.top().iter().zip(header.bottom()).enumerate() {
+            self.traced(Cause::Clue(col), observer.as_deref_mut(), |set| {
+                set._account_for_fixed(top, (0..size).map(|y| (col, y)), buf)
+            })?;
+            self.traced(Cause::Clue(size + col), observer.as_deref_mut(), |set| {
+                set._account_for_fixed(bottom, (0..size).rev().map(|y| (col, y)), buf)
+            })?;
         }
 
-        for row in 0..size {
-            let views_from_left = unsafe { *header.get_unchecked(size * 2 + row) };
+        for (row, (&left, &right)) in header.left().iter().zip(header.right()).enumerate() {
+            self.traced(
+                Cause::Clue(2 * size + row),
+                observer.as_deref_mut(),
+                |set| set._account_for_fixed(left, (0..size).map(|x| (x, row)), buf),
+            )?;
+            self.traced(
+                Cause::Clue(3 * size + row),
+                observer.as_deref_mut(),
+                |set| set._account_for_fixed(right, (0..size).rev().map(|x| (x, row)), buf),
+            )?;
+        }
 
-            unsafe {
-                self._account_for_header(views_from_left, (0..size).map(|x| (x, row)), buf)?;
-            }
+        Ok(())
+    }
 
-            let views_from_right = unsafe { *header.get_unchecked(size * 3 + row) };
+    /// Removes the duplicates around the cells of `buf`, and applies the clues of the provided
+    /// header-line again, until no more cell gets fixed.
+    ///
+    /// `buf` is left empty.
+    pub fn propagate(
+        &mut self,
+        header: &Header,
+        buf: &mut Vec<(usize, usize)>,
+    ) -> Result<(), NoSolution> {
+        self.propagate_traced(header, buf, None)
+    }
+
+    /// Like [`BoardSet::propagate`], but reports every deduction to `observer`.
+    pub fn propagate_traced(
+        &mut self,
+        header: &Header,
+        buf: &mut Vec<(usize, usize)>,
+        mut observer: Option<&mut (dyn SolveObserver + '_)>,
+    ) -> Result<(), NoSolution> {
+        loop {
+            match observer.as_deref_mut() {
+                None => self.remove_duplicates_in(buf)?,
+                Some(observer) => {
+                    while let Some((x, y)) = buf.pop() {
+                        self.traced(Cause::Fixed { x, y }, Some(&mut *observer), |set| {
+                            set.remove_duplicates_around(x, y, buf)
+                        })?;
+                    }
+                }
+            }
+            self.account_for_fixed(header, buf, observer.as_deref_mut())?;
 
-            unsafe {
-                self._account_for_header(views_from_right, (0..size).rev().map(|x| (x, row)), buf)?;
+            if buf.is_empty() {
+                return Ok(());
             }
         }
-
-        Ok(())
     }
 
     fn _remove_duplicates(
@@ -330,15 +711,7 @@ impl BoardSet {
                 continue;
             }
 
-            let index = (self.size + 1) * col + (self.size + 1) * self.size * y;
-            let cell = unsafe { self.cell_mut(index) };
-            if cell.forbid(value) {
-                match cell.count() {
-                    0 => return Err(NoSolution),
-                    1 => now_fixed.push((col, y)),
-                    _ => (),
-                }
-            };
+            self.forbid(col, y, value, now_fixed)?;
         }
 
         // same column
@@ -348,15 +721,7 @@ impl BoardSet {
                 continue;
             }
 
-            let index = (self.size + 1) * x + (self.size + 1) * self.size * row;
-            let cell = unsafe { self.cell_mut(index) };
-            if cell.forbid(value) {
-                match cell.count() {
-                    0 => return Err(NoSolution),
-                    1 => now_fixed.push((x, row)),
-                    _ => (),
-                }
-            };
+            self.forbid(x, row, value, now_fixed)?;
         }
 
         Ok(())
@@ -370,33 +735,14 @@ impl BoardSet {
     //  be better. And `forbid` should be renamed `remove`, it's clearer this way.
     //
     /// Sets the provided cell to `value` and forbids duplicates around that value.
-    ///
-    /// # Safety
-    ///
-    /// * `x` and `y` must be in bounds.
-    /// * `subindex` must be in bounds.
-    pub unsafe fn set_and_remove_duplicates(
+    pub fn set_and_remove_duplicates(
         &mut self,
         x: usize,
         y: usize,
-        subindex: usize,
+        value: u8,
         now_fixed: &mut Vec<(usize, usize)>,
     ) -> Result<(), NoSolution> {
-        debug_assert!(x < self.size);
-        debug_assert!(y < self.size);
-
-        let index = (self.size + 1) * x + (self.size + 1) * self.size * y;
-
-        // SAFETY:
-        //  If `x` and `y` are in bounds, then `index` is a valid index.
-        let cell = unsafe { self.cell_mut(index) };
-
-        // SAFETY:
-        //  The caller must provide a valid subindex.
-        let value = unsafe { *cell.slice().get_unchecked(subindex) };
-
-        cell.set(value)?;
-
+        self.set(x, y, value)?;
         self._remove_duplicates(x, y, value, now_fixed)
     }
 
@@ -406,33 +752,16 @@ impl BoardSet {
     /// If removing duplicates for a cell disallows *every* value, `NoSolution` is returned.
     ///
     /// The cells that now contain only one possible value are pushed to `now_fixed`.
-    ///
-    /// # Safety
-    ///
-    /// * `x` and `y` must be in bounds (less than the size).
-    /// * The cell at that position must allow exactly one value.
-    pub unsafe fn remove_duplicates_around(
+    pub fn remove_duplicates_around(
         &mut self,
         x: usize,
         y: usize,
         now_fixed: &mut Vec<(usize, usize)>,
     ) -> Result<(), NoSolution> {
-        debug_assert!(x < self.size);
-        debug_assert!(y < self.size);
-
-        let index = (self.size + 1) * x + (self.size + 1) * self.size * y;
-
-        // SAFETY:
-        //  If `x` and `y` are in bounds, then `index` is a valid index.
-        let cell = unsafe { self.cell(index) };
-
+        let cell = self.cell(x, y);
         debug_assert_eq!(cell.count(), 1);
 
-        // SAFETY:
-        //  The caller must make sure that this cell contains at least one value.
-        let value = unsafe { *cell.slice().get_unchecked(0) };
-
-        self._remove_duplicates(x, y, value, now_fixed)
+        self._remove_duplicates(x, y, cell.first(), now_fixed)
     }
 
     /// Removes the duplicates around the values specified in the provided vector, leaving that
@@ -442,46 +771,99 @@ impl BoardSet {
         buf: &mut Vec<(usize, usize)>,
     ) -> Result<(), NoSolution> {
         while let Some((x, y)) = buf.pop() {
-            unsafe { self.remove_duplicates_around(x, y, buf)? };
+            self.remove_duplicates_around(x, y, buf)?;
         }
 
         Ok(())
     }
 
+    /// Returns the index of the cell to branch on: the undetermined cell with the fewest
+    /// candidates, the first one in row-major order winning ties.
+    ///
+    /// `None` is returned when every cell is fixed.
+    pub fn branch_index(&self) -> Option<usize> {
+        self.branch_index_from(0).map(|(i, _)| i)
+    }
+
+    /// Like [`BoardSet::branch_index`], but only looks up the cells from `start`, and returns
+    /// the number of candidates of the cell along with its index.
+    fn branch_index_from(&self, start: usize) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+
+        for (i, cell) in self.cells.iter().enumerate().skip(start) {
+            let count = cell.count();
+            if count == 1 || best.is_some_and(|(_, min)| min <= count) {
+                continue;
+            }
+
+            best = Some((i, count));
+            if count <= 2 {
+                // No undetermined cell can have fewer candidates.
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Returns the index of the first cell from `start` that allows more than one value, in
+    /// row-major order.
+    fn first_undetermined_from(&self, start: usize) -> Option<usize> {
+        (start..self.cells.len()).find(|&i| self.cells[i].count() > 1)
+    }
+
+    /// Returns the number of cells that allow a single value.
+    pub fn fixed(&self) -> usize {
+        self.fixed
+    }
+
     /// Assumes that the board is complete and turns it into a normal board.
-    pub fn create_board(&self) -> Box<[u8]> {
-        (0..self.size * self.size)
-            .map(|i| {
-                let index = i * (self.size + 1);
-                let cell = unsafe { self.cell(index) };
-                if cell.count() == 1 {
-                    cell.slice()[0]
-                } else {
-                    0
-                }
+    pub fn create_board(&self) -> Board {
+        let cells = self
+            .cells
+            .iter()
+            .map(|cell| match cell.count() {
+                1 => cell.first(),
+                _ => 0,
             })
-            .collect()
+            .collect();
+
+        Board::new(self.size, cells)
     }
 }
 
-/// A board that remembers where it stopped backtracking.
+/// A cell of a board that remembers where it stopped backtracking.
 ///
-/// This type is used to backtrack as far as possible witout having to clone the board.
+/// The board itself is shared by every cell of the search: the values tried on this cell are
+/// undone by rewinding the board to `mark`, such that it never has to be cloned.
+#[derive(Clone)]
 struct BacktrackingBoard {
-    /// The original [`BoardSet`], used when actually backtracking.
-    original: BoardSet,
-    /// The inner [`BoardSet`] instance.
-    set: BoardSet,
+    /// The state of the board before any value was tried on this cell.
+    mark: Mark,
     /// The index of the cell on which we are currently backtracking.
     ///
     /// This is always less than `size * size`.
     ///
-    /// Every cell *before* that index are fixed to a single value.
+    /// This is the cell with the fewest candidates, as returned by [`BoardSet::branch_index`],
+    /// unless another heuristic is used.
     current_index: usize,
+    /// The way the cells of the children of this board are picked.
+    heuristic: Heuristic,
+    /// The values that the cell allowed before any was tried.
+    cell: BoardCell,
     /// The index of the value that we will choose next to backtrack.
     ///
     /// This is always in bound of the cell's possibilities.
     current_subindex: usize,
+    /// The index after the last value that will be tried on the current cell.
+    ///
+    /// This is the number of possibilities of the cell, unless the remaining values have been
+    /// handed to another search.
+    end_subindex: usize,
+    /// The order in which the possibilities of the cell are tried, as indices in them.
+    ///
+    /// When empty, they are tried in increasing order.
+    order: Vec<u8>,
 }
 
 /// An error which may occur when backtracking.
@@ -494,80 +876,138 @@ enum BacktrackError {
 }
 
 impl BacktrackingBoard {
-    /// Creates a new [`BacktrackingBoard`] from the provided [`BoardSet`].
+    /// Creates a new [`BacktrackingBoard`] that branches on the cell of the provided
+    /// [`BoardSet`] picked by `heuristic`.
     ///
-    /// If the provided board is already complete, the function returns [`Err`] with the input
-    /// [`BoardSet`].
-    pub fn new(set: BoardSet) -> Result<Self, BoardSet> {
-        let mut current_index = 0;
-
-        while current_index < set.size * set.size
-            && unsafe { set.cell(current_index * (set.size + 1)) }.count() == 1
-        {
-            current_index += 1;
-        }
-
-        if current_index == set.size * set.size {
-            return Err(set);
-        }
+    /// If the provided board is already complete, the function returns [`None`].
+    pub fn new(set: &BoardSet, heuristic: Heuristic) -> Option<Self> {
+        let current_index = match heuristic {
+            Heuristic::Mrv => set.branch_index()?,
+            Heuristic::Lex => set.first_undetermined_from(0)?,
+        };
+        let cell = set.cells[current_index];
 
-        Ok(Self {
-            original: set.clone(),
-            set,
+        Some(Self {
+            mark: set.mark(),
+            current_index,
+            heuristic,
+            cell,
+            current_subindex: 0,
+            end_subindex: cell.count(),
+            order: Vec::new(),
+        })
+    }
+
+    /// Creates the [`BacktrackingBoard`] that branches on `set` once a value of `parent` has been
+    /// tried, without looking up every cell.
+    ///
+    /// This is the cell [`BacktrackingBoard::new`] would choose. The cells that have not changed
+    /// since `parent` was created, and that come before its cell, have more candidates than it.
+    /// They can be skipped, unless every other cell has more candidates too. With
+    /// [`Heuristic::Lex`], the cells before the one of `parent` are all fixed.
+    pub fn fork_from(parent: &Self, set: &BoardSet) -> Option<Self> {
+        if set.fixed() == set.cells.len() {
+            return None;
+        }
+
+        let current_index = match parent.heuristic {
+            Heuristic::Mrv => {
+                let start = set.trail[parent.mark.len..]
+                    .iter()
+                    .fold(parent.current_index, |start, &(i, _)| start.min(i));
+                match set.branch_index_from(start) {
+                    Some((i, count)) if count <= parent.candidates() => i,
+                    _ => set.branch_index()?,
+                }
+            }
+            Heuristic::Lex => set.first_undetermined_from(parent.current_index)?,
+        };
+        let cell = set.cells[current_index];
+
+        Some(Self {
+            mark: set.mark(),
             current_index,
+            heuristic: parent.heuristic,
+            cell,
             current_subindex: 0,
+            end_subindex: cell.count(),
+            order: Vec::new(),
         })
     }
 
-    fn _try_backtrack(&mut self, buf: &mut Vec<(usize, usize)>) -> Result<(), NoSolution> {
+    /// Tries the possibilities of the current cell in a random order.
+    pub fn shuffle(&mut self, rng: &mut dyn RngCore) {
+        use rand::seq::SliceRandom;
+
+        self.order = (0..self.candidates() as u8).collect();
+        self.order.shuffle(rng);
+    }
+
+    /// Returns the index of the possibility tried at `subindex`, in the possibilities of the
+    /// current cell.
+    fn candidate(&self, subindex: usize) -> usize {
+        self.order.get(subindex).map_or(subindex, |&i| i as usize)
+    }
+
+    /// Returns the value tried at `subindex` on the current cell, if any.
+    fn value(&self, subindex: usize) -> Option<u8> {
+        self.cell.values().nth(self.candidate(subindex))
+    }
+
+    /// Returns the number of values that can be tried on the cell currently being backtracked.
+    pub fn candidates(&self) -> usize {
+        self.cell.count()
+    }
+
+    /// Returns the number of bytes used by this [`BacktrackingBoard`], not counting the board.
+    pub fn memory(&self) -> usize {
+        core::mem::size_of::<Self>() + self.order.len()
+    }
+
+    fn _try_backtrack(
+        &self,
+        set: &mut BoardSet,
+        header: &Header,
+        buf: &mut Vec<(usize, usize)>,
+        mut observer: Option<&mut (dyn SolveObserver + '_)>,
+    ) -> Result<(), NoSolution> {
         buf.clear();
 
-        let x = self.current_index % self.set.size;
-        let y = self.current_index / self.set.size;
+        let x = self.current_index % set.size;
+        let y = self.current_index / set.size;
 
-        unsafe {
-            self.set
-                .set_and_remove_duplicates(x, y, self.current_subindex, buf)?
-        };
+        let value = self.value(self.current_subindex).ok_or(NoSolution)?;
+        set.traced(Cause::Guess, observer.as_deref_mut(), |set| {
+            set.set_and_remove_duplicates(x, y, value, buf)
+        })?;
 
-        self.set.remove_duplicates_in(buf)
+        set.propagate_traced(header, buf, observer)
     }
 
-    // TODO: possible optimization
-    //  If we store the total number of "one" cells, we can check easily whether the board is
-    //  complete or not, AND we can start backtracking on the cells that are the most efficient
-    //  with the least amount of possibilities. We might even be able to cache this too to save
-    //  the lookup.
-    //
-    //  At the moment, we are backtracking from top-left to bottom-right and we know that we're done
-    //  when the backtracking index reaches the end; meaning that `remove_duplicates_around` is not
-    //  as optimized as it could be. In this state, we could simply check for duplicates *after*
-    //  the input index.
-    //
-    //  Something else: we store the "original" board in the `BacktrackingBoard`. Meaning that the
-    //  final stack of `BacktrackingBoard` instance will duplicate one board each.
-    //
-    //  It's probably possible to multi-thread this. Each "fork" is independ from the others, and
-    //  we could spawn a new task for every possible subindex.
-    //
-    /// Tries to continue backtracking using the current state. When an error occurs (no solution is
-    /// possible from this state), the internal state is restored.
+    /// Tries to continue backtracking using the current state. `set` is first restored as it was
+    /// before any value was tried on this cell, which also undoes the previous calls.
     ///
     /// Calling this function again in case of error always produces an error.
     ///
     /// Otherwise, `Ok(())` is returned and the modified state is conserved.
     ///
-    /// `buf` will be cleared and used during the algorithm.
-    pub fn try_backtrack(&mut self, buf: &mut Vec<(usize, usize)>) -> Result<(), BacktrackError> {
-        self.set.array.copy_from_slice(&self.original.array);
+    /// The clues of `header` are applied again once the value is set. `buf` will be cleared and
+    /// used during the algorithm. The deductions made along the way are reported to `observer`.
+    pub fn try_backtrack(
+        &mut self,
+        set: &mut BoardSet,
+        header: &Header,
+        buf: &mut Vec<(usize, usize)>,
+        observer: Option<&mut (dyn SolveObserver + '_)>,
+    ) -> Result<(), BacktrackError> {
+        set.rewind(self.mark);
 
-        let count = unsafe { self.set.cell(self.current_index * (self.set.size + 1)) }.count();
-        if self.current_subindex == count {
+        if self.current_subindex == self.end_subindex {
             // We are out of possible values. There is no possible solution.
             return Err(BacktrackError::NoSolution);
         }
 
-        let result = self._try_backtrack(buf);
+        let result = self._try_backtrack(set, header, buf, observer);
         self.current_subindex += 1;
         match result {
             Ok(()) => Ok(()),
@@ -576,109 +1016,1576 @@ impl BacktrackingBoard {
     }
 }
 
-/// Solves the provided header.
-pub fn solve(header: &[u8], size: usize) -> Result<Box<[u8]>, SolutionError> {
-    let mut buf = Vec::new();
-    let mut set = BoardSet::new(size);
-    set.account_for_header(header, &mut buf)?;
-    set.remove_duplicates_in(&mut buf)?;
+/// Returns whether the provided complete board produces `header`.
+///
+/// The constraints accounted for while backtracking only remove duplicates, meaning that a
+/// complete board may still have the wrong view counts.
+///
+/// A clue of `0` is missing, and accepts any number of views.
+fn satisfies_header(board: &Board, header: &Header) -> bool {
+    crate::generate::solution_to_header(board)
+        .iter()
+        .zip(header.clues())
+        .all(|(&views, &clue)| clue == 0 || views == clue)
+}
 
-    let mut backtrackers = Vec::new();
+/// The result of a single step of a [`Search`].
+enum Step {
+    /// The search must continue.
+    Continue,
+    /// A solution has been found.
+    Solution(Board),
+    /// Every possible board has been explored.
+    Done,
+}
 
-    match BacktrackingBoard::new(set) {
-        Ok(ok) => backtrackers.push(ok),
-        Err(complete) => return Ok(complete.create_board()),
-    };
+/// Creates the [`BoardSet`] of the cells allowed by `header` and `givens`, before any value has
+/// been tried.
+///
+/// `None` is returned when the header and the givens already contradict each other.
+fn root(header: &Header, givens: &[u8], buf: &mut Vec<(usize, usize)>) -> Option<BoardSet> {
+    root_traced(header, givens, buf, None)
+}
+
+/// Like [`root`], but reports every deduction to `observer`.
+fn root_traced(
+    header: &Header,
+    givens: &[u8],
+    buf: &mut Vec<(usize, usize)>,
+    mut observer: Option<&mut (dyn SolveObserver + '_)>,
+) -> Option<BoardSet> {
+    let mut set = BoardSet::new(header.size());
+    if set
+        .account_for_header_traced(header, buf, observer.as_deref_mut())
+        .is_err()
+        || set
+            .traced(Cause::Given, observer.as_deref_mut(), |set| {
+                set.account_for_givens(givens, buf)
+            })
+            .is_err()
+        || set.propagate_traced(header, buf, observer).is_err()
+    {
+        return None;
+    }
+    Some(set)
+}
+
+/// The state of a backtracking search over the solutions of a header.
+struct Search<'a> {
+    /// The header being solved.
+    header: &'a Header,
+    /// The size of the board.
+    size: usize,
+    /// The limits that the search must respect.
+    limits: Limits,
+    /// A buffer used by the [`BacktrackingBoard`]s.
+    buf: Vec<(usize, usize)>,
+    /// The board currently being explored, shared by every [`BacktrackingBoard`].
+    set: BoardSet,
+    /// The stack of backtracking boards. The last one is the one currently explored.
+    backtrackers: Vec<BacktrackingBoard>,
+    /// Shuffles the values tried on every cell, when they are not tried in increasing order.
+    rng: Option<&'a mut dyn RngCore>,
+    /// Whether the deductions made by every step are reported to the observer.
+    traced: bool,
+    /// The number of bytes currently used by `backtrackers`, not counting the board they share.
+    memory: usize,
+    /// A solution found before the search even started, when the header alone is enough to
+    /// determine the whole board.
+    pending: Option<Board>,
+    /// Statistics about the search so far.
+    stats: Stats,
+}
+
+impl<'a> Search<'a> {
+    /// Creates a new [`Search`] over the solutions of `header` that agree with `givens`.
+    ///
+    /// See [`BoardSet::account_for_givens`].
+    pub fn with_givens(header: &'a Header, givens: &[u8], limits: Limits) -> Self {
+        let mut buf = Vec::new();
+        match root(header, givens, &mut buf) {
+            Some(set) => Self::from_set(header, set, limits),
+            None => Self::empty(header, limits),
+        }
+    }
+
+    /// Creates a new [`Search`] over the solutions of `header` that agree with `givens`, trying the
+    /// values of every cell in an order chosen by `rng`.
+    pub fn shuffled(
+        header: &'a Header,
+        givens: &[u8],
+        limits: Limits,
+        rng: &'a mut dyn RngCore,
+    ) -> Self {
+        let mut search = Self::with_givens(header, givens, limits);
+        if let Some(root) = search.backtrackers.last_mut() {
+            root.shuffle(rng);
+        }
+        search.rng = Some(rng);
+        search
+    }
+
+    /// Creates a new [`Search`] over the solutions of `header` that agree with `givens`, reporting
+    /// every deduction it makes to the observer of its steps.
+    ///
+    /// The deductions made before the first step are reported to `observer`.
+    pub fn traced(
+        header: &'a Header,
+        givens: &[u8],
+        limits: Limits,
+        observer: &mut dyn SolveObserver,
+    ) -> Self {
+        let mut buf = Vec::new();
+        let mut search = match root_traced(header, givens, &mut buf, Some(observer)) {
+            Some(set) => Self::from_set(header, set, limits),
+            None => Self::empty(header, limits),
+        };
+        search.traced = true;
+        search
+    }
+
+    /// Creates a [`Search`] that has nothing to explore.
+    fn empty(header: &'a Header, limits: Limits) -> Self {
+        Self {
+            header,
+            size: header.size(),
+            limits,
+            buf: Vec::new(),
+            set: BoardSet::new(header.size()),
+            backtrackers: Vec::new(),
+            rng: None,
+            traced: false,
+            memory: 0,
+            pending: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Creates a new [`Search`] over the boards that `set` allows.
+    ///
+    /// `set` must already account for the header, as done by [`root`].
+    fn from_set(header: &'a Header, mut set: BoardSet, limits: Limits) -> Self {
+        let mut search = Self::empty(header, limits);
+        search.stats.deduced = set.fixed();
+
+        // The deductions made before the search are never undone.
+        set.trail.clear();
+
+        match BacktrackingBoard::new(&set, limits.heuristic) {
+            Some(ok) => {
+                search.memory += ok.memory();
+                search.backtrackers.push(ok);
+                search.stats.max_depth = 1;
+            }
+            None => {
+                let board = set.create_board();
+                if satisfies_header(&board, header) {
+                    search.pending = Some(board);
+                }
+            }
+        }
+        search.set = set;
+
+        search
+    }
+
+    /// Returns the board currently being explored, if any.
+    pub fn current(&self) -> Option<&BoardSet> {
+        self.backtrackers.last().map(|_| &self.set)
+    }
+
+    /// Returns the cell that the next step will set, and the value it will try, as
+    /// `(x, y, value)`.
+    pub fn branch(&self) -> Option<(usize, usize, u8)> {
+        let backtracker = self.backtrackers.last()?;
+        let index = backtracker.current_index;
+        let value = backtracker.value(backtracker.current_subindex)?;
+
+        Some((index % self.size, index / self.size, value))
+    }
+
+    /// Returns the path of the solution that the last step found: the index of the value tried on
+    /// every cell the search is backtracking on.
+    ///
+    /// Solutions are found in the lexicographic order of their paths.
+    #[cfg(feature = "std")]
+    fn solution_path(&self) -> Vec<usize> {
+        self.backtrackers
+            .iter()
+            .map(|b| b.current_subindex - 1)
+            .collect()
+    }
+
+    /// Returns the path of the next node that the search will explore.
+    ///
+    /// Every node explored from now on has a path that is larger or equal, in the lexicographic
+    /// order.
+    #[cfg(feature = "std")]
+    fn position(&self) -> Vec<usize> {
+        let mut path = Vec::with_capacity(self.backtrackers.len());
+        if let Some((last, rest)) = self.backtrackers.split_last() {
+            path.extend(rest.iter().map(|b| b.current_subindex - 1));
+            path.push(last.current_subindex);
+        }
+        path
+    }
+
+    /// Creates a [`Search`] over the solutions of `header` that agree with `givens`, going on from
+    /// `checkpoint`.
+    ///
+    /// `None` is returned when the path of the checkpoint is not a node of the search tree.
+    #[cfg(feature = "std")]
+    fn resume(
+        header: &'a Header,
+        givens: &[u8],
+        limits: Limits,
+        checkpoint: &Checkpoint,
+    ) -> Option<Self> {
+        let mut search = Self::with_givens(header, givens, limits);
+        search.stats = Stats {
+            deduced: search.stats.deduced,
+            ..checkpoint.stats
+        };
+
+        let Some((&last, rest)) = checkpoint.path.split_last() else {
+            return Some(search);
+        };
+        search.pending = None;
+
+        // Every cell but the last one has been set to the value of the path, and the search is
+        // exploring the board it produced.
+        for &subindex in rest {
+            let backtracker = search.backtrackers.last_mut()?;
+            if subindex >= backtracker.end_subindex {
+                return None;
+            }
+            backtracker.current_subindex = subindex;
+            backtracker
+                .try_backtrack(&mut search.set, header, &mut search.buf, None)
+                .ok()?;
+            let child = BacktrackingBoard::fork_from(backtracker, &search.set)?;
+            search.memory += child.memory();
+            search.backtrackers.push(child);
+        }
+
+        let backtracker = search.backtrackers.last_mut()?;
+        if last > backtracker.end_subindex {
+            return None;
+        }
+        backtracker.current_subindex = last;
+
+        Some(search)
+    }
+
+    /// Removes the values that remain to be tried on the lowest cell the search is backtracking
+    /// on, and returns the boards they produce along with their paths.
+    ///
+    /// The search then only explores the subtree it is currently in, and the returned boards
+    /// can be explored by other searches, see [`Search::from_set`].
+    #[cfg(feature = "std")]
+    fn split(&mut self) -> Vec<(Vec<usize>, BoardSet)> {
+        let level = match self
+            .backtrackers
+            .iter()
+            .position(|b| b.current_subindex < b.end_subindex)
+        {
+            Some(level) => level,
+            None => return Vec::new(),
+        };
+
+        let prefix: Vec<usize> = self.backtrackers[..level]
+            .iter()
+            .map(|b| b.current_subindex - 1)
+            .collect();
+
+        let backtracker = &mut self.backtrackers[level];
+        let mut base = self.set.clone();
+        base.rewind(backtracker.mark);
+
+        let mut forks = Vec::new();
+        for subindex in backtracker.current_subindex..backtracker.end_subindex {
+            let mut fork = backtracker.clone();
+            let mut set = base.clone();
+            fork.current_subindex = subindex;
+            if fork
+                .try_backtrack(&mut set, self.header, &mut self.buf, None)
+                .is_ok()
+            {
+                let mut path = prefix.clone();
+                path.push(subindex);
+                forks.push((path, set));
+            }
+        }
+        backtracker.end_subindex = backtracker.current_subindex;
+
+        forks
+    }
+
+    /// Advances the search by a single step.
+    pub fn step(&mut self) -> Result<Step, SolutionError> {
+        self.step_observed(&mut ())
+    }
+
+    /// Advances the search by a single step, reporting what happens to `observer`.
+    pub fn step_observed(
+        &mut self,
+        observer: &mut dyn SolveObserver,
+    ) -> Result<Step, SolutionError> {
+        if let Some(board) = self.pending.take() {
+            observer.on_solution(&board);
+            return Ok(Step::Solution(board));
+        }
+
+        let branch = self.branch();
+        let depth = self.backtrackers.len();
+        let backtracker = match self.backtrackers.last_mut() {
+            Some(backtracker) => backtracker,
+            None => return Ok(Step::Done),
+        };
 
-    loop {
         if sigint::occured() {
             return Err(SolutionError::Interrupted);
         }
 
-        let backtracker = backtrackers.last_mut().unwrap();
-        match backtracker.try_backtrack(&mut buf) {
-            // TODO:
-            //  calling `new` here re-computes `current_index` from the start. We should create a
-            //  special `new_backtracking_fork` function that keeps the index (or something like
-            //  that).
-            Ok(()) => match BacktrackingBoard::new(backtracker.set.clone()) {
-                Ok(ok) => backtrackers.push(ok),
-                Err(complete) => return Ok(complete.create_board()),
-            },
+        if self
+            .limits
+            .max_nodes
+            .is_some_and(|max| self.stats.steps >= max)
+        {
+            return Err(SolutionError::NodeLimit);
+        }
+
+        #[cfg(feature = "std")]
+        if self
+            .limits
+            .deadline
+            .is_some_and(|deadline| std::time::Instant::now() >= deadline)
+        {
+            return Err(SolutionError::Timeout);
+        }
+
+        self.stats.steps += 1;
+
+        if let Some((x, y, value)) = branch {
+            observer.on_assign(x, y, value);
+        }
+
+        let traced = self.traced.then_some(&mut *observer);
+        match backtracker.try_backtrack(&mut self.set, self.header, &mut self.buf, traced) {
+            Ok(()) => {
+                observer.on_propagate(&Frame {
+                    set: &self.set,
+                    step: self.stats.steps,
+                    depth,
+                    branch,
+                });
+
+                match BacktrackingBoard::fork_from(backtracker, &self.set) {
+                    Some(mut ok) => {
+                        if let Some(rng) = &mut self.rng {
+                            ok.shuffle(*rng);
+                        }
+                        self.memory += ok.memory();
+                        let memory = self.memory + self.set.memory();
+                        if self.limits.max_memory.is_some_and(|max| memory > max) {
+                            // The value will be tried again if the search goes on from here.
+                            self.memory -= ok.memory();
+                            backtracker.current_subindex -= 1;
+                            return Err(SolutionError::MemoryLimit);
+                        }
+                        self.backtrackers.push(ok);
+                        self.stats.max_depth = self.stats.max_depth.max(self.backtrackers.len());
+                    }
+                    None => {
+                        let board = self.set.create_board();
+                        if satisfies_header(&board, self.header) {
+                            observer.on_solution(&board);
+                            return Ok(Step::Solution(board));
+                        }
+                    }
+                }
+            }
             Err(BacktrackError::NoSolution) => {
-                backtrackers.pop();
-                if backtrackers.is_empty() {
-                    return Err(SolutionError::NoSolution);
+                if let Some(popped) = self.backtrackers.pop() {
+                    self.memory -= popped.memory();
+                    self.stats.backtracks += 1;
+                    observer.on_backtrack(self.backtrackers.len());
                 }
             }
             Err(BacktrackError::Retry) => (),
         }
+
+        Ok(Step::Continue)
     }
 }
 
-/// Solves the provided header, but animates the process.
-pub fn solve_animated(
-    header: &[u8],
-    size: usize,
-    w: &mut dyn WriteColor,
-    interval: Duration,
-) -> Result<Box<[u8]>, SolutionError> {
-    let mut buf = Vec::new();
-    let mut set = BoardSet::new(size);
-    set.account_for_header(header, &mut buf)?;
-    set.remove_duplicates_in(&mut buf)?;
+/// Solves the provided header.
+pub fn solve(header: &Header, limits: Limits) -> Result<Board, SolutionError> {
+    solve_with_stats(header, limits, &mut Stats::default())
+}
+
+/// Solves the provided header, writing statistics about the search to `stats`.
+///
+/// `stats` is written even when no solution is found.
+pub fn solve_with_stats(
+    header: &Header,
+    limits: Limits,
+    stats: &mut Stats,
+) -> Result<Board, SolutionError> {
+    solve_with(header, &[], limits, stats)
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, and writing statistics about
+/// the search to `stats`.
+///
+/// `givens` is either empty, or holds one value per cell of the board. Cells whose given value is
+/// `0` are free. `stats` is written even when no solution is found.
+pub fn solve_with(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    stats: &mut Stats,
+) -> Result<Board, SolutionError> {
+    let mut search = Search::with_givens(header, givens, limits);
+
+    let result = loop {
+        match search.step() {
+            Ok(Step::Continue) => (),
+            Ok(Step::Solution(board)) => break Ok(board),
+            Ok(Step::Done) => break Err(SolutionError::NoSolution),
+            Err(err) => break Err(err),
+        }
+    };
+
+    *stats = search.stats;
+    result
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, but tries the values of every
+/// cell in an order chosen by `rng` rather than in increasing order.
+///
+/// Headers with multiple solutions may produce any of them, depending on `rng`. `stats` is written
+/// even when no solution is found.
+pub fn solve_shuffled(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    rng: &mut dyn RngCore,
+    stats: &mut Stats,
+) -> Result<Board, SolutionError> {
+    let mut search = Search::shuffled(header, givens, limits, rng);
+
+    let result = loop {
+        match search.step() {
+            Ok(Step::Continue) => (),
+            Ok(Step::Solution(board)) => break Ok(board),
+            Ok(Step::Done) => break Err(SolutionError::NoSolution),
+            Err(err) => break Err(err),
+        }
+    };
+
+    *stats = search.stats;
+    result
+}
+
+/// The point a search has reached, from which it can go on with [`solve_checkpointed`].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg(feature = "std")]
+pub struct Checkpoint {
+    /// The path of the next node that the search explores: the index of the value tried on every
+    /// cell the search is backtracking on. The last index is the next value to try on the last
+    /// cell.
+    ///
+    /// An empty path starts the search from the beginning.
+    pub path: Vec<usize>,
+    /// The statistics of the search up to that point.
+    pub stats: Stats,
+}
+
+#[cfg(feature = "std")]
+impl Checkpoint {
+    /// Returns whether the search over the solutions of `header` that agree with `givens` can go
+    /// on from this checkpoint.
+    ///
+    /// A checkpoint only fits the search that wrote it, and only as long as the solver takes the
+    /// same decisions.
+    pub fn fits(&self, header: &Header, givens: &[u8]) -> bool {
+        Search::resume(header, givens, Limits::default(), self).is_some()
+    }
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, and going on from `from`.
+///
+/// Every `interval`, and when the search is stopped by an error, `save` is called with the point
+/// the search has reached, such that it can be resumed later. When `from` does not fit the header
+/// (see [`Checkpoint::fits`]), the search starts from the beginning.
+///
+/// `stats` is written even when no solution is found. It includes the statistics of `from`.
+#[cfg(feature = "std")]
+pub fn solve_checkpointed(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    from: &Checkpoint,
+    interval: Option<Duration>,
+    stats: &mut Stats,
+    save: &mut dyn FnMut(&Checkpoint),
+) -> Result<Board, SolutionError> {
+    /// The number of steps between two reads of the clock.
+    const CLOCK_STEPS: usize = 1024;
+
+    let mut search = Search::resume(header, givens, limits, from)
+        .unwrap_or_else(|| Search::with_givens(header, givens, limits));
+    let mut next = interval.map(|interval| std::time::Instant::now() + interval);
+    let checkpoint = |search: &Search| Checkpoint {
+        path: search.position(),
+        stats: search.stats,
+    };
+
+    let result = loop {
+        match search.step() {
+            Ok(Step::Continue) => (),
+            Ok(Step::Solution(board)) => break Ok(board),
+            Ok(Step::Done) => break Err(SolutionError::NoSolution),
+            Err(err) => {
+                save(&checkpoint(&search));
+                break Err(err);
+            }
+        }
+
+        if let (Some(interval), Some(at)) = (interval, next) {
+            if search.stats.steps.is_multiple_of(CLOCK_STEPS) && std::time::Instant::now() >= at {
+                save(&checkpoint(&search));
+                next = Some(std::time::Instant::now() + interval);
+            }
+        }
+    };
+
+    *stats = search.stats;
+    result
+}
+
+/// Returns the cells of the board of `header` that can be deduced without guessing, keeping the
+/// cells given by `givens`. The other cells are `0`.
+///
+/// This is the board the search starts from. When the clues and the givens contradict each
+/// other, [`SolutionError::NoSolution`] is returned.
+pub fn deduce(header: &Header, givens: &[u8]) -> Result<Board, SolutionError> {
+    match root(header, givens, &mut Vec::new()) {
+        Some(set) => Ok(set.create_board()),
+        None => Err(SolutionError::NoSolution),
+    }
+}
+
+/// A subtree of the search, explored by a worker of [`solve_parallel`].
+#[cfg(feature = "std")]
+struct Task {
+    /// The path of the root of the subtree, as returned by [`Search::position`].
+    path: Vec<usize>,
+    /// The cells allowed at the root of the subtree.
+    set: BoardSet,
+}
+
+/// The state shared by the workers of [`solve_parallel`].
+#[cfg(feature = "std")]
+struct Shared<'a> {
+    /// The header being solved.
+    header: &'a Header,
+    /// The limits of every worker.
+    limits: Limits,
+    /// The tasks of every worker. Workers take their own tasks from the front, and steal the
+    /// tasks of the others from the back.
+    queues: Vec<std::sync::Mutex<std::collections::VecDeque<Task>>>,
+    /// The number of tasks that are queued or being explored. The search is over when it
+    /// reaches zero.
+    pending: std::sync::atomic::AtomicUsize,
+    /// The number of workers waiting for a task.
+    idle: std::sync::atomic::AtomicUsize,
+    /// The solution with the smallest path found so far, along with that path.
+    best: std::sync::Mutex<Option<(Vec<usize>, Board)>>,
+    /// The error that stopped the search.
+    error: std::sync::Mutex<Option<SolutionError>>,
+    /// The statistics of every worker, added together.
+    stats: std::sync::Mutex<Stats>,
+}
+
+#[cfg(feature = "std")]
+impl Shared<'_> {
+    /// Returns whether a subtree whose nodes all have a path larger or equal to `path` can be
+    /// skipped, because a solution that comes before it has already been found.
+    fn is_pruned(&self, path: &[usize]) -> bool {
+        match &*self.best.lock().unwrap() {
+            Some((best, _)) => best.as_slice() < path,
+            None => false,
+        }
+    }
+
+    /// Returns whether the search has been stopped by an error.
+    fn is_stopped(&self) -> bool {
+        self.error.lock().unwrap().is_some()
+    }
+
+    /// Stops the search because of `err`, unless another error stopped it already.
+    fn stop(&self, err: SolutionError) {
+        self.error.lock().unwrap().get_or_insert(err);
+    }
+
+    /// Takes a task from the queue of the worker `id`, or steals one from another worker.
+    fn take(&self, id: usize) -> Option<Task> {
+        if let Some(task) = self.queues[id].lock().unwrap().pop_front() {
+            return Some(task);
+        }
+
+        let others = self.queues.iter().cycle().skip(id + 1);
+        others
+            .take(self.queues.len() - 1)
+            .find_map(|queue| queue.lock().unwrap().pop_back())
+    }
 
-    let _ = crate::format::print_solution(
-        w,
-        &set.create_board(),
+    /// Explores the subtree of `task` on the worker `id`.
+    fn explore(&self, id: usize, task: Task) {
+        use std::sync::atomic::Ordering;
+
+        /// The number of steps between two checks of the shared state.
+        const INTERVAL: usize = 256;
+
+        if self.is_pruned(&task.path) {
+            return;
+        }
+
+        let mut search = Search::from_set(self.header, task.set, self.limits);
+        let mut path = task.path;
+        let prefix = path.len();
+        let mut since_check = 0;
+
+        loop {
+            match search.step() {
+                Ok(Step::Continue) => (),
+                Ok(Step::Solution(board)) => {
+                    // Later solutions of the subtree come after this one.
+                    path.extend(search.solution_path());
+                    let mut best = self.best.lock().unwrap();
+                    if best.as_ref().is_none_or(|(best, _)| path < *best) {
+                        *best = Some((path, board));
+                    }
+                    break;
+                }
+                Ok(Step::Done) => break,
+                Err(err) => {
+                    self.stop(err);
+                    break;
+                }
+            }
+
+            since_check += 1;
+            if since_check < INTERVAL {
+                continue;
+            }
+            since_check = 0;
+
+            if self.is_stopped() {
+                break;
+            }
+
+            path.truncate(prefix);
+            path.extend(search.position());
+            if self.is_pruned(&path) {
+                break;
+            }
+
+            // Waiting workers are given the subtrees this one has not explored yet.
+            if self.idle.load(Ordering::Relaxed) != 0 {
+                let forks = search.split();
+                self.pending.fetch_add(forks.len(), Ordering::SeqCst);
+                let mut queue = self.queues[id].lock().unwrap();
+                for (fork, set) in forks {
+                    let mut fork_path = path[..prefix].to_vec();
+                    fork_path.extend(fork);
+                    queue.push_back(Task {
+                        path: fork_path,
+                        set,
+                    });
+                }
+            }
+        }
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.steps += search.stats.steps;
+        stats.backtracks += search.stats.backtracks;
+        stats.max_depth = stats.max_depth.max(prefix + search.stats.max_depth);
+    }
+
+    /// Runs the worker `id` until the search is over.
+    fn work(&self, id: usize) {
+        use std::sync::atomic::Ordering;
+
+        let mut waiting = false;
+        while self.pending.load(Ordering::SeqCst) != 0 && !self.is_stopped() {
+            match self.take(id) {
+                Some(task) => {
+                    if waiting {
+                        self.idle.fetch_sub(1, Ordering::Relaxed);
+                        waiting = false;
+                    }
+                    self.explore(id, task);
+                    self.pending.fetch_sub(1, Ordering::SeqCst);
+                }
+                None => {
+                    if !waiting {
+                        self.idle.fetch_add(1, Ordering::Relaxed);
+                        waiting = true;
+                    }
+                    std::thread::sleep(Duration::from_micros(50));
+                }
+            }
+        }
+    }
+}
+
+/// Solves the provided header using `threads` threads, keeping the cells given by `givens`, and
+/// writing statistics about the search to `stats`.
+///
+/// The backtracking tree is split between the threads, which take the subtrees left unexplored by
+/// the others when they run out of work. The returned solution is the one [`solve`] would return:
+/// the search only stops once the subtrees that come before the first solution found have been
+/// explored. The memory budget of `limits` is divided between the threads.
+///
+/// A search given a node budget runs on a single thread, such that its result does not depend on
+/// the machine.
+#[cfg(feature = "std")]
+pub fn solve_parallel(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    threads: usize,
+    stats: &mut Stats,
+) -> Result<Board, SolutionError> {
+    if threads <= 1 || limits.max_nodes.is_some() {
+        return solve_with(header, givens, limits, stats);
+    }
+
+    let set = match root(header, givens, &mut Vec::new()) {
+        Some(set) => set,
+        None => return Err(SolutionError::NoSolution),
+    };
+    let deduced = set.fixed();
+
+    let shared = Shared {
         header,
-        size as u8,
-        &crate::args::OutputFormat::Both,
-    );
+        limits: Limits {
+            max_memory: limits.max_memory.map(|max| max / threads),
+            ..limits
+        },
+        queues: (0..threads).map(|_| Default::default()).collect(),
+        pending: 1.into(),
+        idle: 0.into(),
+        best: Default::default(),
+        error: Default::default(),
+        stats: Default::default(),
+    };
+    shared.queues[0].lock().unwrap().push_back(Task {
+        path: Vec::new(),
+        set,
+    });
+
+    std::thread::scope(|scope| {
+        for id in 0..threads {
+            let shared = &shared;
+            scope.spawn(move || shared.work(id));
+        }
+    });
+
+    *stats = Stats {
+        deduced,
+        ..shared.stats.into_inner().unwrap()
+    };
+    if let Some(err) = shared.error.into_inner().unwrap() {
+        return Err(err);
+    }
+    match shared.best.into_inner().unwrap() {
+        Some((_, board)) => Ok(board),
+        None => Err(SolutionError::NoSolution),
+    }
+}
+
+/// Counts the solutions of the provided header.
+///
+/// The search stops as soon as `limit` solutions have been found, meaning that the returned count
+/// is never larger than `limit`.
+pub fn count_solutions(
+    header: &Header,
+    limit: usize,
+    limits: Limits,
+) -> Result<usize, SolutionError> {
+    count_solutions_with(header, &[], limit, limits)
+}
+
+/// Counts the solutions of the provided header that agree with `givens`.
+///
+/// See [`count_solutions`] and [`for_each_solution_with`].
+pub fn count_solutions_with(
+    header: &Header,
+    givens: &[u8],
+    limit: usize,
+    limits: Limits,
+) -> Result<usize, SolutionError> {
+    let mut count = 0;
+
+    if limit != 0 {
+        for_each_solution_with(header, givens, limits, &mut |_| {
+            count += 1;
+            count < limit
+        })?;
+    }
+
+    Ok(count)
+}
+
+/// An iterator over the solutions of a header, created by [`solutions`].
+///
+/// Every call to [`Iterator::next`] resumes the search where the previous solution was found.
+pub struct Solutions<'a> {
+    /// The search being resumed.
+    search: Search<'a>,
+    /// The error that ended the search, if any.
+    error: Option<SolutionError>,
+}
+
+impl Solutions<'_> {
+    /// Returns the error that ended the iteration, if the search did not run to completion.
+    ///
+    /// When this is `None` once the iterator is exhausted, every solution has been yielded.
+    pub fn error(&self) -> Option<SolutionError> {
+        self.error
+    }
+
+    /// Returns statistics about the search so far.
+    pub fn stats(&self) -> Stats {
+        self.search.stats
+    }
+}
+
+impl Iterator for Solutions<'_> {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        loop {
+            match self.search.step() {
+                Ok(Step::Continue) => (),
+                Ok(Step::Solution(board)) => return Some(board),
+                Ok(Step::Done) => return None,
+                Err(err) => {
+                    self.error = Some(err);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Solutions<'_> {}
+
+/// Lazily enumerates the solutions of the provided header.
+///
+/// The search only runs when the next solution is requested, so taking the first two solutions
+/// of a header is enough to know whether it is unique. The iteration ends early when the search
+/// is interrupted or exceeds `limits`, see [`Solutions::error`].
+pub fn solutions(header: &Header, limits: Limits) -> Solutions<'_> {
+    solutions_with(header, &[], limits)
+}
+
+/// Lazily enumerates the solutions of the provided header that agree with `givens`.
+///
+/// See [`solutions`] and [`for_each_solution_with`].
+pub fn solutions_with<'a>(header: &'a Header, givens: &[u8], limits: Limits) -> Solutions<'a> {
+    Solutions {
+        search: Search::with_givens(header, givens, limits),
+        error: None,
+    }
+}
+
+/// Calls `on_solution` with every solution of the provided header, until it returns `false`.
+pub fn for_each_solution(
+    header: &Header,
+    limits: Limits,
+    on_solution: &mut dyn FnMut(&Board) -> bool,
+) -> Result<(), SolutionError> {
+    for_each_solution_with(header, &[], limits, on_solution)
+}
+
+/// Calls `on_solution` with every solution of the provided header that agrees with `givens`,
+/// until it returns `false`.
+///
+/// `givens` is either empty, or holds one value per cell of the board. Cells whose given value is
+/// `0` are free.
+pub fn for_each_solution_with(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    on_solution: &mut dyn FnMut(&Board) -> bool,
+) -> Result<(), SolutionError> {
+    let mut solutions = solutions_with(header, givens, limits);
+
+    for board in &mut solutions {
+        if !on_solution(&board) {
+            return Ok(());
+        }
+    }
+
+    match solutions.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// An estimation of the size of the search tree explored when solving a header.
+#[cfg(feature = "std")]
+pub struct Estimate {
+    /// The estimated number of nodes of the search tree.
+    pub nodes: f64,
+    /// The half-width of the 95% confidence interval around `nodes`.
+    pub nodes_margin: f64,
+    /// The average time spent on a single node during the estimation.
+    pub time_per_node: Duration,
+}
+
+/// The totals accumulated over the random paths taken by [`probe`].
+struct Probes {
+    /// The sum of the estimates of every path.
+    sum: f64,
+    /// The sum of the squares of the estimates of every path.
+    sum_of_squares: f64,
+    /// The number of nodes visited along every path.
+    visited: usize,
+}
+
+/// Takes `probes` random paths from the root of the search tree of `header` to one of its leaves.
+///
+/// `None` is returned when the header alone is enough to determine the board (or to prove that
+/// there is no solution).
+fn probe(
+    header: &Header,
+    rng: &mut dyn RngCore,
+    probes: usize,
+) -> Result<Option<Probes>, SolutionError> {
+    let mut buf = Vec::new();
+    let mut set = BoardSet::new(header.size());
+    let root = if set.account_for_header(header, &mut buf).is_err()
+        || set.propagate(header, &mut buf).is_err()
+    {
+        None
+    } else {
+        BacktrackingBoard::new(&set, Heuristic::default())
+    };
 
-    let mut backtrackers = Vec::new();
+    let root = match root {
+        Some(root) => root,
+        None => return Ok(None),
+    };
 
-    match BacktrackingBoard::new(set) {
-        Ok(ok) => backtrackers.push(ok),
-        Err(complete) => return Ok(complete.create_board()),
+    let mut totals = Probes {
+        sum: 0.0,
+        sum_of_squares: 0.0,
+        visited: 0,
     };
 
-    loop {
+    for _ in 0..probes {
         if sigint::occured() {
             return Err(SolutionError::Interrupted);
         }
 
-        let backtracker = backtrackers.last_mut().unwrap();
+        let mut node = root.clone();
+        let mut weight = 1.0;
+        // The root of the search tree.
+        let mut estimate = 1.0;
+
+        loop {
+            // Every candidate of the current node is a child in the search tree.
+            let candidates = node.candidates();
+            weight *= candidates as f64;
+            estimate += weight;
+            totals.visited += 1;
+
+            node.current_subindex = rng.gen_range(0..candidates);
+            match node.try_backtrack(&mut set, header, &mut buf, None) {
+                Ok(()) => match BacktrackingBoard::fork_from(&node, &set) {
+                    Some(child) => node = child,
+                    None => break,
+                },
+                Err(_) => break,
+            }
+        }
 
-        print!("\x1B[{}A\x1B[J", size + 2);
-        let _ = crate::format::print_solution(
-            w,
-            &backtracker.set.create_board(),
-            header,
-            size as u8,
-            &crate::args::OutputFormat::Both,
-        );
-        std::thread::sleep(interval);
+        totals.sum += estimate;
+        totals.sum_of_squares += estimate * estimate;
+    }
 
-        match backtracker.try_backtrack(&mut buf) {
-            // TODO:
-            //  calling `new` here re-computes `current_index` from the start. We should create a
-            //  special `new_backtracking_fork` function that keeps the index (or something like
-            //  that).
-            Ok(()) => match BacktrackingBoard::new(backtracker.set.clone()) {
-                Ok(ok) => backtrackers.push(ok),
-                Err(complete) => {
-                    print!("\x1B[{}A\x1B[J", size + 2);
-                    return Ok(complete.create_board());
-                }
-            },
-            Err(BacktrackError::NoSolution) => {
-                backtrackers.pop();
-                if backtrackers.is_empty() {
-                    return Err(SolutionError::NoSolution);
+    Ok(Some(totals))
+}
+
+/// Estimates the number of nodes that the backtracking search explores in the worst case when
+/// solving `header`.
+///
+/// This is the `nodes` field of [`estimate`], without the measures that need the standard library.
+pub fn estimate_nodes(
+    header: &Header,
+    rng: &mut dyn RngCore,
+    probes: usize,
+) -> Result<f64, SolutionError> {
+    match probe(header, rng, probes)? {
+        Some(totals) => Ok(totals.sum / probes.max(1) as f64),
+        None => Ok(1.0),
+    }
+}
+
+/// Estimates the number of nodes that the backtracking search explores in the worst case when
+/// solving `header`.
+///
+/// This uses Knuth's estimator: `probes` random paths are taken from the root of the search tree
+/// to one of its leaves, and the number of nodes is extrapolated from the number of children of
+/// each node along those paths.
+#[cfg(feature = "std")]
+pub fn estimate(
+    header: &Header,
+    rng: &mut dyn RngCore,
+    probes: usize,
+) -> Result<Estimate, SolutionError> {
+    let start = std::time::Instant::now();
+
+    // The header alone is enough to determine the board (or to prove that there is no solution).
+    let totals = match probe(header, rng, probes)? {
+        Some(totals) => totals,
+        None => {
+            return Ok(Estimate {
+                nodes: 1.0,
+                nodes_margin: 0.0,
+                time_per_node: Duration::ZERO,
+            })
+        }
+    };
+
+    let n = probes.max(1) as f64;
+    let mean = totals.sum / n;
+    let variance = (totals.sum_of_squares / n - mean * mean).max(0.0);
+
+    Ok(Estimate {
+        nodes: mean,
+        nodes_margin: 1.96 * (variance / n).sqrt(),
+        time_per_node: start.elapsed().div_f64(totals.visited.max(1) as f64),
+    })
+}
+
+/// A snapshot of a search, taken between two of its steps.
+pub struct Frame<'a> {
+    /// The board currently being explored.
+    set: &'a BoardSet,
+    /// The number of steps taken so far.
+    pub step: usize,
+    /// The number of cells on which the search is currently backtracking.
+    pub depth: usize,
+    /// The cell that the next step will set, and the value it will try, as `(x, y, value)`.
+    pub branch: Option<(usize, usize, u8)>,
+}
+
+impl Frame<'_> {
+    /// Returns the values that are still allowed for the cell at `(x, y)`, in increasing order.
+    pub fn candidates(&self, x: usize, y: usize) -> Candidates {
+        assert!(x < self.set.size && y < self.set.size);
+        self.set.cell(x, y).values()
+    }
+
+    /// Returns the board currently being explored. Cells that are not fixed are set to `0`.
+    pub fn board(&self) -> Board {
+        self.set.create_board()
+    }
+}
+
+/// What a deduction of the solver follows from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cause {
+    /// A clue, as its index in [`Header::clues`].
+    Clue(usize),
+    /// The two clues at both ends of a line, as their indices in [`Header::clues`].
+    CluePair(usize, usize),
+    /// The cells provided in advance.
+    Given,
+    /// The cell at `(x, y)`, whose value can't appear again in its row and column.
+    Fixed { x: usize, y: usize },
+    /// The value tried by the search on a cell.
+    Guess,
+}
+
+/// Candidates removed from a cell by the propagation of the constraints.
+#[derive(Debug, Clone, Copy)]
+pub struct Deduction<'a> {
+    /// What the deduction follows from.
+    pub cause: Cause,
+    /// The column of the cell.
+    pub x: usize,
+    /// The row of the cell.
+    pub y: usize,
+    /// The values that have been removed, in increasing order.
+    pub removed: &'a [u8],
+    /// The values that are still allowed, in increasing order.
+    pub left: &'a [u8],
+}
+
+/// Watches a search as it runs, for front-ends that need to show or record its progress.
+///
+/// Every method does nothing by default.
+pub trait SolveObserver {
+    /// Called before every step of the search, with a snapshot of the board being explored.
+    fn on_frame(&mut self, _frame: &Frame) {}
+
+    /// Called when the search fixes the cell at `(x, y)` to `value`, trying a new branch.
+    fn on_assign(&mut self, _x: usize, _y: usize, _value: u8) {}
+
+    /// Called once the value fixed by the last [`SolveObserver::on_assign`] has been removed from
+    /// the other cells of its row and column.
+    ///
+    /// When this leads to a contradiction, this method is not called, and the next value is tried
+    /// instead.
+    fn on_propagate(&mut self, _frame: &Frame) {}
+
+    /// Called when every value of a cell has been tried, and the search goes back to the previous
+    /// one. `depth` is the number of cells on which the search is still backtracking.
+    fn on_backtrack(&mut self, _depth: usize) {}
+
+    /// Called when a solution is found.
+    fn on_solution(&mut self, _board: &Board) {}
+
+    /// Called when the propagation of the constraints removes candidates from a cell.
+    ///
+    /// Only traced searches report their deductions, see [`solve_traced`].
+    fn on_deduction(&mut self, _deduction: &Deduction) {}
+
+    /// Called when the propagation of the constraints finds that the board explored has no
+    /// solution, because of `cause`.
+    ///
+    /// Only traced searches report their contradictions, see [`solve_traced`].
+    fn on_contradiction(&mut self, _cause: Cause) {}
+}
+
+/// Observes nothing.
+impl SolveObserver for () {}
+
+/// Calls the wrapped function on every [`SolveObserver::on_frame`].
+struct OnFrame<F>(F);
+
+impl<F: FnMut(&Frame)> SolveObserver for OnFrame<F> {
+    fn on_frame(&mut self, frame: &Frame) {
+        (self.0)(frame);
+    }
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, and calling `on_frame` before
+/// every step of the search.
+///
+/// Statistics about the search are written to `stats`, even when no solution is found.
+pub fn solve_observed(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    stats: &mut Stats,
+    on_frame: &mut dyn FnMut(&Frame),
+) -> Result<Board, SolutionError> {
+    solve_with_observer(header, givens, limits, stats, &mut OnFrame(on_frame))
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, and reporting every event of
+/// the search to `observer`.
+///
+/// Statistics about the search are written to `stats`, even when no solution is found.
+pub fn solve_with_observer(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    stats: &mut Stats,
+    observer: &mut dyn SolveObserver,
+) -> Result<Board, SolutionError> {
+    let search = Search::with_givens(header, givens, limits);
+    run_observed(search, stats, observer)
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, and reporting every event of
+/// the search to `observer`, along with every deduction made by the propagation of the
+/// constraints.
+///
+/// Statistics about the search are written to `stats`, even when no solution is found.
+pub fn solve_traced(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    stats: &mut Stats,
+    observer: &mut dyn SolveObserver,
+) -> Result<Board, SolutionError> {
+    let search = Search::traced(header, givens, limits, observer);
+    run_observed(search, stats, observer)
+}
+
+/// Runs `search` to its first solution, reporting every event to `observer`.
+fn run_observed(
+    mut search: Search,
+    stats: &mut Stats,
+    observer: &mut dyn SolveObserver,
+) -> Result<Board, SolutionError> {
+    let mut step = 0;
+
+    let result = loop {
+        if let Some(set) = search.current() {
+            observer.on_frame(&Frame {
+                set,
+                step,
+                depth: search.backtrackers.len(),
+                branch: search.branch(),
+            });
+        }
+
+        step += 1;
+        match search.step_observed(observer) {
+            Ok(Step::Continue) => (),
+            Ok(Step::Solution(board)) => break Ok(board),
+            Ok(Step::Done) => break Err(SolutionError::NoSolution),
+            Err(err) => break Err(err),
+        }
+    };
+
+    *stats = search.stats;
+    result
+}
+
+/// Solves the provided header, keeping the cells given by `givens`, but animates the process.
+///
+/// Statistics about the search are written to `stats`, even when no solution is found.
+#[cfg(feature = "cli")]
+pub fn solve_animated(
+    header: &Header,
+    givens: &[u8],
+    limits: Limits,
+    stats: &mut Stats,
+    w: &mut dyn WriteColor,
+    interval: Duration,
+) -> Result<Board, SolutionError> {
+    let size = header.size();
+    // The clues and the cells share the same columns, see `format::print_both`.
+    let width = crate::alphabet::log10(size as u8);
+    // The frame currently on screen, with the cursor on the line right below it.
+    let mut previous: Option<Board> = None;
+    let _ = crate::terminal::hide_cursor(w);
+
+    let result = solve_observed(header, givens, limits, stats, &mut |frame| {
+        let board = frame.board();
+
+        // After a suspension, the previous frame may not be where it was anymore.
+        if crate::terminal::resumed() {
+            previous = None;
+        }
+
+        match &previous {
+            Some(previous) => {
+                // Only rewrite the cells that changed, leaving the rest of the frame untouched.
+                let _ = w.set_color(
+                    termcolor::ColorSpec::new()
+                        .set_fg(Some(termcolor::Color::Blue))
+                        .set_intense(true),
+                );
+                for (i, (&old, &new)) in previous.cells().iter().zip(board.cells()).enumerate() {
+                    if old == new {
+                        continue;
+                    }
+                    let up = (size + 1 - i / size) as u16;
+                    let column = ((width + 1) * (i % size + 1)) as u16;
+                    let _ = crate::terminal::cursor_up(w, up);
+                    let _ = crate::terminal::cursor_to_column(w, column);
+                    let _ = write!(w, "{new:<width$}");
+                    let _ = crate::terminal::cursor_down(w, up);
+                    let _ = crate::terminal::cursor_to_column(w, 0);
                 }
+                let _ = w.reset();
+                let _ = w.flush();
             }
-            Err(BacktrackError::Retry) => (),
+            None => {
+                let _ = crate::format::print_solution(
+                    w,
+                    &board,
+                    header,
+                    &crate::args::OutputFormat::Both,
+                    &crate::alphabet::Alphabet::Digits,
+                );
+            }
+        }
+
+        previous = Some(board);
+        std::thread::sleep(interval);
+    });
+
+    if result.is_ok() && previous.is_some() {
+        let _ = crate::terminal::cursor_up(w, size as u16 + 2);
+        let _ = crate::terminal::clear_below(w);
+    }
+    let _ = crate::terminal::show_cursor(w);
+
+    result
+}
+
+/// A branch taken by [`Session::branch`].
+pub struct Branch {
+    /// The column of the cell that has been fixed.
+    pub x: usize,
+    /// The row of the cell that has been fixed.
+    pub y: usize,
+    /// The value the cell has been fixed to.
+    pub value: u8,
+    /// The result of the propagation of that value.
+    pub result: Result<(), SolutionError>,
+}
+
+/// A board edited one deduction at a time, used to inspect the reasoning of the solver.
+///
+/// Every edit can be undone.
+pub struct Session<'a> {
+    /// The header being solved.
+    header: &'a Header,
+    /// The current state of the board.
+    set: BoardSet,
+    /// The states of the board before every edit, the last one being the most recent.
+    history: Vec<Mark>,
+}
+
+impl<'a> Session<'a> {
+    /// Creates a new [`Session`] over the board of `header`, once the clues have been accounted
+    /// for.
+    ///
+    /// When the clues contradict each other, some cell of the board has no candidate left.
+    pub fn new(header: &'a Header) -> Self {
+        let mut set = BoardSet::new(header.size());
+        let _ = set.account_for_header(header, &mut Vec::new());
+
+        Self {
+            header,
+            set,
+            history: Vec::new(),
+        }
+    }
+
+    /// Returns the size of the board.
+    pub fn size(&self) -> usize {
+        self.set.size
+    }
+
+    /// Returns the values that are still allowed for the cell at `(x, y)`, in increasing order.
+    pub fn candidates(&self, x: usize, y: usize) -> Candidates {
+        assert!(x < self.set.size && y < self.set.size);
+        self.set.cell(x, y).values()
+    }
+
+    /// Returns the current board. Cells that are not fixed are set to `0`.
+    pub fn board(&self) -> Board {
+        self.set.create_board()
+    }
+
+    /// Returns whether every cell is fixed, and the resulting board satisfies the header.
+    pub fn is_solved(&self) -> bool {
+        let board = self.board();
+        !board.contains(&0) && satisfies_header(&board, self.header)
+    }
+
+    /// Fixes the cell at `(x, y)` to `value`, without removing it from the other cells.
+    ///
+    /// If the cell does not accept `value`, the board is left untouched and an error is returned.
+    pub fn assign(&mut self, x: usize, y: usize, value: u8) -> Result<(), SolutionError> {
+        assert!(x < self.set.size && y < self.set.size);
+
+        let before = self.set.mark();
+        self.set.set(x, y, value)?;
+        self.history.push(before);
+
+        Ok(())
+    }
+
+    /// Removes the value of every fixed cell from the other cells of its row and column, and
+    /// applies the clues again to the lines of the fixed cells, until no more cell gets fixed.
+    ///
+    /// When a cell has no candidate left, an error is returned. The board is kept as it is, so
+    /// that the contradiction can be inspected.
+    pub fn propagate(&mut self) -> Result<(), SolutionError> {
+        self.history.push(self.set.mark());
+
+        let size = self.set.size;
+        let mut buf: Vec<(usize, usize)> = (0..size * size)
+            .filter(|&i| self.set.cells[i].count() == 1)
+            .map(|i| (i % size, i / size))
+            .collect();
+
+        Ok(self.set.propagate(self.header, &mut buf)?)
+    }
+
+    /// Returns the cell the solver would branch on next, as `(x, y)`.
+    ///
+    /// Like the solver, this is the cell with the fewest candidates, the first one in row-major
+    /// order winning ties.
+    pub fn branch_cell(&self) -> Option<(usize, usize)> {
+        let size = self.set.size;
+
+        self.set.branch_index().map(|i| (i % size, i / size))
+    }
+
+    /// Takes the branch the solver would take next: fixes the cell returned by
+    /// [`Session::branch_cell`] to its first candidate, and propagates that choice.
+    ///
+    /// Both edits are undone at once. `None` is returned when every cell is fixed.
+    pub fn branch(&mut self) -> Option<Branch> {
+        let (x, y) = self.branch_cell()?;
+        let value = self.candidates(x, y).next()?;
+
+        self.assign(x, y, value).ok()?;
+        let result = self.propagate();
+        // Undoing the branch restores the board as it was before the assignment.
+        self.history.pop();
+
+        Some(Branch {
+            x,
+            y,
+            value,
+            result,
+        })
+    }
+
+    /// Restores the board as it was before the last edit.
+    ///
+    /// `false` is returned when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(mark) => {
+                self.set.rewind(mark);
+                true
+            }
+            None => false,
         }
     }
 }
+
+/// Why the value of a [`Hint`] is forced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The value is the only one missing from the row and the column of the cell.
+    Lines,
+    /// Once the values of the row and the column of the cell are removed, the clues found at
+    /// those indices in the header leave no other value.
+    Clues(Vec<usize>),
+    /// The value only follows from removing the fixed values from their rows and columns and
+    /// applying the clues again across the whole board, like the solver does before it starts
+    /// guessing.
+    Propagation,
+}
+
+/// A cell whose value can be deduced without guessing, as returned by [`hint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The column of the cell.
+    pub x: usize,
+    /// The row of the cell.
+    pub y: usize,
+    /// The value forced on the cell.
+    pub value: u8,
+    /// Why that value is forced.
+    pub reason: Reason,
+}
+
+/// Returns whether the clue `clue` allows `value` on the cell found `distance` cells away from it.
+///
+/// This is the first rule applied by [`BoardSet::account_for_header`].
+fn clue_allows(clue: u8, distance: usize, size: usize, value: u8) -> bool {
+    let (clue, value) = (clue as usize, value as usize);
+
+    match clue {
+        0 => true,
+        1 => distance != 0 || value == size,
+        _ if clue == size => value == distance + 1,
+        _ => value < size - clue + 2 + distance,
+    }
+}
+
+/// Finds a cell of `board` whose value can be deduced from `header` and from the cells that are
+/// already filled, without guessing. Cells whose value is `0` are empty.
+///
+/// Deductions that only involve the row, the column and the clues of a single cell are preferred,
+/// and the smallest set of clues that forces the value is reported. `None` is returned when the
+/// board is complete, or when no cell can be deduced without guessing.
+///
+/// When the filled cells contradict the header or each other, [`SolutionError::NoSolution`] is
+/// returned.
+pub fn hint(header: &Header, board: &Board) -> Result<Option<Hint>, SolutionError> {
+    let size = header.size();
+    assert_eq!(board.size(), size);
+
+    let mut set = BoardSet::new(size);
+    let mut buf = Vec::new();
+    set.account_for_header(header, &mut buf)?;
+    set.account_for_givens(board, &mut buf)?;
+    set.propagate(header, &mut buf)?;
+
+    for (x, y) in (0..size * size).map(|i| (i % size, i / size)) {
+        if board.get(x, y) != 0 {
+            continue;
+        }
+
+        // The clues of the cell, along with their distance to it.
+        let clues = [
+            (x, y),
+            (size + x, size - 1 - y),
+            (2 * size + y, x),
+            (3 * size + y, size - 1 - x),
+        ];
+        let remaining = |mask: usize| {
+            (1..=size as u8).filter(move |&value| {
+                !board.row(y).contains(&value)
+                    && !board.col(x).any(|v| v == value)
+                    && clues.iter().enumerate().all(|(i, &(clue, distance))| {
+                        mask & (1 << i) == 0 || clue_allows(header[clue], distance, size, value)
+                    })
+            })
+        };
+
+        // Subsets of the clues, the smallest first.
+        let mut masks: Vec<usize> = (0..16).collect();
+        masks.sort_by_key(|mask| mask.count_ones());
+
+        for mask in masks {
+            let mut values = remaining(mask);
+            if let (Some(value), None) = (values.next(), values.next()) {
+                let reason = match mask {
+                    0 => Reason::Lines,
+                    _ => Reason::Clues(
+                        (0..4)
+                            .filter(|i| mask & (1 << i) != 0)
+                            .map(|i| clues[i].0)
+                            .collect(),
+                    ),
+                };
+
+                return Ok(Some(Hint {
+                    x,
+                    y,
+                    value,
+                    reason,
+                }));
+            }
+        }
+    }
+
+    let fixed = set.create_board();
+    Ok((0..size * size)
+        .find(|&i| board[i] == 0 && fixed[i] != 0)
+        .map(|i| Hint {
+            x: i % size,
+            y: i / size,
+            value: fixed[i],
+            reason: Reason::Propagation,
+        }))
+}
@@ -0,0 +1,102 @@
+//! Draws puzzles as SVG images.
+
+use std::io::{self, Write};
+
+use crate::board::{Board, Header};
+
+/// The side of a cell, in user units.
+const CELL: usize = 40;
+
+/// Writes an SVG image of the header surrounding an empty grid.
+///
+/// Clues of `0` are left out.
+pub fn write_puzzle(w: &mut dyn Write, header: &Header) -> io::Result<()> {
+    write_board(w, header, None)
+}
+
+/// Writes an SVG image of the header surrounding a grid, filled with `solution` when provided.
+///
+/// Clues of `0` are left out.
+pub fn write_board(w: &mut dyn Write, header: &Header, solution: Option<&Board>) -> io::Result<()> {
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    write_element(w, header, solution)
+}
+
+/// Writes the `<svg>` element drawn by [`write_board`], without the XML declaration, such that
+/// it can be embedded in an HTML page.
+pub fn write_element(
+    w: &mut dyn Write,
+    header: &Header,
+    solution: Option<&Board>,
+) -> io::Result<()> {
+    let size = header.size();
+    // One cell of margin is left around the grid for the clues.
+    let side = (size + 2) * CELL;
+    let grid = size * CELL;
+
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{side}" height="{side}" viewBox="0 0 {side} {side}">"#
+    )?;
+    writeln!(w, r#"<rect width="{side}" height="{side}" fill="white"/>"#)?;
+
+    writeln!(w, r#"<g stroke="black" stroke-width="1">"#)?;
+    for i in 1..size {
+        let at = (i + 1) * CELL;
+        writeln!(
+            w,
+            r#"<line x1="{at}" y1="{CELL}" x2="{at}" y2="{end}"/>"#,
+            end = CELL + grid,
+        )?;
+        writeln!(
+            w,
+            r#"<line x1="{CELL}" y1="{at}" x2="{end}" y2="{at}"/>"#,
+            end = CELL + grid,
+        )?;
+    }
+    writeln!(w, "</g>")?;
+    writeln!(
+        w,
+        r#"<rect x="{CELL}" y="{CELL}" width="{grid}" height="{grid}" fill="none" stroke="black" stroke-width="3"/>"#
+    )?;
+
+    writeln!(
+        w,
+        r#"<g font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+        CELL / 2,
+    )?;
+    for (index, &clue) in header.iter().enumerate() {
+        if clue == 0 {
+            continue;
+        }
+
+        let i = index % size + 1;
+        let (x, y) = match index / size {
+            0 => (i, 0),
+            1 => (i, size + 1),
+            2 => (0, i),
+            _ => (size + 1, i),
+        };
+        writeln!(
+            w,
+            r#"<text x="{}" y="{}">{clue}</text>"#,
+            x * CELL + CELL / 2,
+            y * CELL + CELL / 2,
+        )?;
+    }
+    if let Some(solution) = solution {
+        for (y, row) in solution.rows().enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                writeln!(
+                    w,
+                    r#"<text x="{}" y="{}" fill="dimgray">{value}</text>"#,
+                    (x + 1) * CELL + CELL / 2,
+                    (y + 1) * CELL + CELL / 2,
+                )?;
+            }
+        }
+    }
+    writeln!(w, "</g>")?;
+
+    writeln!(w, "</svg>")
+}
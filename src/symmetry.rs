@@ -0,0 +1,161 @@
+//! Symmetries of the square, used to recognize puzzles that are rotations or reflections of each
+//! other.
+
+use alloc::boxed::Box;
+
+/// One of the eight symmetries of the square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Symmetry {
+    /// Leaves the board unchanged.
+    #[cfg_attr(feature = "cli", value(skip))]
+    Identity,
+    /// Rotates the board by 90 degrees, clockwise.
+    Rotate90,
+    /// Rotates the board by 180 degrees.
+    Rotate180,
+    /// Rotates the board by 270 degrees, clockwise.
+    Rotate270,
+    /// Mirrors the board horizontally (the left column becomes the right one).
+    FlipH,
+    /// Mirrors the board vertically (the top row becomes the bottom one).
+    FlipV,
+    /// Mirrors the board along its main diagonal.
+    Transpose,
+    /// Mirrors the board along its anti-diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// Every symmetry of the square.
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipH,
+        Self::FlipV,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+
+    /// Returns the position of the cell `(x, y)` of a board of size `size` once this symmetry has
+    /// been applied to the board.
+    pub fn apply(self, x: usize, y: usize, size: usize) -> (usize, usize) {
+        let last = size - 1;
+
+        match self {
+            Self::Identity => (x, y),
+            Self::Rotate90 => (last - y, x),
+            Self::Rotate180 => (last - x, last - y),
+            Self::Rotate270 => (y, last - x),
+            Self::FlipH => (last - x, y),
+            Self::FlipV => (x, last - y),
+            Self::Transpose => (y, x),
+            Self::AntiTranspose => (last - y, last - x),
+        }
+    }
+}
+
+/// Returns the first two cells seen by the clue at `index` in a header of size `size`, starting
+/// with the closest one.
+///
+/// Those two cells are enough to know where the clue is and in which direction it looks.
+fn clue_cells(index: usize, size: usize) -> [(usize, usize); 2] {
+    let i = index % size;
+    let last = size - 1;
+
+    match index / size {
+        // From the top.
+        0 => [(i, 0), (i, 1)],
+        // From the bottom.
+        1 => [(i, last), (i, last - 1)],
+        // From the left.
+        2 => [(0, i), (1, i)],
+        // From the right.
+        _ => [(last, i), (last - 1, i)],
+    }
+}
+
+/// Applies `symmetry` to a board of size `size`.
+pub fn transform_board(board: &[u8], size: usize, symmetry: Symmetry) -> Box<[u8]> {
+    let mut result: Box<[u8]> = core::iter::repeat_n(0, board.len()).collect();
+
+    for y in 0..size {
+        for x in 0..size {
+            let (tx, ty) = symmetry.apply(x, y, size);
+            result[tx + ty * size] = board[x + y * size];
+        }
+    }
+
+    result
+}
+
+/// Applies `symmetry` to a header of size `size`, moving each clue to the place it occupies once
+/// the board has been transformed.
+pub fn transform_header(header: &[u8], size: usize, symmetry: Symmetry) -> Box<[u8]> {
+    // With a single cell, every clue is the same.
+    if size < 2 {
+        return header.into();
+    }
+
+    let mut result: Box<[u8]> = core::iter::repeat_n(0, header.len()).collect();
+
+    for (index, &clue) in header.iter().enumerate() {
+        let [a, b] = clue_cells(index, size);
+        let moved = [
+            symmetry.apply(a.0, a.1, size),
+            symmetry.apply(b.0, b.1, size),
+        ];
+
+        // Exactly one clue looks at the transformed cells in that order.
+        let target = (0..header.len())
+            .find(|&target| clue_cells(target, size) == moved)
+            .unwrap();
+        result[target] = clue;
+    }
+
+    result
+}
+
+/// Returns the canonical form of a puzzle: the smallest header (in lexicographical order) among
+/// its images through every symmetry of the square. When a board is provided, it is transformed
+/// along with the header, and it is used to break ties.
+///
+/// Two puzzles have the same canonical form if, and only if, one of them is a rotation or a
+/// reflection of the other.
+pub fn canonical_form(
+    header: &[u8],
+    board: Option<&[u8]>,
+    size: usize,
+) -> (Box<[u8]>, Option<Box<[u8]>>) {
+    Symmetry::ALL
+        .iter()
+        .map(|&symmetry| {
+            (
+                transform_header(header, size, symmetry),
+                board.map(|board| transform_board(board, size, symmetry)),
+            )
+        })
+        .min()
+        .unwrap()
+}
+
+/// Computes a hash of the canonical form of a puzzle.
+///
+/// This uses the 64-bit FNV-1a algorithm over the size of the board followed by the canonical
+/// header and, if provided, the canonical board. The hash is therefore stable across platforms and
+/// versions of the program.
+pub fn canonical_hash(header: &[u8], board: Option<&[u8]>, size: usize) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let (header, board) = canonical_form(header, board, size);
+
+    core::iter::once(size as u8)
+        .chain(header.iter().copied())
+        .chain(board.iter().flat_map(|board| board.iter().copied()))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+}
@@ -0,0 +1,140 @@
+//! Keeps the terminal in a usable state when the program is suspended (using `Ctrl+Z`) and
+//! resumed, and moves its cursor around.
+//!
+//! The cursor is always moved through `crossterm`, which falls back to the console API on the
+//! Windows terminals that do not understand escape sequences.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossterm::{cursor, terminal, QueueableCommand};
+
+/// The escape sequence hiding the cursor.
+#[cfg(unix)]
+const HIDE_CURSOR: &[u8] = b"\x1B[?25l";
+/// The escape sequence showing the cursor.
+#[cfg(unix)]
+const SHOW_CURSOR: &[u8] = b"\x1B[?25h";
+
+/// Whether the cursor is currently hidden.
+static CURSOR_HIDDEN: AtomicBool = AtomicBool::new(false);
+/// Whether the program has been resumed since the last call to [`resumed`].
+static RESUMED: AtomicBool = AtomicBool::new(false);
+
+/// Initializes the SIGTSTP and SIGCONT handlers.
+///
+/// When the program is suspended, the cursor is shown again before the process actually stops.
+/// When it is resumed, the cursor is hidden again and [`resumed`] starts returning `true`.
+#[cfg(unix)]
+pub fn initialize() {
+    use signal_hook::consts::{SIGCONT, SIGTSTP};
+
+    let mut signals = signal_hook::iterator::Signals::new([SIGTSTP, SIGCONT]).unwrap();
+
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            let hidden = CURSOR_HIDDEN.load(Ordering::Relaxed);
+
+            if signal == SIGTSTP {
+                if hidden {
+                    let _ = write_tty(SHOW_CURSOR);
+                }
+                // Actually stop the process.
+                let _ = signal_hook::low_level::emulate_default_handler(SIGTSTP);
+            } else {
+                if hidden {
+                    let _ = write_tty(HIDE_CURSOR);
+                }
+                RESUMED.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
+/// Enables the processing of escape sequences by the console.
+///
+/// The SIGTSTP and SIGCONT signals do not exist on this platform.
+#[cfg(windows)]
+pub fn initialize() {
+    // Consoles that do not support escape sequences are driven through the console API instead.
+    let _ = crossterm::ansi_support::supports_ansi();
+}
+
+/// Initializes the SIGTSTP and SIGCONT handlers.
+///
+/// Those signals do not exist on this platform.
+#[cfg(not(any(unix, windows)))]
+pub fn initialize() {}
+
+/// Writes `bytes` directly to the controlling terminal of the process.
+#[cfg(unix)]
+fn write_tty(bytes: &[u8]) -> io::Result<()> {
+    let mut tty = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
+    tty.write_all(bytes)?;
+    tty.flush()
+}
+
+/// Returns whether the program has been resumed after having been suspended since the last call
+/// to this function.
+///
+/// Whatever was drawn to the terminal before the program was suspended may have been scrolled
+/// away by the shell, and must therefore be drawn again from scratch.
+pub fn resumed() -> bool {
+    RESUMED.swap(false, Ordering::Relaxed)
+}
+
+/// Hides the cursor of the terminal `w` is writing to.
+pub fn hide_cursor(w: &mut dyn Write) -> io::Result<()> {
+    CURSOR_HIDDEN.store(true, Ordering::Relaxed);
+    w.queue(cursor::Hide)?;
+    w.flush()
+}
+
+/// Shows the cursor of the terminal `w` is writing to.
+pub fn show_cursor(w: &mut dyn Write) -> io::Result<()> {
+    CURSOR_HIDDEN.store(false, Ordering::Relaxed);
+    w.queue(cursor::Show)?;
+    w.flush()
+}
+
+/// Moves the cursor of the terminal `w` is writing to `lines` lines up, keeping its column.
+pub fn cursor_up(w: &mut dyn Write, lines: u16) -> io::Result<()> {
+    w.queue(cursor::MoveUp(lines))?;
+    Ok(())
+}
+
+/// Moves the cursor of the terminal `w` is writing to `lines` lines down, keeping its column.
+pub fn cursor_down(w: &mut dyn Write, lines: u16) -> io::Result<()> {
+    w.queue(cursor::MoveDown(lines))?;
+    Ok(())
+}
+
+/// Moves the cursor of the terminal `w` is writing to the column `column` of its line, starting
+/// at 0.
+pub fn cursor_to_column(w: &mut dyn Write, column: u16) -> io::Result<()> {
+    w.queue(cursor::MoveToColumn(column))?;
+    Ok(())
+}
+
+/// Clears everything from the cursor of the terminal `w` is writing to until the end of the
+/// screen.
+pub fn clear_below(w: &mut dyn Write) -> io::Result<()> {
+    w.queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+    Ok(())
+}
+
+/// Clears the line the cursor of the terminal `w` is writing to is on, and moves the cursor back
+/// to its start.
+pub fn clear_line(w: &mut dyn Write) -> io::Result<()> {
+    w.queue(cursor::MoveToColumn(0))?;
+    w.queue(terminal::Clear(terminal::ClearType::CurrentLine))?;
+    Ok(())
+}
+
+/// Clears the whole screen of the terminal `w` is writing to, and moves the cursor to its
+/// top-left corner.
+pub fn clear_screen(w: &mut dyn Write) -> io::Result<()> {
+    w.queue(terminal::Clear(terminal::ClearType::All))?;
+    w.queue(cursor::MoveTo(0, 0))?;
+    Ok(())
+}
@@ -0,0 +1,329 @@
+//! Writes solver traces.
+//!
+//! A trace is written using the JSON Lines format: every line is a JSON object describing an event
+//! of the search. The `type` field of the object determines which event it describes.
+//!
+//! [`DeductionWriter`] rather logs the deductions made by the propagation of the constraints,
+//! either as plain text or as JSON Lines.
+//!
+//! The formats are documented in the README.
+
+use std::io::{self, Write};
+
+use crate::board::{Board, Header};
+use crate::explain::Clue;
+use crate::solve::{self, Cause, Deduction, SolveObserver};
+
+/// The version of the trace format. It is incremented every time the format changes.
+pub const VERSION: u32 = 1;
+
+/// Writes a list of values as a JSON array.
+fn write_array<T: std::fmt::Display>(
+    w: &mut dyn Write,
+    values: impl IntoIterator<Item = T>,
+) -> io::Result<()> {
+    w.write_all(b"[")?;
+    for (i, value) in values.into_iter().enumerate() {
+        if i != 0 {
+            w.write_all(b",")?;
+        }
+        write!(w, "{value}")?;
+    }
+    w.write_all(b"]")
+}
+
+/// Returns the name of the result of a search, as written in traces.
+fn result_name(result: &Result<Board, solve::SolutionError>) -> &'static str {
+    match result {
+        Ok(_) => "solved",
+        Err(solve::SolutionError::NoSolution) => "no-solution",
+        Err(solve::SolutionError::Interrupted) => "interrupted",
+        Err(solve::SolutionError::MemoryLimit) => "memory-limit",
+        Err(solve::SolutionError::NodeLimit) => "node-limit",
+        Err(solve::SolutionError::Timeout) => "timeout",
+    }
+}
+
+/// Writes the events of a search to a [`Write`] implementation.
+pub struct TraceWriter<W> {
+    w: W,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Creates a new [`TraceWriter`] writing to `w`.
+    pub fn new(w: W) -> Self {
+        Self { w }
+    }
+
+    /// Returns the inner [`Write`] implementation.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    /// Writes the `start` event, describing the header being solved.
+    pub fn start(&mut self, header: &Header) -> io::Result<()> {
+        write!(
+            self.w,
+            "{{\"type\":\"start\",\"version\":{VERSION},\"size\":{},\"header\":",
+            header.size(),
+        )?;
+        write_array(&mut self.w, header.iter())?;
+        self.w.write_all(b"}\n")
+    }
+
+    /// Writes a `frame` event, describing the state of the search between two steps.
+    pub fn frame(&mut self, frame: &solve::Frame, size: usize) -> io::Result<()> {
+        write!(
+            self.w,
+            "{{\"type\":\"frame\",\"step\":{},\"depth\":{},\"branch\":",
+            frame.step, frame.depth
+        )?;
+        match frame.branch {
+            Some((x, y, value)) => write!(self.w, "{{\"x\":{x},\"y\":{y},\"value\":{value}}}")?,
+            None => self.w.write_all(b"null")?,
+        }
+
+        self.w.write_all(b",\"candidates\":[")?;
+        for y in 0..size {
+            for x in 0..size {
+                if x != 0 || y != 0 {
+                    self.w.write_all(b",")?;
+                }
+
+                write_array(&mut self.w, frame.candidates(x, y))?;
+            }
+        }
+        self.w.write_all(b"]}\n")
+    }
+
+    /// Writes the `end` event, describing the result of the search.
+    pub fn end(&mut self, result: &Result<Board, solve::SolutionError>) -> io::Result<()> {
+        let name = result_name(result);
+        write!(self.w, "{{\"type\":\"end\",\"result\":\"{name}\"")?;
+        if let Ok(solution) = result {
+            self.w.write_all(b",\"solution\":")?;
+            write_array(&mut self.w, solution.iter())?;
+        }
+        self.w.write_all(b"}\n")
+    }
+}
+
+/// The format of the log written by a [`DeductionWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeductionFormat {
+    /// One sentence per line.
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+/// Writes the deductions and the guesses of a search to a [`Write`] implementation.
+///
+/// The writer observes a search started by [`solve::solve_traced`]. Since the search can't be
+/// aborted by its observer, the first error is kept, and returned by [`DeductionWriter::end`].
+pub struct DeductionWriter<'a, W> {
+    w: W,
+    format: DeductionFormat,
+    header: &'a Header,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: Write> DeductionWriter<'a, W> {
+    /// Creates a new [`DeductionWriter`] logging the search of `header` to `w`.
+    pub fn new(w: W, format: DeductionFormat, header: &'a Header) -> Self {
+        Self {
+            w,
+            format,
+            header,
+            error: None,
+        }
+    }
+
+    /// Returns the inner [`Write`] implementation.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    /// Writes the header being solved.
+    pub fn start(&mut self) -> io::Result<()> {
+        let header = self.header;
+        match self.format {
+            DeductionFormat::Text => {
+                self.w.write_all(b"solving")?;
+                for clue in header.iter() {
+                    write!(self.w, " {clue}")?;
+                }
+                self.w.write_all(b"\n")
+            }
+            DeductionFormat::Json => {
+                write!(
+                    self.w,
+                    "{{\"type\":\"start\",\"version\":{VERSION},\"size\":{},\"header\":",
+                    header.size(),
+                )?;
+                write_array(&mut self.w, header.iter())?;
+                self.w.write_all(b"}\n")
+            }
+        }
+    }
+
+    /// Writes the result of the search, or returns the first error that occurred while writing
+    /// the log.
+    pub fn end(&mut self, result: &Result<Board, solve::SolutionError>) -> io::Result<()> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+
+        let name = result_name(result);
+        match self.format {
+            DeductionFormat::Text => writeln!(self.w, "result: {}", name.replace('-', " ")),
+            DeductionFormat::Json => {
+                writeln!(self.w, "{{\"type\":\"end\",\"result\":\"{name}\"}}")
+            }
+        }
+    }
+
+    /// Writes `cause` as plain text.
+    fn write_cause(&mut self, cause: Cause) -> io::Result<()> {
+        match cause {
+            Cause::Clue(index) => write!(self.w, "{}", Clue::new(self.header, index)),
+            Cause::CluePair(first, last) => write!(
+                self.w,
+                "{} and {}",
+                Clue::new(self.header, first),
+                Clue::new(self.header, last),
+            ),
+            Cause::Given => self.w.write_all(b"the givens"),
+            Cause::Fixed { x, y } => write!(self.w, "r{}c{}", y + 1, x + 1),
+            Cause::Guess => self.w.write_all(b"the guess"),
+        }
+    }
+
+    /// Writes `cause` as a JSON object.
+    fn write_cause_json(&mut self, cause: Cause) -> io::Result<()> {
+        match cause {
+            Cause::Clue(index) => write!(self.w, "{{\"type\":\"clue\",\"clue\":{index}}}"),
+            Cause::CluePair(first, last) => write!(
+                self.w,
+                "{{\"type\":\"clue-pair\",\"clues\":[{first},{last}]}}"
+            ),
+            Cause::Given => self.w.write_all(b"{\"type\":\"given\"}"),
+            Cause::Fixed { x, y } => write!(self.w, "{{\"type\":\"cell\",\"x\":{x},\"y\":{y}}}"),
+            Cause::Guess => self.w.write_all(b"{\"type\":\"guess\"}"),
+        }
+    }
+
+    fn deduction(&mut self, deduction: &Deduction) -> io::Result<()> {
+        let Deduction { x, y, .. } = *deduction;
+        match self.format {
+            DeductionFormat::Text => {
+                self.write_cause(deduction.cause)?;
+                match deduction.cause {
+                    Cause::CluePair(..) | Cause::Given => self.w.write_all(b" rule out ")?,
+                    _ => self.w.write_all(b" rules out ")?,
+                }
+                write_list(&mut self.w, deduction.removed)?;
+                write!(self.w, " in r{}c{}, leaving ", y + 1, x + 1)?;
+                write_list(&mut self.w, deduction.left)?;
+                self.w.write_all(b"\n")
+            }
+            DeductionFormat::Json => {
+                self.w.write_all(b"{\"type\":\"deduction\",\"cause\":")?;
+                self.write_cause_json(deduction.cause)?;
+                write!(self.w, ",\"x\":{x},\"y\":{y},\"removed\":")?;
+                write_array(&mut self.w, deduction.removed)?;
+                self.w.write_all(b",\"left\":")?;
+                write_array(&mut self.w, deduction.left)?;
+                self.w.write_all(b"}\n")
+            }
+        }
+    }
+
+    fn contradiction(&mut self, cause: Cause) -> io::Result<()> {
+        match self.format {
+            DeductionFormat::Text => {
+                self.w.write_all(b"contradiction found by ")?;
+                self.write_cause(cause)?;
+                self.w.write_all(b"\n")
+            }
+            DeductionFormat::Json => {
+                self.w
+                    .write_all(b"{\"type\":\"contradiction\",\"cause\":")?;
+                self.write_cause_json(cause)?;
+                self.w.write_all(b"}\n")
+            }
+        }
+    }
+
+    fn guess(&mut self, x: usize, y: usize, value: u8) -> io::Result<()> {
+        match self.format {
+            DeductionFormat::Text => writeln!(self.w, "guess r{}c{} = {value}", y + 1, x + 1),
+            DeductionFormat::Json => writeln!(
+                self.w,
+                "{{\"type\":\"guess\",\"x\":{x},\"y\":{y},\"value\":{value}}}"
+            ),
+        }
+    }
+
+    fn backtrack(&mut self, depth: usize) -> io::Result<()> {
+        match self.format {
+            DeductionFormat::Text => writeln!(self.w, "backtrack to depth {depth}"),
+            DeductionFormat::Json => {
+                writeln!(self.w, "{{\"type\":\"backtrack\",\"depth\":{depth}}}")
+            }
+        }
+    }
+
+    fn solution(&mut self, board: &Board) -> io::Result<()> {
+        match self.format {
+            DeductionFormat::Text => self.w.write_all(b"solution found\n"),
+            DeductionFormat::Json => {
+                self.w.write_all(b"{\"type\":\"solution\",\"board\":")?;
+                write_array(&mut self.w, board.iter())?;
+                self.w.write_all(b"}\n")
+            }
+        }
+    }
+
+    /// Keeps the first error that occurs.
+    fn record(&mut self, f: impl FnOnce(&mut Self) -> io::Result<()>) {
+        if self.error.is_none() {
+            if let Err(err) = f(self) {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+/// Writes a list of values separated by commas.
+fn write_list(w: &mut dyn Write, values: &[u8]) -> io::Result<()> {
+    for (i, value) in values.iter().enumerate() {
+        if i != 0 {
+            w.write_all(b", ")?;
+        }
+        write!(w, "{value}")?;
+    }
+    Ok(())
+}
+
+impl<W: Write> SolveObserver for DeductionWriter<'_, W> {
+    fn on_assign(&mut self, x: usize, y: usize, value: u8) {
+        self.record(|w| w.guess(x, y, value));
+    }
+
+    fn on_backtrack(&mut self, depth: usize) {
+        self.record(|w| w.backtrack(depth));
+    }
+
+    fn on_solution(&mut self, board: &Board) {
+        self.record(|w| w.solution(board));
+    }
+
+    fn on_deduction(&mut self, deduction: &Deduction) {
+        self.record(|w| w.deduction(deduction));
+    }
+
+    fn on_contradiction(&mut self, cause: Cause) {
+        self.record(|w| w.contradiction(cause));
+    }
+}
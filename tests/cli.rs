@@ -0,0 +1,30 @@
+//! Runs the command-line interface end to end.
+
+use std::process::Command;
+
+/// Runs the program with `args`, and returns its standard output.
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_skyscrapper-cli"))
+        .args(args)
+        .output()
+        .expect("failed to run the program");
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).expect("the output is not UTF-8")
+}
+
+#[test]
+fn verify_does_not_mark_an_exact_count_as_a_lower_bound() {
+    // The puzzle of this seed has a single solution.
+    let output = run(&["verify", "--seed", "1", "--size", "5", "--limit", "1"]);
+    assert!(output.contains("solutions:       1\n"), "{output}");
+
+    let output = run(&["verify", "--seed", "1", "--size", "5"]);
+    assert!(output.contains("solutions:       1\n"), "{output}");
+}
+
+#[test]
+fn verify_marks_a_count_past_the_limit_as_a_lower_bound() {
+    // The puzzle of this seed has more than one solution.
+    let output = run(&["verify", "--seed", "2", "--size", "4", "--limit", "1"]);
+    assert!(output.contains("solutions:       1+\n"), "{output}");
+}